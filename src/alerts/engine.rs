@@ -0,0 +1,112 @@
+//! Incremental evaluation of alert rules.
+//!
+//! Stored events update the windows of the rules they match and
+//! re-evaluate just those rules. A background task re-evaluates every rule
+//! periodically, so windows drain and `absence` rules fire without events.
+
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use super::notifier::Notifier;
+use super::window::RuleWindow;
+use crate::domain::{AlertState, AlertStatus, Event, MetricsPtr};
+
+/// Locks `window`, recovering it if a panic poisoned the lock.
+fn lock(window: &Mutex<RuleWindow>) -> MutexGuard<'_, RuleWindow> {
+    window.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Every rule's window, plus where state changes are reported.
+pub struct AlertEngine {
+    windows: Vec<Mutex<RuleWindow>>,
+    metrics: MetricsPtr,
+    notifier: Notifier,
+}
+
+impl AlertEngine {
+    // ---
+
+    pub fn new(windows: Vec<RuleWindow>, metrics: MetricsPtr, notifier: Notifier) -> Self {
+        // ---
+        for window in &windows {
+            metrics.record_alert(&window.status());
+        }
+        Self {
+            windows: windows.into_iter().map(Mutex::new).collect(),
+            metrics,
+            notifier,
+        }
+    }
+
+    /// Whether any rule matches `event`.
+    pub fn wants(&self, event: &Event) -> bool {
+        // ---
+        self.windows
+            .iter()
+            .any(|window| lock(window).rule.matches(event))
+    }
+
+    /// Counts `event` for every rule it matches and re-evaluates those rules.
+    pub fn observe(&self, event: &Event, now: DateTime<Utc>) {
+        // ---
+        for window in &self.windows {
+            let mut window = lock(window);
+            if window.rule.matches(event) {
+                window.record(now);
+                self.evaluate_window(&mut window, now);
+            }
+        }
+    }
+
+    /// Re-evaluates every rule at `now`.
+    pub fn evaluate(&self, now: DateTime<Utc>) {
+        // ---
+        for window in &self.windows {
+            self.evaluate_window(&mut lock(window), now);
+        }
+    }
+
+    /// Current state of every rule, in configuration order.
+    pub fn statuses(&self) -> Vec<AlertStatus> {
+        // ---
+        self.windows
+            .iter()
+            .map(|window| lock(window).status())
+            .collect()
+    }
+
+    fn evaluate_window(&self, window: &mut RuleWindow, now: DateTime<Utc>) {
+        // ---
+        let changed = window.evaluate(now);
+        let status = window.status();
+        self.metrics.record_alert(&status);
+
+        let Some(state) = changed else {
+            return;
+        };
+        match state {
+            AlertState::Firing => tracing::warn!(
+                rule = %status.rule,
+                value = status.value,
+                threshold = status.threshold,
+                "🚨 Alert firing"
+            ),
+            _ => tracing::info!(rule = %status.rule, state = state.as_str(), "Alert state changed"),
+        }
+        if matches!(state, AlertState::Firing | AlertState::Resolved) {
+            self.notifier.notify(&window.rule, status);
+        }
+    }
+
+    /// Re-evaluates every rule each `interval` until the task is aborted.
+    pub async fn run(self: Arc<Self>, interval: Duration) {
+        // ---
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            self.evaluate(Utc::now());
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Cloneable handle to the alerting subsystem.
+
+use anyhow::Result;
+use chrono::Utc;
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use super::engine::AlertEngine;
+use super::notifier::Notifier;
+use super::window::RuleWindow;
+use crate::config::AlertSettings;
+use crate::domain::{AlertRule, AlertStatus, Event, EventObserver, MetricsPtr};
+
+/// Alert rules, their state and the periodic evaluation task.
+#[derive(Clone)]
+pub struct Alerts {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    engine: Arc<AlertEngine>,
+    interval: Duration,
+    evaluator: OnceLock<JoinHandle<()>>,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        if let Some(task) = self.evaluator.get() {
+            task.abort();
+        }
+    }
+}
+
+impl Alerts {
+    // ---
+
+    /// Sets up `rules`; periodic evaluation starts with [`start`](Self::start).
+    pub fn new(
+        rules: Vec<AlertRule>,
+        settings: &AlertSettings,
+        metrics: MetricsPtr,
+    ) -> Result<Self> {
+        // ---
+        let now = Utc::now();
+        let windows = rules
+            .into_iter()
+            .map(|rule| RuleWindow::new(rule, now))
+            .collect();
+        Ok(Self {
+            inner: Arc::new(Inner {
+                engine: Arc::new(AlertEngine::new(windows, metrics, Notifier::new(settings)?)),
+                interval: Duration::from_millis(settings.evaluation_interval_ms),
+                evaluator: OnceLock::new(),
+            }),
+        })
+    }
+
+    /// Starts the periodic evaluation task if it is not running. Must be called within a Tokio runtime.
+    pub fn start(&self) {
+        // ---
+        let engine = self.inner.engine.clone();
+        let interval = self.inner.interval;
+        self.inner
+            .evaluator
+            .get_or_init(|| tokio::spawn(engine.run(interval)));
+    }
+
+    /// Current state of every rule, in configuration order.
+    pub fn statuses(&self) -> Vec<AlertStatus> {
+        self.inner.engine.statuses()
+    }
+}
+
+impl EventObserver for Alerts {
+    fn wants(&self, event: &Event) -> bool {
+        self.inner.engine.wants(event)
+    }
+
+    fn event_stored(&self, event: &Event) {
+        self.inner.engine.observe(event, Utc::now());
+    }
+}
+
+impl fmt::Debug for Alerts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Alerts")
+            .field("rules", &self.statuses().len())
+            .field("interval", &self.inner.interval)
+            .finish_non_exhaustive()
+    }
+}
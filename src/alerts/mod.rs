@@ -0,0 +1,31 @@
+//! Threshold alerting over the stream of stored events.
+//!
+//! Rules from `[alerts]` watch one event type each over a sliding window.
+//! They are evaluated as events are stored and periodically in between;
+//! their state is served at `GET /v1/alerts`, published as gauges and, when a
+//! rule has a webhook, posted to it whenever the rule fires or resolves.
+
+mod engine;
+mod handle;
+mod notifier;
+mod window;
+
+// Public exports
+pub use handle::Alerts;
+
+use crate::config::AlertSettings;
+use crate::domain::{AlertRule, MetricsPtr};
+
+/// Sets up `rules` and starts their periodic evaluation.
+///
+/// Must be called within a Tokio runtime.
+pub fn create_alerts(
+    rules: Vec<AlertRule>,
+    settings: &AlertSettings,
+    metrics: MetricsPtr,
+) -> anyhow::Result<Alerts> {
+    // ---
+    let alerts = Alerts::new(rules, settings, metrics)?;
+    alerts.start();
+    Ok(alerts)
+}
@@ -0,0 +1,97 @@
+//! Forwarding of alert state changes to a rule's webhook.
+//!
+//! Each notification is sent from its own task and retried with backoff,
+//! so a slow endpoint never holds up evaluation. Notifications of one rule
+//! may therefore arrive out of order; receivers can order them by `since`.
+
+use anyhow::{anyhow, Result};
+use reqwest::{header, Client};
+use std::time::Duration;
+
+use crate::config::AlertSettings;
+use crate::domain::{AlertRule, AlertStatus};
+use crate::infrastructure::exponential_backoff;
+use crate::webhooks::sign_webhook;
+
+/// Delay before the first retry of a notification.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Upper bound of the retry delay.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Sends firing/resolved notifications.
+pub struct Notifier {
+    client: Client,
+    max_attempts: u32,
+}
+
+impl Notifier {
+    // ---
+
+    pub fn new(settings: &AlertSettings) -> Result<Self> {
+        // ---
+        Ok(Self {
+            client: Client::builder()
+                .timeout(Duration::from_millis(settings.notify_timeout_ms))
+                .build()?,
+            max_attempts: settings.notify_max_attempts,
+        })
+    }
+
+    /// Posts `status` to the rule's webhook, if it has one, in the background.
+    ///
+    /// Must be called within a Tokio runtime.
+    pub fn notify(&self, rule: &AlertRule, status: AlertStatus) {
+        // ---
+        let Some(url) = rule.webhook_url.clone() else {
+            return;
+        };
+        let secret = rule.webhook_secret.clone();
+        let client = self.client.clone();
+        let max_attempts = self.max_attempts;
+        tokio::spawn(async move {
+            for attempt in 0..max_attempts {
+                match send(&client, &url, secret.as_deref(), &status).await {
+                    Ok(()) => return,
+                    Err(err) => tracing::warn!(
+                        rule = %status.rule,
+                        attempt = attempt + 1,
+                        "Alert notification failed: {:#}",
+                        err
+                    ),
+                }
+                if attempt + 1 < max_attempts {
+                    tokio::time::sleep(exponential_backoff(INITIAL_BACKOFF, MAX_BACKOFF, attempt))
+                        .await;
+                }
+            }
+            tracing::error!(rule = %status.rule, %url, "Giving up on alert notification");
+        });
+    }
+}
+
+/// Posts one notification; any non-`2xx` response is an error.
+async fn send(
+    client: &Client,
+    url: &str,
+    secret: Option<&str>,
+    status: &AlertStatus,
+) -> Result<()> {
+    // ---
+    let body = serde_json::to_vec(status)?;
+    let mut request = client
+        .post(url)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("x-argus-alert", &status.rule);
+    if let Some(secret) = secret {
+        request = request.header("x-argus-signature", sign_webhook(secret, &body));
+    }
+    let response = request.body(body).send().await?;
+
+    let code = response.status();
+    if code.is_success() {
+        Ok(())
+    } else {
+        Err(anyhow!("{} responded {}", url, code))
+    }
+}
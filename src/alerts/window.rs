@@ -0,0 +1,223 @@
+//! Sliding-window state of one alert rule.
+//!
+//! Matching events are counted in one-second buckets, so memory is bounded
+//! by the window length rather than the event rate. Times are arrival
+//! times: an event submitted late with an old `timestamp` still counts as
+//! happening now.
+
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
+
+use crate::domain::{AlertCondition, AlertRule, AlertState, AlertStatus};
+
+/// One rule's window, current value and state.
+pub struct RuleWindow {
+    pub rule: AlertRule,
+
+    /// (second, matching events in that second), oldest first.
+    buckets: VecDeque<(i64, u64)>,
+
+    /// Sum of `buckets`.
+    in_window: u64,
+
+    /// Arrival of the last matching event, or the engine start before any.
+    last_seen: DateTime<Utc>,
+
+    state: AlertState,
+    since: DateTime<Utc>,
+    value: f64,
+}
+
+impl RuleWindow {
+    // ---
+
+    /// An empty window; `absence` rules count their window from `now`.
+    pub fn new(rule: AlertRule, now: DateTime<Utc>) -> Self {
+        // ---
+        Self {
+            rule,
+            buckets: VecDeque::new(),
+            in_window: 0,
+            last_seen: now,
+            state: AlertState::Inactive,
+            since: now,
+            value: 0.0,
+        }
+    }
+
+    /// Counts a matching event arriving at `at`.
+    pub fn record(&mut self, at: DateTime<Utc>) {
+        // ---
+        let second = at.timestamp();
+        match self.buckets.back_mut() {
+            Some((last, count)) if *last == second => *count += 1,
+            _ => self.buckets.push_back((second, 1)),
+        }
+        self.in_window += 1;
+        self.last_seen = self.last_seen.max(at);
+    }
+
+    /// Recomputes the value and state at `now`; returns the new state if it changed.
+    pub fn evaluate(&mut self, now: DateTime<Utc>) -> Option<AlertState> {
+        // ---
+        let window_secs = self.rule.window.as_secs().max(1) as i64;
+        let oldest = now.timestamp() - window_secs;
+        while let Some(&(second, count)) = self.buckets.front() {
+            if second > oldest {
+                break;
+            }
+            self.buckets.pop_front();
+            self.in_window -= count;
+        }
+
+        let (value, breached) = match self.rule.condition {
+            AlertCondition::Count => {
+                let value = self.in_window as f64;
+                (value, value > self.rule.threshold)
+            }
+            AlertCondition::Rate => {
+                let value = self.in_window as f64 / window_secs as f64;
+                (value, value > self.rule.threshold)
+            }
+            AlertCondition::Absence => {
+                let quiet = (now - self.last_seen).num_milliseconds().max(0) as f64 / 1000.0;
+                (quiet, quiet >= window_secs as f64)
+            }
+        };
+        self.value = value;
+
+        let held = (now - self.since).to_std().unwrap_or_default() >= self.rule.for_duration;
+        let next = match (breached, self.state) {
+            (true, AlertState::Firing) => AlertState::Firing,
+            (true, AlertState::Pending) if held => AlertState::Firing,
+            (true, AlertState::Pending) => AlertState::Pending,
+            (true, _) if self.rule.for_duration.is_zero() => AlertState::Firing,
+            (true, _) => AlertState::Pending,
+            (false, AlertState::Firing) => AlertState::Resolved,
+            (false, AlertState::Pending) => AlertState::Inactive,
+            (false, state) => state,
+        };
+        if next == self.state {
+            return None;
+        }
+        self.state = next;
+        self.since = now;
+        Some(next)
+    }
+
+    /// The rule's state as last evaluated.
+    pub fn status(&self) -> AlertStatus {
+        // ---
+        AlertStatus {
+            rule: self.rule.name.clone(),
+            event_type: self.rule.events.event_type.clone(),
+            condition: self.rule.condition,
+            threshold: self.rule.threshold,
+            window_secs: self.rule.window.as_secs(),
+            state: self.state,
+            value: self.value,
+            since: self.since,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+    use crate::domain::EventFilter;
+    use chrono::Duration as Span;
+    use std::time::Duration;
+
+    fn rule(condition: AlertCondition, threshold: f64, for_secs: u64) -> AlertRule {
+        AlertRule {
+            name: "test".to_string(),
+            events: EventFilter {
+                event_type: "payment_failed".to_string(),
+                filter: None,
+            },
+            condition,
+            threshold,
+            window: Duration::from_secs(60),
+            for_duration: Duration::from_secs(for_secs),
+            webhook_url: None,
+            webhook_secret: None,
+        }
+    }
+
+    #[test]
+    fn count_rule_fires_and_resolves_as_the_window_slides() {
+        // ---
+        let start = Utc::now();
+        let mut window = RuleWindow::new(rule(AlertCondition::Count, 2.0, 0), start);
+
+        window.record(start);
+        window.record(start);
+        assert_eq!(window.evaluate(start), None);
+        window.record(start + Span::seconds(1));
+        assert_eq!(
+            window.evaluate(start + Span::seconds(1)),
+            Some(AlertState::Firing)
+        );
+        assert_eq!(window.status().value, 3.0);
+
+        // The first two events leave the window
+        assert_eq!(
+            window.evaluate(start + Span::seconds(60)),
+            Some(AlertState::Resolved)
+        );
+        assert_eq!(window.status().value, 1.0);
+        assert_eq!(window.evaluate(start + Span::seconds(61)), None);
+        assert_eq!(window.status().value, 0.0);
+    }
+
+    #[test]
+    fn pending_rule_fires_only_after_holding() {
+        // ---
+        let start = Utc::now();
+        let mut window = RuleWindow::new(rule(AlertCondition::Rate, 0.01, 30), start);
+
+        window.record(start);
+        assert_eq!(window.evaluate(start), Some(AlertState::Pending));
+        assert_eq!(window.evaluate(start + Span::seconds(10)), None);
+        assert_eq!(
+            window.evaluate(start + Span::seconds(30)),
+            Some(AlertState::Firing)
+        );
+        assert_eq!(
+            window.evaluate(start + Span::seconds(61)),
+            Some(AlertState::Resolved)
+        );
+
+        // Breached again, but it clears before `for_secs` passes
+        window.record(start + Span::seconds(70));
+        assert_eq!(
+            window.evaluate(start + Span::seconds(70)),
+            Some(AlertState::Pending)
+        );
+        assert_eq!(
+            window.evaluate(start + Span::seconds(131)),
+            Some(AlertState::Inactive)
+        );
+    }
+
+    #[test]
+    fn absence_rule_fires_when_events_stop() {
+        // ---
+        let start = Utc::now();
+        let mut window = RuleWindow::new(rule(AlertCondition::Absence, 0.0, 0), start);
+
+        assert_eq!(window.evaluate(start + Span::seconds(59)), None);
+        assert_eq!(
+            window.evaluate(start + Span::seconds(60)),
+            Some(AlertState::Firing)
+        );
+        window.record(start + Span::seconds(61));
+        assert_eq!(
+            window.evaluate(start + Span::seconds(61)),
+            Some(AlertState::Resolved)
+        );
+        assert_eq!(window.status().value, 0.0);
+    }
+}
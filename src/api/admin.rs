@@ -0,0 +1,97 @@
+//! Operational admin endpoints.
+//!
+//! Mounted only when the binary hands the router a `LogFilter`
+//! (`server.admin_api`). They sit behind the API key layer and, for
+//! certificate-authenticated clients, require the `admin` role.
+
+use axum::{
+    extract::{rejection::JsonRejection, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{auth, json_error, LogFilter};
+
+/// Body of `GET` and `PUT /admin/log-level`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LogLevelBody {
+    /// `tracing` filter directive, e.g. `info,argus_events::repository=debug`.
+    pub filter: String,
+}
+
+/// Admin routes, with their own state.
+pub fn routes<S>(log_filter: LogFilter) -> Router<S> {
+    // ---
+    Router::new()
+        .route("/admin/log-level", get(get_log_level).put(set_log_level))
+        .route_layer(middleware::from_fn_with_state(
+            auth::ADMIN,
+            auth::require_role,
+        ))
+        .with_state(log_filter)
+}
+
+/// GET /admin/log-level handler
+#[utoipa::path(
+    get,
+    path = "/admin/log-level",
+    tag = "admin",
+    security((), ("api_key" = []), ("bearer" = [])),
+    responses(
+        (status = 200, description = "Active log filter", body = LogLevelBody),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the admin role")
+    )
+)]
+pub(super) async fn get_log_level(State(log_filter): State<LogFilter>) -> Response {
+    // ---
+    Json(LogLevelBody {
+        filter: log_filter.current().to_string(),
+    })
+    .into_response()
+}
+
+/// PUT /admin/log-level handler
+#[utoipa::path(
+    put,
+    path = "/admin/log-level",
+    tag = "admin",
+    security((), ("api_key" = []), ("bearer" = [])),
+    request_body = LogLevelBody,
+    responses(
+        (status = 200, description = "Filter replaced", body = LogLevelBody),
+        (status = 400, description = "Invalid filter directive or malformed JSON"),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the admin role"),
+        (status = 422, description = "Body lacks a `filter` string")
+    )
+)]
+pub(super) async fn set_log_level(
+    State(log_filter): State<LogFilter>,
+    body: Result<Json<LogLevelBody>, JsonRejection>,
+) -> Response {
+    // ---
+    let Json(body) = match body {
+        Ok(body) => body,
+        Err(rejection) => return json_error(rejection.status(), rejection.body_text()),
+    };
+
+    match log_filter.set(&body.filter) {
+        Ok(()) => {
+            tracing::info!(filter = %body.filter, "Log filter changed via admin endpoint");
+            Json(body).into_response()
+        }
+        Err(err) => {
+            tracing::warn!(filter = %body.filter, %err, "Rejected log filter");
+            json_error(
+                StatusCode::BAD_REQUEST,
+                format!("invalid log filter: {}", err),
+            )
+        }
+    }
+}
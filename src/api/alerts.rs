@@ -0,0 +1,34 @@
+//! Alert state endpoint.
+//!
+//! `GET /v1/alerts` lists every configured rule with its current state
+//! (`inactive`, `pending`, `firing` or `resolved`) and value. Rules are
+//! configured in `[alerts]`; there is no API to change them.
+
+use axum::{extract::State, response::IntoResponse, Json};
+
+use super::AppState;
+use crate::domain::AlertStatus;
+
+/// GET /v1/alerts handler
+#[utoipa::path(
+    get,
+    path = "/v1/alerts",
+    tag = "alerts",
+    security((), ("api_key" = []), ("bearer" = [])),
+    responses(
+        (status = 200, description = "Every alert rule, in configuration order", body = [AlertStatus]),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the read role")
+    )
+)]
+#[tracing::instrument(name = "alerts.list", skip_all)]
+pub(super) async fn list_alerts(State(state): State<AppState>) -> impl IntoResponse {
+    // ---
+
+    let statuses: Vec<AlertStatus> = state
+        .alerts
+        .as_ref()
+        .map(|alerts| alerts.statuses())
+        .unwrap_or_default();
+    Json(statuses).into_response()
+}
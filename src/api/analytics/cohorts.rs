@@ -0,0 +1,147 @@
+//! Cohort retention endpoint.
+
+use axum::{
+    extract::{rejection::JsonRejection, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use super::check_range;
+use crate::api::json_error;
+use crate::api::AppState;
+use crate::domain::{is_payload_path, CohortPeriod, CohortQuery, CohortReport};
+
+/// Periods reported when none are requested.
+const DEFAULT_PERIODS: usize = 8;
+
+/// Most periods that can be reported.
+const MAX_PERIODS: usize = 366;
+
+/// Request body for `POST /v1/analytics/retention`
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CohortInput {
+    /// Event type whose first occurrence places an actor in a cohort, e.g. `user_signup`.
+    pub birth_event: String,
+    /// Event type that counts as coming back, e.g. `login`.
+    pub return_event: String,
+    /// Dotted payload path identifying the actor, e.g. `user_id`.
+    pub key: String,
+    /// `day`, `week` (default) or `month`.
+    pub period: Option<CohortPeriod>,
+    /// Periods reported after the birth period (default 8, at most 366).
+    pub periods: Option<usize>,
+    /// Earliest birth (inclusive).
+    pub start: Option<DateTime<Utc>>,
+    /// Latest birth (inclusive).
+    pub end: Option<DateTime<Utc>>,
+}
+
+/// Checks a retention request and turns it into a query.
+fn validate(input: CohortInput) -> Result<CohortQuery, String> {
+    // ---
+    if input.birth_event.trim().is_empty() || input.return_event.trim().is_empty() {
+        return Err("birth_event and return_event must not be empty".to_string());
+    }
+    if !is_payload_path(&input.key) {
+        return Err(format!("key '{}' is not a dotted payload path", input.key));
+    }
+    let periods = input.periods.unwrap_or(DEFAULT_PERIODS);
+    if !(1..=MAX_PERIODS).contains(&periods) {
+        return Err(format!("periods must be between 1 and {}", MAX_PERIODS));
+    }
+    check_range(input.start, input.end)?;
+    Ok(CohortQuery {
+        birth_event: input.birth_event,
+        return_event: input.return_event,
+        key: input.key,
+        period: input.period.unwrap_or(CohortPeriod::Week),
+        periods,
+        start: input.start,
+        end: input.end,
+    })
+}
+
+/// POST /v1/analytics/retention handler
+#[utoipa::path(
+    post,
+    path = "/v1/analytics/retention",
+    tag = "analytics",
+    security((), ("api_key" = []), ("bearer" = [])),
+    request_body = CohortInput,
+    responses(
+        (status = 200, description = "Cohorts by birth period with the actors retained in each later period", body = CohortReport),
+        (status = 400, description = "Malformed request body"),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the read role"),
+        (status = 422, description = "Invalid event types, key, periods or time range"),
+        (status = 500, description = "Storage failure")
+    )
+)]
+#[tracing::instrument(name = "analytics.retention", skip_all)]
+pub(in crate::api) async fn retention(
+    State(state): State<AppState>,
+    input: Result<Json<CohortInput>, JsonRejection>,
+) -> Response {
+    // ---
+    let input = match input {
+        Ok(Json(input)) => input,
+        Err(rejection) => return json_error(rejection.status(), rejection.body_text()),
+    };
+    let query = match validate(input) {
+        Ok(query) => query,
+        Err(message) => return json_error(StatusCode::UNPROCESSABLE_ENTITY, message),
+    };
+
+    match state.repo.cohorts(query).await {
+        Ok(report) => Json(report).into_response(),
+        Err(err) => {
+            tracing::error!(?err, "Failed to compute cohort retention");
+            json_error(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+
+    fn input(periods: Option<usize>) -> CohortInput {
+        // ---
+        CohortInput {
+            birth_event: "user_signup".to_string(),
+            return_event: "login".to_string(),
+            key: "user_id".to_string(),
+            period: None,
+            periods,
+            start: None,
+            end: None,
+        }
+    }
+
+    #[test]
+    fn retention_requests_are_validated() {
+        // ---
+        let query = validate(input(None)).unwrap();
+        assert_eq!(query.period, CohortPeriod::Week);
+        assert_eq!(query.periods, DEFAULT_PERIODS);
+
+        assert!(validate(input(Some(0))).is_err());
+        assert!(validate(input(Some(MAX_PERIODS + 1))).is_err());
+        assert!(validate(CohortInput {
+            return_event: " ".to_string(),
+            ..input(None)
+        })
+        .is_err());
+        assert!(validate(CohortInput {
+            key: "user.".to_string(),
+            ..input(None)
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,137 @@
+//! Conversion funnel endpoint.
+
+use axum::{
+    extract::{rejection::JsonRejection, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use super::check_range;
+use crate::api::json_error;
+use crate::api::AppState;
+use crate::domain::{is_payload_path, FunnelQuery, FunnelReport};
+
+/// Most steps a funnel may have.
+const MAX_STEPS: usize = 20;
+
+/// Request body for `POST /v1/analytics/funnel`
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct FunnelInput {
+    /// Event types of the steps, in order (2 to 20).
+    pub steps: Vec<String>,
+    /// Dotted payload path identifying the actor, e.g. `user_id`.
+    pub key: String,
+    /// Longest time from the first step to any later one.
+    pub window_secs: u64,
+    /// Start of the time range (inclusive).
+    pub start: Option<DateTime<Utc>>,
+    /// End of the time range (inclusive).
+    pub end: Option<DateTime<Utc>>,
+}
+
+/// Checks a funnel request and turns it into a query.
+fn validate(input: FunnelInput) -> Result<FunnelQuery, String> {
+    // ---
+    if !(2..=MAX_STEPS).contains(&input.steps.len()) {
+        return Err(format!("steps must list 2 to {} event types", MAX_STEPS));
+    }
+    if input.steps.iter().any(|step| step.trim().is_empty()) {
+        return Err("steps must not contain empty event types".to_string());
+    }
+    if !is_payload_path(&input.key) {
+        return Err(format!("key '{}' is not a dotted payload path", input.key));
+    }
+    let window = i64::try_from(input.window_secs)
+        .ok()
+        .and_then(Duration::try_seconds)
+        .filter(|window| *window > Duration::zero())
+        .ok_or_else(|| "window_secs must be a positive number of seconds".to_string())?;
+    check_range(input.start, input.end)?;
+    Ok(FunnelQuery {
+        steps: input.steps,
+        key: input.key,
+        window,
+        start: input.start,
+        end: input.end,
+    })
+}
+
+/// POST /v1/analytics/funnel handler
+#[utoipa::path(
+    post,
+    path = "/v1/analytics/funnel",
+    tag = "analytics",
+    security((), ("api_key" = []), ("bearer" = [])),
+    request_body = FunnelInput,
+    responses(
+        (status = 200, description = "Actors reaching each step and the conversion rates", body = FunnelReport),
+        (status = 400, description = "Malformed request body"),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the read role"),
+        (status = 422, description = "Invalid steps, key, window or time range"),
+        (status = 500, description = "Storage failure")
+    )
+)]
+#[tracing::instrument(name = "analytics.funnel", skip_all)]
+pub(in crate::api) async fn funnel(
+    State(state): State<AppState>,
+    input: Result<Json<FunnelInput>, JsonRejection>,
+) -> Response {
+    // ---
+    let input = match input {
+        Ok(Json(input)) => input,
+        Err(rejection) => return json_error(rejection.status(), rejection.body_text()),
+    };
+    let query = match validate(input) {
+        Ok(query) => query,
+        Err(message) => return json_error(StatusCode::UNPROCESSABLE_ENTITY, message),
+    };
+
+    match state.repo.funnel(query).await {
+        Ok(report) => Json(report).into_response(),
+        Err(err) => {
+            tracing::error!(?err, "Failed to compute funnel");
+            json_error(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+
+    fn input(steps: &[&str], key: &str, window_secs: u64) -> FunnelInput {
+        // ---
+        FunnelInput {
+            steps: steps.iter().map(|s| s.to_string()).collect(),
+            key: key.to_string(),
+            window_secs,
+            start: None,
+            end: None,
+        }
+    }
+
+    #[test]
+    fn funnel_requests_are_validated() {
+        // ---
+        let query = validate(input(&["signup", "purchase"], "user.id", 3600)).unwrap();
+        assert_eq!(query.window, Duration::hours(1));
+
+        assert!(validate(input(&["signup"], "user_id", 60)).is_err());
+        assert!(validate(input(&["signup", " "], "user_id", 60)).is_err());
+        assert!(validate(input(&["signup", "purchase"], "user..id", 60)).is_err());
+        assert!(validate(input(&["signup", "purchase"], "user_id", 0)).is_err());
+        assert!(validate(input(&["signup", "purchase"], "user_id", u64::MAX)).is_err());
+
+        let mut reversed = input(&["signup", "purchase"], "user_id", 60);
+        reversed.start = Some(Utc::now());
+        reversed.end = Some(Utc::now() - Duration::days(1));
+        assert!(validate(reversed).is_err());
+    }
+}
@@ -0,0 +1,55 @@
+//! Analytics over the stored events.
+//!
+//! These routes read stored events, so they require the `read` role for
+//! certificate-authenticated clients, like `GET /v1/events`. Invalid
+//! requests are answered with `422` and an `{"error"}` body.
+
+mod cohorts;
+mod funnel;
+mod sessions;
+mod statistics;
+mod values;
+
+use axum::{
+    middleware,
+    routing::{get, post},
+    Router,
+};
+use chrono::{DateTime, Utc};
+
+use super::{auth, AppState};
+
+// Exports used by the OpenAPI document
+pub(super) use cohorts::{__path_retention, CohortInput};
+pub(super) use funnel::{__path_funnel, FunnelInput};
+pub(super) use sessions::__path_sessions;
+pub(super) use statistics::__path_statistics;
+pub(super) use values::{__path_distinct, __path_top, DistinctResponse, TopResponse};
+
+/// Analytics routes, relative to the root.
+pub fn routes() -> Router<AppState> {
+    // ---
+    Router::new()
+        .route("/analytics/funnel", post(funnel::funnel))
+        .route("/analytics/sessions", get(sessions::sessions))
+        .route("/analytics/retention", post(cohorts::retention))
+        .route("/analytics/distinct", get(values::distinct))
+        .route("/analytics/top", get(values::top))
+        .route("/analytics/statistics", get(statistics::statistics))
+        .route_layer(middleware::from_fn_with_state(
+            auth::READ,
+            auth::require_role,
+        ))
+}
+
+/// Checks that a time range does not end before it starts.
+pub(super) fn check_range(
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+) -> Result<(), String> {
+    // ---
+    match (start, end) {
+        (Some(start), Some(end)) if start > end => Err("start must not be after end".to_string()),
+        _ => Ok(()),
+    }
+}
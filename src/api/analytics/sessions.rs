@@ -0,0 +1,148 @@
+//! Session endpoint.
+
+use axum::{
+    extract::{rejection::QueryRejection, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use super::check_range;
+use crate::api::json_error;
+use crate::api::AppState;
+use crate::domain::{is_payload_path, EventQuery, SessionQuery, SessionReport};
+
+/// Inactivity gap when none is given: 30 minutes.
+const DEFAULT_GAP_SECS: u64 = 30 * 60;
+
+/// Sessions listed when no limit is given.
+const DEFAULT_LIMIT: usize = 100;
+
+/// Most sessions that can be listed.
+const MAX_LIMIT: usize = 10_000;
+
+/// Query parameters for `GET /v1/analytics/sessions`
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SessionParams {
+    /// Dotted payload path identifying the actor, e.g. `user_id`.
+    pub key: String,
+    /// Longest pause within a session, in seconds (default 1800).
+    pub gap_secs: Option<u64>,
+    /// Only use events of this type.
+    #[serde(rename = "type")]
+    pub event_type: Option<String>,
+    /// Inclusive lower bound, RFC 3339 timestamp.
+    pub start: Option<DateTime<Utc>>,
+    /// Inclusive upper bound, RFC 3339 timestamp.
+    pub end: Option<DateTime<Utc>>,
+    /// Most sessions listed (default 100, at most 10000); the summary covers all.
+    pub limit: Option<usize>,
+}
+
+/// Checks session parameters and turns them into a query.
+fn validate(params: SessionParams) -> Result<SessionQuery, String> {
+    // ---
+    if !is_payload_path(&params.key) {
+        return Err(format!("key '{}' is not a dotted payload path", params.key));
+    }
+    let gap = i64::try_from(params.gap_secs.unwrap_or(DEFAULT_GAP_SECS))
+        .ok()
+        .and_then(Duration::try_seconds)
+        .filter(|gap| *gap > Duration::zero())
+        .ok_or_else(|| "gap_secs must be a positive number of seconds".to_string())?;
+    check_range(params.start, params.end)?;
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT);
+    if limit > MAX_LIMIT {
+        return Err(format!("limit must not exceed {}", MAX_LIMIT));
+    }
+    Ok(SessionQuery {
+        key: params.key,
+        gap,
+        events: EventQuery {
+            event_type: params.event_type,
+            start: params.start,
+            end: params.end,
+        },
+        limit,
+    })
+}
+
+/// GET /v1/analytics/sessions handler
+#[utoipa::path(
+    get,
+    path = "/v1/analytics/sessions",
+    tag = "analytics",
+    security((), ("api_key" = []), ("bearer" = [])),
+    params(SessionParams),
+    responses(
+        (status = 200, description = "Sessions by start time, with a summary of all of them", body = SessionReport),
+        (status = 400, description = "Malformed query parameters"),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the read role"),
+        (status = 422, description = "Invalid key, gap, time range or limit"),
+        (status = 500, description = "Storage failure")
+    )
+)]
+#[tracing::instrument(name = "analytics.sessions", skip_all)]
+pub(in crate::api) async fn sessions(
+    State(state): State<AppState>,
+    params: Result<Query<SessionParams>, QueryRejection>,
+) -> Response {
+    // ---
+    let params = match params {
+        Ok(Query(params)) => params,
+        Err(rejection) => return json_error(rejection.status(), rejection.body_text()),
+    };
+    let query = match validate(params) {
+        Ok(query) => query,
+        Err(message) => return json_error(StatusCode::UNPROCESSABLE_ENTITY, message),
+    };
+
+    match state.repo.sessions(query).await {
+        Ok(report) => Json(report).into_response(),
+        Err(err) => {
+            tracing::error!(?err, "Failed to compute sessions");
+            json_error(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+
+    fn params(key: &str, gap_secs: Option<u64>, limit: Option<usize>) -> SessionParams {
+        // ---
+        SessionParams {
+            key: key.to_string(),
+            gap_secs,
+            event_type: None,
+            start: None,
+            end: None,
+            limit,
+        }
+    }
+
+    #[test]
+    fn session_parameters_are_validated() {
+        // ---
+        let query = validate(params("user_id", None, None)).unwrap();
+        assert_eq!(query.gap, Duration::minutes(30));
+        assert_eq!(query.limit, DEFAULT_LIMIT);
+
+        assert!(validate(params("", None, None)).is_err());
+        assert!(validate(params("user_id", Some(0), None)).is_err());
+        assert!(validate(params("user_id", None, Some(MAX_LIMIT + 1))).is_err());
+
+        let mut reversed = params("user_id", None, None);
+        reversed.start = Some(Utc::now());
+        reversed.end = Some(Utc::now() - Duration::days(1));
+        assert!(validate(reversed).is_err());
+    }
+}
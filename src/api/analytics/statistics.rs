@@ -0,0 +1,144 @@
+//! Numeric statistics endpoint.
+
+use axum::{
+    extract::{rejection::QueryRejection, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::time::Duration;
+use utoipa::IntoParams;
+
+use super::check_range;
+use crate::api::json_error;
+use crate::api::AppState;
+use crate::domain::{
+    is_payload_path, EventQuery, NonNumericField, NumericStatsQuery, NumericStatsReport,
+};
+
+/// Bucket length when none is given: one hour.
+const DEFAULT_INTERVAL_SECS: u64 = 3600;
+
+/// Query parameters for `GET /v1/analytics/statistics`
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatisticsParams {
+    /// Event type whose events are read.
+    #[serde(rename = "type")]
+    pub event_type: String,
+    /// Dotted payload path of a numeric field, e.g. `amount`.
+    pub field: String,
+    /// Bucket length in seconds (default 3600).
+    pub interval_secs: Option<u64>,
+    /// Inclusive lower bound, RFC 3339 timestamp.
+    pub start: Option<DateTime<Utc>>,
+    /// Inclusive upper bound, RFC 3339 timestamp.
+    pub end: Option<DateTime<Utc>>,
+}
+
+/// Checks statistics parameters and turns them into a query.
+fn validate(params: StatisticsParams) -> Result<NumericStatsQuery, String> {
+    // ---
+    if params.event_type.trim().is_empty() {
+        return Err("type must not be empty".to_string());
+    }
+    if !is_payload_path(&params.field) {
+        return Err(format!(
+            "field '{}' is not a dotted payload path",
+            params.field
+        ));
+    }
+    let interval_secs = params.interval_secs.unwrap_or(DEFAULT_INTERVAL_SECS);
+    if interval_secs == 0 {
+        return Err("interval_secs must be greater than 0".to_string());
+    }
+    check_range(params.start, params.end)?;
+    Ok(NumericStatsQuery {
+        events: EventQuery {
+            event_type: Some(params.event_type),
+            start: params.start,
+            end: params.end,
+        },
+        field: params.field,
+        interval: Duration::from_secs(interval_secs),
+    })
+}
+
+/// GET /v1/analytics/statistics handler
+#[utoipa::path(
+    get,
+    path = "/v1/analytics/statistics",
+    tag = "analytics",
+    security((), ("api_key" = []), ("bearer" = [])),
+    params(StatisticsParams),
+    responses(
+        (status = 200, description = "Count, sum, avg, min, max and p50/p95/p99 per bucket and in total", body = NumericStatsReport),
+        (status = 400, description = "Malformed query parameters, or a matching event whose field is not numeric"),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the read role"),
+        (status = 422, description = "Invalid type, field, interval or time range"),
+        (status = 500, description = "Storage failure")
+    )
+)]
+#[tracing::instrument(name = "analytics.statistics", skip_all)]
+pub(in crate::api) async fn statistics(
+    State(state): State<AppState>,
+    params: Result<Query<StatisticsParams>, QueryRejection>,
+) -> Response {
+    // ---
+    let params = match params {
+        Ok(Query(params)) => params,
+        Err(rejection) => return json_error(rejection.status(), rejection.body_text()),
+    };
+    let query = match validate(params) {
+        Ok(query) => query,
+        Err(message) => return json_error(StatusCode::UNPROCESSABLE_ENTITY, message),
+    };
+
+    match state.repo.numeric_stats(query).await {
+        Ok(report) => Json(report).into_response(),
+        Err(err) => match err.downcast_ref::<NonNumericField>() {
+            Some(non_numeric) => json_error(StatusCode::BAD_REQUEST, non_numeric.to_string()),
+            None => {
+                tracing::error!(?err, "Failed to compute numeric statistics");
+                json_error(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+
+    fn params(field: &str, interval_secs: Option<u64>) -> StatisticsParams {
+        // ---
+        StatisticsParams {
+            event_type: "purchase".to_string(),
+            field: field.to_string(),
+            interval_secs,
+            start: None,
+            end: None,
+        }
+    }
+
+    #[test]
+    fn statistics_parameters_are_validated() {
+        // ---
+        let query = validate(params("order.amount", None)).unwrap();
+        assert_eq!(query.interval, Duration::from_secs(DEFAULT_INTERVAL_SECS));
+        assert_eq!(query.events.event_type.as_deref(), Some("purchase"));
+
+        assert!(validate(params("order.amount", Some(0))).is_err());
+        assert!(validate(params(".amount", None)).is_err());
+        assert!(validate(StatisticsParams {
+            event_type: " ".to_string(),
+            ..params("amount", None)
+        })
+        .is_err());
+    }
+}
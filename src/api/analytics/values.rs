@@ -0,0 +1,264 @@
+//! Distinct and top-N value endpoints.
+//!
+//! Both summarise one payload field of one event type. Counts are exact
+//! unless `approximate=true` is given or there are too many distinct
+//! values, in which case they come from HyperLogLog and Count-Min sketches
+//! and the response says `"approximate": true`.
+
+use axum::{
+    extract::{rejection::QueryRejection, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::check_range;
+use crate::api::json_error;
+use crate::api::AppState;
+use crate::domain::{
+    is_payload_path, FieldValueQuery, FieldValueReport, ValueCount, SKETCH_TOP_VALUES,
+};
+
+/// Values listed by `GET /v1/analytics/top` when no limit is given.
+const DEFAULT_LIMIT: usize = 10;
+
+/// Query parameters for `GET /v1/analytics/distinct`
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DistinctParams {
+    /// Event type whose events are read.
+    #[serde(rename = "type")]
+    pub event_type: String,
+    /// Dotted payload path of the field, e.g. `user_id`.
+    pub field: String,
+    /// Inclusive lower bound, RFC 3339 timestamp.
+    pub start: Option<DateTime<Utc>>,
+    /// Inclusive upper bound, RFC 3339 timestamp.
+    pub end: Option<DateTime<Utc>>,
+    /// Estimate from sketches instead of counting exactly (default false).
+    pub approximate: Option<bool>,
+}
+
+/// Query parameters for `GET /v1/analytics/top`
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TopParams {
+    /// Event type whose events are read.
+    #[serde(rename = "type")]
+    pub event_type: String,
+    /// Dotted payload path of the field, e.g. `source`.
+    pub field: String,
+    /// Inclusive lower bound, RFC 3339 timestamp.
+    pub start: Option<DateTime<Utc>>,
+    /// Inclusive upper bound, RFC 3339 timestamp.
+    pub end: Option<DateTime<Utc>>,
+    /// Values listed (default 10, at most 100).
+    pub limit: Option<usize>,
+    /// Estimate from sketches instead of counting exactly (default false).
+    pub approximate: Option<bool>,
+}
+
+/// Response of `GET /v1/analytics/distinct`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DistinctResponse {
+    pub event_type: String,
+    pub field: String,
+    /// Events carrying the field.
+    pub events: u64,
+    /// Distinct values of the field.
+    pub distinct: u64,
+    /// Whether `distinct` is an estimate.
+    pub approximate: bool,
+}
+
+/// Response of `GET /v1/analytics/top`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TopResponse {
+    pub event_type: String,
+    pub field: String,
+    /// Events carrying the field.
+    pub events: u64,
+    /// Most frequent values, most frequent first.
+    pub values: Vec<ValueCount>,
+    /// Whether the counts are estimates (never below the true count).
+    pub approximate: bool,
+}
+
+/// Checks the shared parameters and turns them into a query.
+fn validate(
+    event_type: String,
+    field: String,
+    range: (Option<DateTime<Utc>>, Option<DateTime<Utc>>),
+    limit: usize,
+    approximate: Option<bool>,
+) -> Result<FieldValueQuery, String> {
+    // ---
+    if event_type.trim().is_empty() {
+        return Err("type must not be empty".to_string());
+    }
+    if !is_payload_path(&field) {
+        return Err(format!("field '{}' is not a dotted payload path", field));
+    }
+    check_range(range.0, range.1)?;
+    if !(1..=SKETCH_TOP_VALUES).contains(&limit) {
+        return Err(format!("limit must be between 1 and {}", SKETCH_TOP_VALUES));
+    }
+    Ok(FieldValueQuery {
+        event_type,
+        field,
+        start: range.0,
+        end: range.1,
+        limit,
+        approximate: approximate.unwrap_or(false),
+    })
+}
+
+/// Runs `query` and shapes the report with `respond`.
+async fn answer(
+    state: AppState,
+    query: Result<FieldValueQuery, String>,
+    respond: impl FnOnce(FieldValueReport) -> Response,
+) -> Response {
+    // ---
+    let query = match query {
+        Ok(query) => query,
+        Err(message) => return json_error(StatusCode::UNPROCESSABLE_ENTITY, message),
+    };
+    match state.repo.field_values(query).await {
+        Ok(report) => respond(report),
+        Err(err) => {
+            tracing::error!(?err, "Failed to count field values");
+            json_error(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+        }
+    }
+}
+
+/// GET /v1/analytics/distinct handler
+#[utoipa::path(
+    get,
+    path = "/v1/analytics/distinct",
+    tag = "analytics",
+    security((), ("api_key" = []), ("bearer" = [])),
+    params(DistinctParams),
+    responses(
+        (status = 200, description = "Number of distinct values of the field", body = DistinctResponse),
+        (status = 400, description = "Malformed query parameters"),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the read role"),
+        (status = 422, description = "Invalid type, field or time range"),
+        (status = 500, description = "Storage failure")
+    )
+)]
+#[tracing::instrument(name = "analytics.distinct", skip_all)]
+pub(in crate::api) async fn distinct(
+    State(state): State<AppState>,
+    params: Result<Query<DistinctParams>, QueryRejection>,
+) -> Response {
+    // ---
+    let params = match params {
+        Ok(Query(params)) => params,
+        Err(rejection) => return json_error(rejection.status(), rejection.body_text()),
+    };
+    let query = validate(
+        params.event_type,
+        params.field,
+        (params.start, params.end),
+        1,
+        params.approximate,
+    );
+
+    answer(state, query, |report| {
+        Json(DistinctResponse {
+            event_type: report.event_type,
+            field: report.field,
+            events: report.events,
+            distinct: report.distinct,
+            approximate: report.approximate,
+        })
+        .into_response()
+    })
+    .await
+}
+
+/// GET /v1/analytics/top handler
+#[utoipa::path(
+    get,
+    path = "/v1/analytics/top",
+    tag = "analytics",
+    security((), ("api_key" = []), ("bearer" = [])),
+    params(TopParams),
+    responses(
+        (status = 200, description = "Most frequent values of the field with their counts", body = TopResponse),
+        (status = 400, description = "Malformed query parameters"),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the read role"),
+        (status = 422, description = "Invalid type, field, time range or limit"),
+        (status = 500, description = "Storage failure")
+    )
+)]
+#[tracing::instrument(name = "analytics.top", skip_all)]
+pub(in crate::api) async fn top(
+    State(state): State<AppState>,
+    params: Result<Query<TopParams>, QueryRejection>,
+) -> Response {
+    // ---
+    let params = match params {
+        Ok(Query(params)) => params,
+        Err(rejection) => return json_error(rejection.status(), rejection.body_text()),
+    };
+    let query = validate(
+        params.event_type,
+        params.field,
+        (params.start, params.end),
+        params.limit.unwrap_or(DEFAULT_LIMIT),
+        params.approximate,
+    );
+
+    answer(state, query, |report| {
+        Json(TopResponse {
+            event_type: report.event_type,
+            field: report.field,
+            events: report.events,
+            values: report.top,
+            approximate: report.approximate,
+        })
+        .into_response()
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+
+    fn check(field: &str, limit: usize) -> Result<FieldValueQuery, String> {
+        validate(
+            "login".to_string(),
+            field.to_string(),
+            (None, None),
+            limit,
+            None,
+        )
+    }
+
+    #[test]
+    fn value_parameters_are_validated() {
+        // ---
+        let query = check("user.id", DEFAULT_LIMIT).unwrap();
+        assert!(!query.approximate);
+        assert_eq!(query.limit, DEFAULT_LIMIT);
+
+        assert!(check("user.", DEFAULT_LIMIT).is_err());
+        assert!(check("user_id", 0).is_err());
+        assert!(check("user_id", SKETCH_TOP_VALUES + 1).is_err());
+        let later = Some(Utc::now());
+        let earlier = later.map(|t| t - chrono::Duration::hours(1));
+        let range = validate("login".into(), "user_id".into(), (later, earlier), 1, None);
+        assert!(range.is_err());
+    }
+}
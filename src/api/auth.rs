@@ -0,0 +1,119 @@
+//! Authentication and authorization for event routes.
+//!
+//! When at least one key is configured, requests must present one via
+//! `Authorization: Bearer <key>` or `X-API-Key: <key>`. Operational
+//! endpoints (health, metrics, spec) are mounted outside this layer.
+//!
+//! Requests arriving with a client certificate (mutual TLS) additionally
+//! carry a `ClientIdentity`; each route group checks its roles with
+//! `require_role`, layered via `route_layer`.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+
+use super::{json_error, LiveConfig};
+use crate::domain::{AnonymousClient, ClientIdentity, Role};
+
+/// Roles admitted to event submission.
+pub const INGEST: &[Role] = &[Role::Ingest];
+
+/// Roles admitted to queries and derived data.
+pub const READ: &[Role] = &[Role::Read];
+
+/// Roles admitted to configuration changes such as webhook subscriptions.
+pub const WRITE: &[Role] = &[Role::Ingest, Role::Admin];
+
+/// Roles admitted to the admin endpoints.
+pub const ADMIN: &[Role] = &[Role::Admin];
+
+/// Middleware rejecting requests without a configured API key.
+pub async fn require_api_key(
+    State(live): State<LiveConfig>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // ---
+    let keys = live.api_keys();
+    if keys.is_empty() {
+        return next.run(request).await;
+    }
+
+    let presented = presented_key(request.headers());
+    let authorized = presented
+        .map(|key| {
+            keys.iter()
+                .any(|k| constant_time_eq(k.as_bytes(), key.as_bytes()))
+        })
+        .unwrap_or(false);
+
+    if authorized {
+        next.run(request).await
+    } else {
+        tracing::warn!(path = %request.uri().path(), "Rejected request without valid API key");
+        (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            Json(json!({ "error": "missing or invalid API key" })),
+        )
+            .into_response()
+    }
+}
+
+/// Middleware rejecting certificate-authenticated clients that hold none
+/// of `roles`.
+///
+/// Requests marked `AnonymousClient` are rejected; requests that arrived
+/// without mutual TLS are not restricted here.
+pub async fn require_role(
+    State(roles): State<&'static [Role]>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // ---
+    let extensions = request.extensions();
+    if extensions.get::<AnonymousClient>().is_some() {
+        tracing::warn!(path = %request.uri().path(), "Rejected request without a client certificate identity");
+        return forbidden("client certificate identity required".to_string());
+    }
+    match extensions.get::<ClientIdentity>() {
+        Some(identity) if !roles.iter().any(|role| identity.has_role(*role)) => {
+            tracing::warn!(subject = %identity.subject, ?roles, "Client certificate lacks required role");
+            let names: Vec<String> = roles.iter().map(|role| format!("'{}'", role)).collect();
+            forbidden(format!(
+                "client '{}' lacks the {} role",
+                identity.subject,
+                names.join(" or ")
+            ))
+        }
+        _ => next.run(request).await,
+    }
+}
+
+fn forbidden(message: String) -> Response {
+    // ---
+    json_error(StatusCode::FORBIDDEN, message)
+}
+
+fn presented_key(headers: &HeaderMap) -> Option<&str> {
+    // ---
+    if let Some(value) = headers.get("x-api-key").and_then(|v| v.to_str().ok()) {
+        return Some(value.trim());
+    }
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+/// Compares two byte strings without short-circuiting on the first mismatch.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    // ---
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
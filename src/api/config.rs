@@ -0,0 +1,137 @@
+//! Runtime configuration for the HTTP API layer.
+//!
+//! Groups the knobs that influence request handling so callers
+//! (the binary, integration tests) can build a router without
+//! reaching into handler internals. Settings that may change while
+//! the server runs live in `LiveConfig`, whose handle the binary keeps
+//! to apply configuration reloads. The log filter lives in `LogFilter`,
+//! shared by reloads and the admin endpoint. The binary also passes in
+//! `Webhooks`, so their state outlives the router, and the configured
+//! `Alerts`, `Rollups` and `Schemas`.
+
+use anyhow::Result;
+use arc_swap::ArcSwap;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use super::{DeprecationPolicy, Lifecycle};
+use crate::alerts::Alerts;
+use crate::domain::ValidationRules;
+use crate::rollups::Rollups;
+use crate::schemas::Schemas;
+use crate::webhooks::Webhooks;
+
+/// Settings applied by the API handlers.
+#[derive(Debug, Clone, Default)]
+pub struct AppConfig {
+    // ---
+    /// Hot-reloadable settings (validation rules, API keys).
+    pub live: LiveConfig,
+
+    /// Serve a Swagger UI for the OpenAPI document at `/docs`.
+    pub openapi_ui: bool,
+
+    /// Headers advertised on the unversioned legacy routes.
+    pub deprecation: DeprecationPolicy,
+
+    /// Lifecycle handle; keep a clone to flip readiness during shutdown.
+    pub lifecycle: Lifecycle,
+
+    /// When set, `/admin/log-level` reads and replaces the log filter.
+    pub log_filter: Option<LogFilter>,
+
+    /// Webhook subscriptions and delivery queue; when unset the router
+    /// creates an in-memory one with default settings.
+    pub webhooks: Option<Webhooks>,
+
+    /// Alert rules evaluated against stored events; when unset there are none.
+    pub alerts: Option<Alerts>,
+
+    /// Rollups maintained from stored events; when unset there are none.
+    pub rollups: Option<Rollups>,
+
+    /// Payload schemas inferred from stored events; when unset none are.
+    pub schemas: Option<Schemas>,
+}
+
+/// Cloneable handle to settings that can be swapped while serving requests.
+#[derive(Debug, Clone, Default)]
+pub struct LiveConfig {
+    validation: Arc<ArcSwap<ValidationRules>>,
+    api_keys: Arc<ArcSwap<HashSet<String>>>,
+}
+
+impl LiveConfig {
+    // ---
+
+    /// Creates a handle with the given initial values.
+    pub fn new(validation: ValidationRules, api_keys: HashSet<String>) -> Self {
+        Self {
+            validation: Arc::new(ArcSwap::from_pointee(validation)),
+            api_keys: Arc::new(ArcSwap::from_pointee(api_keys)),
+        }
+    }
+
+    /// Current validation rules.
+    pub fn validation(&self) -> Arc<ValidationRules> {
+        self.validation.load_full()
+    }
+
+    /// Replaces the validation rules for subsequent requests.
+    pub fn set_validation(&self, rules: ValidationRules) {
+        self.validation.store(Arc::new(rules));
+    }
+
+    /// Currently accepted API keys; empty means authentication is disabled.
+    pub fn api_keys(&self) -> Arc<HashSet<String>> {
+        self.api_keys.load_full()
+    }
+
+    /// Replaces the accepted API keys for subsequent requests.
+    pub fn set_api_keys(&self, keys: HashSet<String>) {
+        self.api_keys.store(Arc::new(keys));
+    }
+}
+
+/// Callback that installs a log filter directive in the subscriber.
+type ApplyFilter = dyn Fn(&str) -> Result<()> + Send + Sync;
+
+/// Cloneable handle to the active log filter directive.
+#[derive(Clone)]
+pub struct LogFilter {
+    current: Arc<ArcSwap<String>>,
+    apply: Arc<ApplyFilter>,
+}
+
+impl LogFilter {
+    // ---
+
+    /// Creates a handle; `apply` installs a directive and fails if it is invalid.
+    pub fn new(initial: &str, apply: impl Fn(&str) -> Result<()> + Send + Sync + 'static) -> Self {
+        Self {
+            current: Arc::new(ArcSwap::from_pointee(initial.to_string())),
+            apply: Arc::new(apply),
+        }
+    }
+
+    /// Directive currently in effect.
+    pub fn current(&self) -> Arc<String> {
+        self.current.load_full()
+    }
+
+    /// Installs `directive`; on error the current filter stays in effect.
+    pub fn set(&self, directive: &str) -> Result<()> {
+        (self.apply)(directive)?;
+        self.current.store(Arc::new(directive.to_string()));
+        Ok(())
+    }
+}
+
+impl fmt::Debug for LogFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogFilter")
+            .field("current", &self.current())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,69 @@
+//! Deprecation headers for the legacy unversioned routes.
+//!
+//! Unversioned paths (`/events`) are served by the current stable version
+//! but tagged with `Deprecation` (RFC 9745), optional `Sunset` (RFC 8594)
+//! and a `Link` to the versioned successor so clients can migrate.
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, TimeZone, Utc};
+
+/// Dates advertised on deprecated routes.
+#[derive(Debug, Clone)]
+pub struct DeprecationPolicy {
+    // ---
+    /// When the unversioned routes were deprecated.
+    pub deprecated_at: DateTime<Utc>,
+
+    /// When the unversioned routes will stop being served, if decided.
+    pub sunset: Option<DateTime<Utc>>,
+
+    /// Version prefix of the successor routes (e.g. `/v1`).
+    pub successor_prefix: String,
+}
+
+impl Default for DeprecationPolicy {
+    fn default() -> Self {
+        Self {
+            // Release that introduced `/v1`
+            deprecated_at: Utc.with_ymd_and_hms(2026, 10, 18, 0, 0, 0).unwrap(),
+            sunset: None,
+            successor_prefix: "/v1".to_string(),
+        }
+    }
+}
+
+/// Middleware adding deprecation headers to every response.
+pub async fn add_deprecation_headers(
+    State(policy): State<DeprecationPolicy>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // ---
+    let successor = format!("{}{}", policy.successor_prefix, request.uri().path());
+    tracing::debug!(path = %request.uri().path(), %successor, "Serving deprecated route");
+
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+
+    let deprecation = format!("@{}", policy.deprecated_at.timestamp());
+    if let Ok(value) = HeaderValue::from_str(&deprecation) {
+        headers.insert(HeaderName::from_static("deprecation"), value);
+    }
+    if let Some(sunset) = policy.sunset {
+        let http_date = sunset.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        if let Ok(value) = HeaderValue::from_str(&http_date) {
+            headers.insert(HeaderName::from_static("sunset"), value);
+        }
+    }
+    if let Ok(value) = HeaderValue::from_str(&format!("<{}>; rel=\"successor-version\"", successor))
+    {
+        headers.append(axum::http::header::LINK, value);
+    }
+
+    response
+}
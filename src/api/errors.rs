@@ -0,0 +1,14 @@
+//! JSON error responses shared by the handlers.
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+
+/// A response with `status` and an `{"error": message}` body.
+pub(crate) fn json_error(status: StatusCode, message: impl Into<String>) -> Response {
+    // ---
+    (status, Json(json!({ "error": message.into() }))).into_response()
+}
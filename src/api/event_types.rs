@@ -0,0 +1,36 @@
+//! Event type discovery endpoint.
+//!
+//! `GET /v1/event-types` lists every stored event type with its count, first
+//! and last timestamps and the payload fields of its recent events, so
+//! clients can learn what is stored without knowing the producers.
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+
+use super::AppState;
+use crate::domain::EventTypeSummary;
+
+/// GET /v1/event-types handler
+#[utoipa::path(
+    get,
+    path = "/v1/event-types",
+    tag = "events",
+    security((), ("api_key" = []), ("bearer" = [])),
+    responses(
+        (status = 200, description = "Every stored event type, ordered by name", body = [EventTypeSummary]),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the read role"),
+        (status = 500, description = "Storage failure", body = String)
+    )
+)]
+#[tracing::instrument(name = "event_types.list", skip_all)]
+pub(super) async fn list_event_types(State(state): State<AppState>) -> impl IntoResponse {
+    // ---
+
+    match state.repo.event_types().await {
+        Ok(event_types) => Json(event_types).into_response(),
+        Err(e) => {
+            tracing::error!(?e, "Failed to list event types");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
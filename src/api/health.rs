@@ -0,0 +1,96 @@
+//! Liveness, readiness and status endpoints.
+//!
+//! - `/healthz` only proves the process is serving HTTP.
+//! - `/readyz` checks the repository and metrics backends and fails while draining.
+//! - `/status` returns the full picture as JSON for humans and dashboards.
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::AppState;
+use crate::domain::ComponentHealth;
+
+/// Body of `/readyz` and `/status`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StatusResponse {
+    /// "ok", "degraded" or "draining".
+    pub status: &'static str,
+    pub version: &'static str,
+    pub uptime_seconds: u64,
+    pub draining: bool,
+    pub components: Vec<ComponentHealth>,
+}
+
+impl StatusResponse {
+    fn is_ready(&self) -> bool {
+        self.status == "ok"
+    }
+}
+
+async fn collect_status(state: &AppState) -> StatusResponse {
+    // ---
+    let components = vec![state.repo.health().await, state.metrics.health()];
+    let draining = state.lifecycle.is_draining();
+
+    let status = if draining {
+        "draining"
+    } else if components.iter().all(|c| c.healthy) {
+        "ok"
+    } else {
+        "degraded"
+    };
+
+    StatusResponse {
+        status,
+        version: env!("CARGO_PKG_VERSION"),
+        uptime_seconds: state.lifecycle.uptime().as_secs(),
+        draining,
+        components,
+    }
+}
+
+/// GET /healthz handler - process liveness
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    tag = "observability",
+    responses((status = 200, description = "Process is alive", body = String, content_type = "text/plain"))
+)]
+pub(super) async fn healthz() -> impl IntoResponse {
+    // ---
+    (StatusCode::OK, "ok")
+}
+
+/// GET /readyz handler - backend readiness
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "observability",
+    responses(
+        (status = 200, description = "Ready to serve traffic", body = StatusResponse),
+        (status = 503, description = "A backend is unhealthy or the service is draining", body = StatusResponse)
+    )
+)]
+pub(super) async fn readyz(State(state): State<AppState>) -> impl IntoResponse {
+    // ---
+    let status = collect_status(&state).await;
+    if status.is_ready() {
+        (StatusCode::OK, Json(status))
+    } else {
+        tracing::warn!(status = status.status, "Readiness check failed");
+        (StatusCode::SERVICE_UNAVAILABLE, Json(status))
+    }
+}
+
+/// GET /status handler - detailed service status
+#[utoipa::path(
+    get,
+    path = "/status",
+    tag = "observability",
+    responses((status = 200, description = "Detailed service status", body = StatusResponse))
+)]
+pub(super) async fn status(State(state): State<AppState>) -> impl IntoResponse {
+    // ---
+    Json(collect_status(&state).await)
+}
@@ -0,0 +1,80 @@
+//! HTTP metrics middleware.
+//!
+//! Applied to the whole router so every route is measured without handler
+//! code: request count and latency by route template, method and status,
+//! an in-flight gauge, and request/response body sizes when known.
+//! Requests that match no route are reported under `UNMATCHED_ROUTE` so
+//! arbitrary paths cannot create new series.
+
+use axum::{
+    body::HttpBody,
+    extract::{MatchedPath, Request, State},
+    http::header,
+    middleware::Next,
+    response::Response,
+};
+use std::time::Instant;
+
+use crate::domain::MetricsPtr;
+
+/// Route label for requests that did not match any route.
+pub const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Middleware recording HTTP metrics for every request.
+pub async fn record_http_metrics(
+    State(metrics): State<MetricsPtr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // ---
+    let start = Instant::now();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+    let method = request.method().to_string();
+    let request_bytes = body_size(request.headers(), request.body().size_hint().exact());
+
+    let _in_flight = InFlight::begin(metrics.clone(), &route);
+    let response = next.run(request).await;
+
+    let response_bytes = body_size(response.headers(), response.body().size_hint().exact());
+    metrics.record_http_request(start, &route, &method, response.status().as_u16());
+    metrics.record_http_body_sizes(&route, &method, request_bytes, response_bytes);
+
+    response
+}
+
+/// Exact body size from the body itself, falling back to `Content-Length`.
+fn body_size(headers: &axum::http::HeaderMap, exact: Option<u64>) -> Option<u64> {
+    // ---
+    exact.or_else(|| {
+        headers
+            .get(header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+    })
+}
+
+/// Keeps the in-flight gauge balanced even if the request future is dropped.
+struct InFlight {
+    metrics: MetricsPtr,
+    route: String,
+}
+
+impl InFlight {
+    fn begin(metrics: MetricsPtr, route: &str) -> Self {
+        metrics.record_http_in_flight(route, 1);
+        Self {
+            metrics,
+            route: route.to_string(),
+        }
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.metrics.record_http_in_flight(&self.route, -1);
+    }
+}
@@ -0,0 +1,129 @@
+//! Process lifecycle shared between the binary and the API handlers.
+//!
+//! The binary flips the service into draining mode when shutdown begins,
+//! which makes readiness probes fail so load balancers stop routing new
+//! traffic while in-flight requests finish. Every request is counted so
+//! shutdown can report how much work was drained or abandoned.
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Cloneable handle to the service's lifecycle state.
+#[derive(Debug, Clone)]
+pub struct Lifecycle {
+    inner: Arc<LifecycleInner>,
+}
+
+#[derive(Debug)]
+struct LifecycleInner {
+    started_at: Instant,
+    draining: AtomicBool,
+    in_flight: AtomicUsize,
+    completed_while_draining: AtomicU64,
+}
+
+impl Default for Lifecycle {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(LifecycleInner {
+                started_at: Instant::now(),
+                draining: AtomicBool::new(false),
+                in_flight: AtomicUsize::new(0),
+                completed_while_draining: AtomicU64::new(0),
+            }),
+        }
+    }
+}
+
+impl Lifecycle {
+    // ---
+
+    /// Marks the service as shutting down; readiness fails from now on.
+    pub fn start_draining(&self) {
+        self.inner.draining.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether shutdown has begun.
+    pub fn is_draining(&self) -> bool {
+        self.inner.draining.load(Ordering::SeqCst)
+    }
+
+    /// Time since the handle was created.
+    pub fn uptime(&self) -> Duration {
+        self.inner.started_at.elapsed()
+    }
+
+    /// Number of requests currently being handled.
+    pub fn in_flight(&self) -> usize {
+        self.inner.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Number of requests that finished after draining began.
+    pub fn completed_while_draining(&self) -> u64 {
+        self.inner.completed_while_draining.load(Ordering::SeqCst)
+    }
+
+    /// Registers a request; the returned guard unregisters it when dropped.
+    pub fn begin_request(&self) -> RequestGuard {
+        self.inner.in_flight.fetch_add(1, Ordering::SeqCst);
+        RequestGuard {
+            lifecycle: self.clone(),
+        }
+    }
+}
+
+/// Keeps a request counted as in-flight until dropped.
+#[derive(Debug)]
+pub struct RequestGuard {
+    lifecycle: Lifecycle,
+}
+
+impl Drop for RequestGuard {
+    fn drop(&mut self) {
+        let inner = &self.lifecycle.inner;
+        inner.in_flight.fetch_sub(1, Ordering::SeqCst);
+        if inner.draining.load(Ordering::SeqCst) {
+            inner
+                .completed_while_draining
+                .fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Middleware counting in-flight requests.
+pub async fn track_in_flight(
+    State(lifecycle): State<Lifecycle>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // ---
+    let _guard = lifecycle.begin_request();
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_requests_drained_after_shutdown_begins() {
+        // ---
+        let lifecycle = Lifecycle::default();
+        let before = lifecycle.begin_request();
+        let during = lifecycle.begin_request();
+        assert_eq!(lifecycle.in_flight(), 2);
+
+        drop(before);
+        lifecycle.start_draining();
+        drop(during);
+
+        assert_eq!(lifecycle.in_flight(), 0);
+        assert_eq!(lifecycle.completed_while_draining(), 1);
+    }
+}
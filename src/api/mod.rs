@@ -1,9 +1,40 @@
 //! Public gateway for the HTTP API layer.
 //!
 //! This module wires up Axum routes and exposes them for integration
-//! into the main application.
+//! into the main application. Each API version lives in its own module
+//! (`v1`, and later `v2`) with its own wire DTOs, sharing `AppState`;
+//! `routes.rs` mounts them under their version prefix.
 
-mod events;
+mod admin;
+mod alerts;
+mod analytics;
+mod auth;
+mod config;
+mod deprecation;
+mod errors;
+mod event_types;
+mod health;
+mod http_metrics;
+mod lifecycle;
+mod observability;
+mod openapi;
+mod request_id;
+mod rollups;
+mod routes;
+mod schemas;
+mod state;
+mod stats;
+mod trace_context;
+mod v1;
+mod webhooks;
 
 // Public exports (visible outside this module)
-pub use events::event_routes;
+pub use config::{AppConfig, LiveConfig, LogFilter};
+pub use deprecation::DeprecationPolicy;
+pub use lifecycle::Lifecycle;
+pub use openapi::ApiDoc;
+pub use routes::{event_routes, event_routes_with_config, API_ROUTES};
+pub use state::AppState;
+
+// Shared by the handlers
+use errors::json_error;
@@ -0,0 +1,39 @@
+//! Unversioned operational endpoints (metrics scraping).
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+
+use super::AppState;
+
+/// GET /metrics handler - Prometheus metrics endpoint
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "observability",
+    responses(
+        (status = 200, description = "Metrics in Prometheus text format", body = String, content_type = "text/plain"),
+        (status = 500, description = "Metrics could not be rendered", body = String, content_type = "text/plain")
+    )
+)]
+pub(super) async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
+    // ---
+
+    tracing::debug!("Serving metrics endpoint");
+
+    match state.metrics.render() {
+        Ok(metrics_content) => (
+            StatusCode::OK,
+            [("content-type", "text/plain; charset=utf-8")],
+            metrics_content,
+        )
+            .into_response(),
+        Err(err) => {
+            tracing::error!("Failed to render metrics: {}", err);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                [("content-type", "text/plain; charset=utf-8")],
+                "Error rendering metrics".to_string(),
+            )
+                .into_response()
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! OpenAPI 3 document for the HTTP API.
+//!
+//! The document is derived from the handler annotations and the actual
+//! request/response types in `events.rs`, so field names (including serde
+//! renames such as `type` on `GET /events`) always match what the router
+//! accepts. Served at `/openapi.json`, with an optional Swagger UI at `/docs`
+//! whose assets are compiled into the binary.
+
+use axum::{response::IntoResponse, routing::get, Json, Router};
+use utoipa::openapi::path::Operation;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::openapi::Deprecated;
+use utoipa::{Modify, OpenApi};
+use utoipa_swagger_ui::{Config, SwaggerUi};
+
+use super::admin::{self, LogLevelBody};
+use super::alerts;
+use super::analytics::{self, CohortInput, DistinctResponse, FunnelInput, TopResponse};
+use super::event_types;
+use super::health::{self, StatusResponse};
+use super::observability;
+use super::rollups;
+use super::schemas;
+use super::stats;
+use super::v1::{self, EventInput, EventResponse, ValidationErrorResponse};
+use super::webhooks::{self, SubscriptionInput, SubscriptionResponse};
+use super::AppState;
+use crate::domain::{
+    AlertCondition, AlertState, AlertStatus, Cohort, CohortPeriod, CohortReport, ComponentHealth,
+    DeadLetter, Event, EventTypeStats, EventTypeSummary, FieldSchema, FieldStats, FieldViolation,
+    FunnelReport, FunnelStep, JsonType, NumericBucket, NumericStats, NumericStatsReport,
+    PayloadField, PayloadSchema, RepositoryStats, RollupBucket, RollupReport, Session,
+    SessionReport, SessionSummary, TraceContext, ValueCount,
+};
+
+/// Aggregated OpenAPI description of every documented route.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Argus Events API",
+        description = "Event ingestion and query service. The unversioned `/events` routes are \
+                       deprecated aliases of `/v1/events` and carry `Deprecation`/`Sunset` headers."
+    ),
+    paths(
+        v1::submit_event,
+        v1::get_events,
+        observability::metrics_handler,
+        health::healthz,
+        health::readyz,
+        health::status,
+        stats::get_stats,
+        event_types::list_event_types,
+        alerts::list_alerts,
+        analytics::funnel,
+        analytics::sessions,
+        analytics::retention,
+        analytics::distinct,
+        analytics::top,
+        analytics::statistics,
+        rollups::get_rollup,
+        schemas::list_schemas,
+        schemas::get_schema,
+        webhooks::create_subscription,
+        webhooks::list_subscriptions,
+        webhooks::delete_subscription,
+        webhooks::list_dead_letters,
+        admin::get_log_level,
+        admin::set_log_level,
+        openapi_json
+    ),
+    components(schemas(
+        EventResponse,
+        EventInput,
+        ValidationErrorResponse,
+        FieldViolation,
+        StatusResponse,
+        ComponentHealth,
+        TraceContext,
+        RepositoryStats,
+        EventTypeStats,
+        EventTypeSummary,
+        PayloadField,
+        JsonType,
+        SubscriptionInput,
+        SubscriptionResponse,
+        DeadLetter,
+        Event,
+        AlertStatus,
+        AlertCondition,
+        AlertState,
+        FunnelInput,
+        FunnelReport,
+        FunnelStep,
+        SessionReport,
+        Session,
+        SessionSummary,
+        CohortInput,
+        CohortReport,
+        Cohort,
+        CohortPeriod,
+        DistinctResponse,
+        TopResponse,
+        ValueCount,
+        NumericStatsReport,
+        NumericBucket,
+        NumericStats,
+        RollupReport,
+        RollupBucket,
+        PayloadSchema,
+        FieldSchema,
+        FieldStats,
+        LogLevelBody
+    )),
+    modifiers(&SecuritySchemes, &LegacyAliases),
+    tags(
+        (name = "events", description = "Event ingestion and querying"),
+        (name = "webhooks", description = "Event delivery to subscribed URLs"),
+        (name = "analytics", description = "Funnels, sessions, retention, distinct and top values, numeric statistics and other aggregates over stored events"),
+        (name = "alerts", description = "Threshold alert rules over stored events"),
+        (name = "rollups", description = "Pre-aggregated buckets maintained as events are stored"),
+        (name = "schemas", description = "Payload schemas inferred from stored events"),
+        (name = "observability", description = "Metrics and service introspection"),
+        (name = "admin", description = "Runtime settings, mounted with `server.admin_api`")
+    )
+)]
+pub struct ApiDoc;
+
+/// Registers the API key schemes accepted on event routes when `auth.api_keys` is set.
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        // ---
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-API-Key"))),
+        );
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+/// Documents the deprecated unversioned aliases of the `/v1/events` paths.
+struct LegacyAliases;
+
+impl Modify for LegacyAliases {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        // ---
+        let aliases: Vec<_> = openapi
+            .paths
+            .paths
+            .iter()
+            .filter_map(|(path, item)| {
+                let legacy = path
+                    .strip_prefix("/v1")
+                    .filter(|legacy| legacy.starts_with("/events"))?
+                    .to_string();
+                let mut item = item.clone();
+                for operation in [
+                    &mut item.get,
+                    &mut item.post,
+                    &mut item.put,
+                    &mut item.patch,
+                    &mut item.delete,
+                ] {
+                    operation.iter_mut().for_each(deprecate);
+                }
+                Some((legacy, item))
+            })
+            .collect();
+        openapi.paths.paths.extend(aliases);
+    }
+}
+
+/// Marks an alias operation deprecated under an operation id of its own.
+fn deprecate(operation: &mut Operation) {
+    // ---
+    operation.deprecated = Some(Deprecated::True);
+    operation.operation_id = operation
+        .operation_id
+        .as_ref()
+        .map(|id| format!("legacy_{}", id));
+}
+
+/// GET /openapi.json handler
+#[utoipa::path(
+    get,
+    path = "/openapi.json",
+    tag = "observability",
+    responses((status = 200, description = "This OpenAPI document", body = Object))
+)]
+pub(super) async fn openapi_json() -> impl IntoResponse {
+    // ---
+    Json(ApiDoc::openapi())
+}
+
+/// Routes serving the spec, plus the UI when `with_ui` is set.
+pub fn routes(with_ui: bool) -> Router<AppState> {
+    // ---
+    let router = Router::new().route("/openapi.json", get(openapi_json));
+    if with_ui {
+        // Swagger UI loading `/openapi.json`, served from assets embedded at build time
+        router.merge(SwaggerUi::new("/docs").config(Config::from("/openapi.json")))
+    } else {
+        router
+    }
+}
@@ -0,0 +1,137 @@
+//! Request id assignment and correlation.
+//!
+//! Every request gets an id: the caller's `X-Request-Id` when it is a
+//! short printable token, otherwise a fresh UUID. The id is stored in the
+//! request extensions (the request span records it, so every log line of
+//! the request carries it), echoed in the `X-Request-Id` response header
+//! and added as `request_id` to JSON error bodies.
+
+use axum::{
+    body::{to_bytes, Body, HttpBody},
+    extract::Request,
+    http::{header, HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// Header carrying the request id in both directions.
+pub static X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest caller-supplied id that is accepted.
+const MAX_INCOMING_LEN: usize = 128;
+
+/// Largest error body rewritten to include the id; larger ones pass through.
+const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
+
+/// Id correlating a request with its logs and response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+/// Middleware assigning a request id and attaching it to the response.
+pub async fn assign_request_id(mut request: Request, next: Next) -> Response {
+    // ---
+    let id = request
+        .headers()
+        .get(&X_REQUEST_ID)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| is_acceptable(value))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    request.extensions_mut().insert(RequestId(id.clone()));
+
+    let response = next.run(request).await;
+    let mut response = if response.status().is_client_error() || response.status().is_server_error()
+    {
+        add_id_to_error_body(response, &id).await
+    } else {
+        response
+    };
+
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(X_REQUEST_ID.clone(), value);
+    }
+    response
+}
+
+/// Whether a caller-supplied id is safe to log and echo.
+fn is_acceptable(id: &str) -> bool {
+    // ---
+    !id.is_empty() && id.len() <= MAX_INCOMING_LEN && id.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// Adds `request_id` to a JSON object body, or gives an empty body one.
+///
+/// Other bodies (plain text, non-object JSON, streams of unknown or large
+/// size) are returned unchanged.
+async fn add_id_to_error_body(response: Response, id: &str) -> Response {
+    // ---
+    let small = response
+        .body()
+        .size_hint()
+        .upper()
+        .is_some_and(|upper| upper <= MAX_ERROR_BODY_BYTES as u64);
+    if !small {
+        return response;
+    }
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    let (mut parts, body) = response.into_parts();
+
+    let bytes = match to_bytes(body, MAX_ERROR_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            tracing::warn!(%err, "Failed to buffer error response body");
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+
+    let rewritten = if bytes.is_empty() {
+        let reason = parts.status.canonical_reason().unwrap_or("error");
+        Some(json!({ "error": reason.to_lowercase(), "request_id": id }))
+    } else if is_json {
+        match serde_json::from_slice::<Value>(&bytes) {
+            Ok(Value::Object(mut object)) => {
+                object.insert("request_id".to_string(), Value::String(id.to_string()));
+                Some(Value::Object(object))
+            }
+            _ => None,
+        }
+    } else {
+        None
+    };
+
+    match rewritten {
+        Some(value) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            parts.headers.insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            );
+            Response::from_parts(parts, Body::from(value.to_string()))
+        }
+        None => Response::from_parts(parts, Body::from(bytes)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+
+    #[test]
+    fn incoming_ids_are_screened() {
+        // ---
+        assert!(is_acceptable("req-42"));
+        assert!(is_acceptable("4bf92f35-77b3-4da6-a3ce-929d0e0e4736"));
+        assert!(!is_acceptable(""));
+        assert!(!is_acceptable("has space"));
+        assert!(!is_acceptable("line\nbreak"));
+        assert!(!is_acceptable(&"x".repeat(MAX_INCOMING_LEN + 1)));
+    }
+}
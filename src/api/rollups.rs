@@ -0,0 +1,73 @@
+//! Rollup endpoint.
+//!
+//! `GET /v1/rollups/{name}` returns the buckets of a rollup configured in
+//! `[rollups]`, optionally limited to a time range. Buckets are maintained
+//! as events are stored, so this never scans raw events.
+
+use axum::{
+    extract::{rejection::QueryRejection, Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use super::analytics::check_range;
+use super::{json_error, AppState};
+use crate::domain::RollupReport;
+
+/// Query parameters for `GET /v1/rollups/{name}`
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RollupParams {
+    /// Only buckets ending after this RFC 3339 timestamp.
+    pub start: Option<DateTime<Utc>>,
+    /// Only buckets starting at or before this RFC 3339 timestamp.
+    pub end: Option<DateTime<Utc>>,
+}
+
+/// GET /v1/rollups/{name} handler
+#[utoipa::path(
+    get,
+    path = "/v1/rollups/{name}",
+    tag = "rollups",
+    security((), ("api_key" = []), ("bearer" = [])),
+    params(("name" = String, Path, description = "Rollup name from `[rollups]`"), RollupParams),
+    responses(
+        (status = 200, description = "The rollup's buckets overlapping the range, by start then group", body = RollupReport),
+        (status = 400, description = "Malformed query parameters"),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the read role"),
+        (status = 404, description = "No such rollup"),
+        (status = 422, description = "start is after end")
+    )
+)]
+#[tracing::instrument(name = "rollups.get", skip_all, fields(rollup = %name))]
+pub(super) async fn get_rollup(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    params: Result<Query<RollupParams>, QueryRejection>,
+) -> Response {
+    // ---
+    let params = match params {
+        Ok(Query(params)) => params,
+        Err(rejection) => return json_error(rejection.status(), rejection.body_text()),
+    };
+    if let Err(message) = check_range(params.start, params.end) {
+        return json_error(StatusCode::UNPROCESSABLE_ENTITY, message);
+    }
+
+    let report = state
+        .rollups
+        .as_ref()
+        .and_then(|rollups| rollups.report(&name, params.start, params.end));
+    match report {
+        Some(report) => Json(report).into_response(),
+        None => json_error(
+            StatusCode::NOT_FOUND,
+            format!("rollup '{}' not found", name),
+        ),
+    }
+}
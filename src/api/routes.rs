@@ -0,0 +1,122 @@
+//! Router assembly for all API versions.
+//!
+//! Each version module contributes relative routes sharing `AppState`;
+//! this file mounts them under their prefix and keeps the legacy
+//! unversioned event aliases pointing at the current stable version.
+
+use axum::{middleware, routing::get, Router};
+use std::sync::Arc;
+
+use super::{
+    admin, auth, deprecation, health, http_metrics, lifecycle, observability, openapi, request_id,
+    trace_context, v1, AppConfig, AppState,
+};
+use crate::domain::{EventObserverPtr, EventRepositoryPtr, MetricsPtr};
+use crate::repository::{instrument_repository, observe_events};
+use crate::webhooks::Webhooks;
+
+/// Every route and its methods, with paths in OpenAPI syntax.
+///
+/// Keep this in step with the router below: the OpenAPI test checks the
+/// document against it and probes each entry. `/admin/log-level` is only
+/// mounted with a `LogFilter`, and the optional Swagger UI at `/docs` is
+/// not listed.
+pub const API_ROUTES: &[(&str, &[&str])] = &[
+    ("/v1/events", &["get", "post"]),
+    ("/events", &["get", "post"]),
+    ("/v1/stats", &["get"]),
+    ("/v1/event-types", &["get"]),
+    ("/v1/alerts", &["get"]),
+    ("/v1/rollups/{name}", &["get"]),
+    ("/v1/schemas", &["get"]),
+    ("/v1/schemas/{event_type}", &["get"]),
+    ("/v1/analytics/funnel", &["post"]),
+    ("/v1/analytics/sessions", &["get"]),
+    ("/v1/analytics/retention", &["post"]),
+    ("/v1/analytics/distinct", &["get"]),
+    ("/v1/analytics/top", &["get"]),
+    ("/v1/analytics/statistics", &["get"]),
+    ("/v1/subscriptions", &["get", "post"]),
+    ("/v1/subscriptions/{id}", &["delete"]),
+    ("/v1/dead-letters", &["get"]),
+    ("/admin/log-level", &["get", "put"]),
+    ("/metrics", &["get"]),
+    ("/healthz", &["get"]),
+    ("/readyz", &["get"]),
+    ("/status", &["get"]),
+    ("/openapi.json", &["get"]),
+];
+
+/// Creates the router with event-related routes and metrics endpoint.
+pub fn event_routes(repo: EventRepositoryPtr, metrics: MetricsPtr) -> Router {
+    // ---
+    event_routes_with_config(repo, metrics, AppConfig::default())
+}
+
+/// Creates the router using an explicit API configuration.
+pub fn event_routes_with_config(
+    repo: EventRepositoryPtr,
+    metrics: MetricsPtr,
+    config: AppConfig,
+) -> Router {
+    // ---
+
+    let webhooks = config
+        .webhooks
+        .unwrap_or_else(|| Webhooks::in_memory(metrics.clone()));
+    let mut observers: Vec<EventObserverPtr> = vec![Arc::new(webhooks.clone())];
+    if let Some(alerts) = &config.alerts {
+        observers.push(Arc::new(alerts.clone()));
+    }
+    if let Some(rollups) = &config.rollups {
+        observers.push(Arc::new(rollups.clone()));
+    }
+    if let Some(schemas) = &config.schemas {
+        observers.push(Arc::new(schemas.clone()));
+    }
+    let state = AppState {
+        repo: instrument_repository(observe_events(repo, observers), metrics.clone()),
+        metrics,
+        live: config.live.clone(),
+        lifecycle: config.lifecycle.clone(),
+        webhooks,
+        alerts: config.alerts,
+        rollups: config.rollups,
+        schemas: config.schemas,
+    };
+
+    // Unversioned aliases of the stable event routes, flagged as deprecated
+    let legacy = v1::event_routes().layer(middleware::from_fn_with_state(
+        config.deprecation,
+        deprecation::add_deprecation_headers,
+    ));
+
+    // Everything but the probes, metrics and docs requires an API key when any are configured
+    let mut protected = Router::new().nest("/v1", v1::routes()).merge(legacy);
+    if let Some(log_filter) = config.log_filter {
+        protected = protected.merge(admin::routes(log_filter));
+    }
+    let protected = protected.layer(middleware::from_fn_with_state(
+        config.live,
+        auth::require_api_key,
+    ));
+
+    Router::new()
+        .merge(protected)
+        .route("/metrics", get(observability::metrics_handler))
+        .route("/healthz", get(health::healthz))
+        .route("/readyz", get(health::readyz))
+        .route("/status", get(health::status))
+        .merge(openapi::routes(config.openapi_ui))
+        .layer(middleware::from_fn_with_state(
+            state.metrics.clone(),
+            http_metrics::record_http_metrics,
+        ))
+        .layer(middleware::from_fn(trace_context::propagate_trace_context))
+        .layer(middleware::from_fn(request_id::assign_request_id))
+        .layer(middleware::from_fn_with_state(
+            config.lifecycle,
+            lifecycle::track_in_flight,
+        ))
+        .with_state(state)
+}
@@ -0,0 +1,73 @@
+//! Inferred payload schema endpoints.
+//!
+//! `GET /v1/schemas` lists the payload schema inferred for every event type
+//! since startup and `GET /v1/schemas/{event_type}` returns one. Schemas are
+//! learnt as events are stored; with `[schemas]` disabled there are none.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use super::{json_error, AppState};
+use crate::domain::PayloadSchema;
+
+/// GET /v1/schemas handler
+#[utoipa::path(
+    get,
+    path = "/v1/schemas",
+    tag = "schemas",
+    security((), ("api_key" = []), ("bearer" = [])),
+    responses(
+        (status = 200, description = "The inferred schema of every event type, ordered by type", body = [PayloadSchema]),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the read role")
+    )
+)]
+#[tracing::instrument(name = "schemas.list", skip_all)]
+pub(super) async fn list_schemas(State(state): State<AppState>) -> Response {
+    // ---
+
+    let schemas: Vec<PayloadSchema> = state
+        .schemas
+        .as_ref()
+        .map(|schemas| schemas.schemas())
+        .unwrap_or_default();
+    Json(schemas).into_response()
+}
+
+/// GET /v1/schemas/{event_type} handler
+#[utoipa::path(
+    get,
+    path = "/v1/schemas/{event_type}",
+    tag = "schemas",
+    security((), ("api_key" = []), ("bearer" = [])),
+    params(("event_type" = String, Path, description = "Event type whose schema is returned")),
+    responses(
+        (status = 200, description = "The event type's inferred schema", body = PayloadSchema),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the read role"),
+        (status = 404, description = "No event of this type was stored since startup")
+    )
+)]
+#[tracing::instrument(name = "schemas.get", skip_all, fields(event_type = %event_type))]
+pub(super) async fn get_schema(
+    State(state): State<AppState>,
+    Path(event_type): Path<String>,
+) -> Response {
+    // ---
+
+    let schema = state
+        .schemas
+        .as_ref()
+        .and_then(|schemas| schemas.schema(&event_type));
+    match schema {
+        Some(schema) => Json(schema).into_response(),
+        None => json_error(
+            StatusCode::NOT_FOUND,
+            format!("no schema for event type '{}'", event_type),
+        ),
+    }
+}
@@ -0,0 +1,21 @@
+//! Shared state handed to every API version's handlers.
+
+use super::{Lifecycle, LiveConfig};
+use crate::alerts::Alerts;
+use crate::domain::{EventRepositoryPtr, MetricsPtr};
+use crate::rollups::Rollups;
+use crate::schemas::Schemas;
+use crate::webhooks::Webhooks;
+
+/// Application state containing shared resources
+#[derive(Clone)]
+pub struct AppState {
+    pub repo: EventRepositoryPtr,
+    pub metrics: MetricsPtr,
+    pub live: LiveConfig,
+    pub lifecycle: Lifecycle,
+    pub webhooks: Webhooks,
+    pub alerts: Option<Alerts>,
+    pub rollups: Option<Rollups>,
+    pub schemas: Option<Schemas>,
+}
@@ -0,0 +1,36 @@
+//! Repository statistics endpoint.
+//!
+//! `GET /v1/stats` summarises the stored events on demand: counts and time
+//! range per event type, totals and approximate size. The same numbers
+//! are published as metrics gauges by a background refresh task.
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+
+use super::AppState;
+use crate::domain::RepositoryStats;
+
+/// GET /v1/stats handler
+#[utoipa::path(
+    get,
+    path = "/v1/stats",
+    tag = "observability",
+    security((), ("api_key" = []), ("bearer" = [])),
+    responses(
+        (status = 200, description = "Summary of the stored events", body = RepositoryStats),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the read role"),
+        (status = 500, description = "Storage failure", body = String)
+    )
+)]
+#[tracing::instrument(name = "stats.get", skip_all)]
+pub(super) async fn get_stats(State(state): State<AppState>) -> impl IntoResponse {
+    // ---
+
+    match state.repo.stats().await {
+        Ok(stats) => Json(stats).into_response(),
+        Err(e) => {
+            tracing::error!(?e, "Failed to compute repository stats");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
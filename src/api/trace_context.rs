@@ -0,0 +1,123 @@
+//! W3C trace context propagation.
+//!
+//! Applied to the whole router: every request runs inside an `http.request`
+//! span, tagged with its request id, whose parent is taken from an incoming
+//! `traceparent` header. The resulting trace and span IDs are put into the
+//! request extensions as a `TraceContext`, so handlers can attach them to
+//! what they store.
+//!
+//! When no OpenTelemetry exporter is configured the span carries no IDs of
+//! its own; the caller's `traceparent` is then passed through unchanged.
+
+use axum::{
+    extract::{MatchedPath, Request},
+    http::HeaderMap,
+    middleware::Next,
+    response::Response,
+};
+use opentelemetry::propagation::{Extractor, TextMapPropagator};
+use opentelemetry::trace::TraceContextExt;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use tracing::{field::Empty, Instrument};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use super::http_metrics::UNMATCHED_ROUTE;
+use super::request_id::RequestId;
+use crate::domain::TraceContext;
+
+/// Middleware running each request in a span linked to the caller's trace.
+pub async fn propagate_trace_context(mut request: Request, next: Next) -> Response {
+    // ---
+    let parent = TraceContextPropagator::new().extract(&HeaderExtractor(request.headers()));
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+    let method = request.method().clone();
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.clone())
+        .unwrap_or_default();
+
+    let span = tracing::info_span!(
+        "http.request",
+        otel.name = %format!("{} {}", method, route),
+        otel.kind = "server",
+        http.request.method = %method,
+        http.route = %route,
+        http.response.status_code = Empty,
+        request_id = %request_id,
+    );
+    // Fails only when no OpenTelemetry layer is installed; the fallback below covers that
+    let _ = span.set_parent(parent.clone());
+
+    if let Some(trace) = trace_context(&span.context()).or_else(|| trace_context(&parent)) {
+        request.extensions_mut().insert(trace);
+    }
+
+    let response = next.run(request).instrument(span.clone()).await;
+    span.record("http.response.status_code", response.status().as_u16());
+    response
+}
+
+/// IDs of the span in `cx`, if it is a valid one.
+fn trace_context(cx: &opentelemetry::Context) -> Option<TraceContext> {
+    // ---
+    let span = cx.span();
+    let span_context = span.span_context();
+    span_context.is_valid().then(|| TraceContext {
+        trace_id: span_context.trace_id().to_string(),
+        span_id: span_context.span_id().to_string(),
+    })
+}
+
+/// Reads propagation headers from an HTTP request.
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+
+    #[test]
+    fn traceparent_header_is_extracted() {
+        // ---
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "traceparent",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+                .parse()
+                .unwrap(),
+        );
+
+        let parent = TraceContextPropagator::new().extract(&HeaderExtractor(&headers));
+
+        assert_eq!(
+            trace_context(&parent),
+            Some(TraceContext {
+                trace_id: "4bf92f3577b34da6a3ce929d0e0e4736".to_string(),
+                span_id: "00f067aa0ba902b7".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn missing_header_yields_no_context() {
+        // ---
+        let parent = TraceContextPropagator::new().extract(&HeaderExtractor(&HeaderMap::new()));
+        assert_eq!(trace_context(&parent), None);
+    }
+}
@@ -0,0 +1,69 @@
+//! Wire types for the v1 API.
+//!
+//! These mirror the domain types today but are deliberately separate so the
+//! domain `Event` can evolve without changing what v1 clients receive.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::domain::{Event, FieldViolation, TraceContext};
+
+/// Request body for `POST /v1/events`
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct EventInput {
+    /// The type/category of the event (e.g., "user_signup").
+    pub event_type: String,
+    /// When the event occurred, as an RFC 3339 timestamp.
+    pub timestamp: DateTime<Utc>,
+    /// Arbitrary structured payload data.
+    #[schema(value_type = Object)]
+    pub payload: serde_json::Value,
+}
+
+/// Query parameters for `GET /v1/events`
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GetEventsQuery {
+    /// Only return events of this type.
+    #[serde(rename = "type")]
+    pub event_type: Option<String>,
+    /// Inclusive lower bound, RFC 3339 timestamp.
+    pub start: Option<String>,
+    /// Inclusive upper bound, RFC 3339 timestamp.
+    pub end: Option<String>,
+}
+
+/// A stored event as returned by `GET /v1/events`.
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = v1::Event)]
+pub struct EventResponse {
+    pub id: Uuid,
+    pub event_type: String,
+    pub timestamp: DateTime<Utc>,
+    #[schema(value_type = Object)]
+    pub payload: serde_json::Value,
+    /// Trace of the request that submitted the event, when it was traced.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace: Option<TraceContext>,
+}
+
+impl From<Event> for EventResponse {
+    fn from(event: Event) -> Self {
+        Self {
+            id: event.id,
+            event_type: event.event_type,
+            timestamp: event.timestamp,
+            payload: event.payload,
+            trace: event.trace,
+        }
+    }
+}
+
+/// Error body returned when a submitted event is rejected.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ValidationErrorResponse {
+    pub error: String,
+    pub violations: Vec<FieldViolation>,
+}
@@ -0,0 +1,188 @@
+//! HTTP handlers for the v1 event endpoints.
+//!
+//! This file defines handlers for submitting and querying events via Axum.
+
+use axum::{
+    extract::{rejection::JsonRejection, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use chrono::Utc;
+use tracing::info;
+use uuid::Uuid;
+
+use super::dto::{EventInput, EventResponse, GetEventsQuery, ValidationErrorResponse};
+use crate::api::AppState;
+use crate::domain::{Event, EventQuery, FieldViolation, TraceContext};
+
+/// POST /events handler
+#[utoipa::path(
+    post,
+    path = "/v1/events",
+    tag = "events",
+    security((), ("api_key" = []), ("bearer" = [])),
+    request_body = EventInput,
+    responses(
+        (status = 201, description = "Event stored"),
+        (status = 400, description = "Malformed request body", body = ValidationErrorResponse),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the ingest role"),
+        (status = 422, description = "Event failed validation", body = ValidationErrorResponse),
+        (status = 500, description = "Storage failure")
+    )
+)]
+#[tracing::instrument(name = "events.submit", skip_all)]
+pub(super) async fn submit_event(
+    State(state): State<AppState>,
+    trace: Option<Extension<TraceContext>>,
+    input: Result<Json<EventInput>, JsonRejection>,
+) -> impl IntoResponse {
+    // ---
+
+    let input = match input {
+        Ok(Json(input)) => input,
+        Err(rejection) => {
+            tracing::warn!(error = %rejection.body_text(), "Malformed event submission");
+            let status = rejection.status();
+            state.metrics.record_validation_failure("malformed_body");
+            let body = ValidationErrorResponse {
+                error: "malformed request body".to_string(),
+                violations: vec![FieldViolation {
+                    field: "body".to_string(),
+                    rule: "malformed_body",
+                    message: rejection.body_text(),
+                }],
+            };
+            return (status, Json(body)).into_response();
+        }
+    };
+
+    let event = Event {
+        id: Uuid::new_v4(),
+        event_type: input.event_type.clone(),
+        timestamp: input.timestamp,
+        payload: input.payload,
+        trace: trace.map(|Extension(trace)| trace),
+    };
+
+    let violations = state.live.validation().validate(&event, Utc::now());
+    if !violations.is_empty() {
+        tracing::warn!(
+            event_type = %input.event_type,
+            violation_count = violations.len(),
+            "Rejected invalid event"
+        );
+        for violation in &violations {
+            state.metrics.record_validation_failure(violation.rule);
+        }
+        let body = ValidationErrorResponse {
+            error: "event failed validation".to_string(),
+            violations,
+        };
+        return (StatusCode::UNPROCESSABLE_ENTITY, Json(body)).into_response();
+    }
+
+    tracing::info!(
+        event_type = %input.event_type,
+        event_id = %event.id,
+        "Processing event submission"
+    );
+
+    match state.repo.store_event(event).await {
+        Ok(_) => {
+            info!(
+                event_type = %input.event_type,
+                "Event stored successfully"
+            );
+            state.metrics.record_event_created(&input.event_type);
+            StatusCode::CREATED.into_response()
+        }
+        Err(err) => {
+            tracing::error!(
+                ?err,
+                event_type = %input.event_type,
+                "Failed to store event"
+            );
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// GET /events handler
+#[utoipa::path(
+    get,
+    path = "/v1/events",
+    tag = "events",
+    security((), ("api_key" = []), ("bearer" = [])),
+    params(GetEventsQuery),
+    responses(
+        (status = 200, description = "Matching events", body = Vec<EventResponse>),
+        (status = 400, description = "Invalid query parameters", body = String),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the read role"),
+        (status = 500, description = "Storage failure", body = String)
+    )
+)]
+#[tracing::instrument(name = "events.query", skip_all)]
+pub(super) async fn get_events(
+    State(state): State<AppState>,
+    Query(params): Query<GetEventsQuery>,
+) -> impl IntoResponse {
+    // ---
+
+    tracing::debug!(
+        event_type = ?params.event_type,
+        start_time = ?params.start,
+        end_time = ?params.end,
+        "Processing events query"
+    );
+
+    let query = match parse_query(params) {
+        Ok(q) => q,
+        Err(e) => {
+            tracing::warn!(?e, "Invalid query parameters");
+            return (StatusCode::BAD_REQUEST, e.to_string()).into_response();
+        }
+    };
+
+    match state.repo.find_events(query).await {
+        Ok(events) => {
+            tracing::info!(event_count = events.len(), "Successfully retrieved events");
+            let events: Vec<EventResponse> = events.into_iter().map(Into::into).collect();
+            Json(events).into_response()
+        }
+        Err(e) => {
+            tracing::error!(?e, "Failed to retrieve events");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+/// Parse query parameters into EventQuery
+fn parse_query(params: GetEventsQuery) -> anyhow::Result<EventQuery> {
+    // ---
+
+    let start = match params.start {
+        Some(s) => Some(chrono::DateTime::parse_from_rfc3339(&s)?.with_timezone(&chrono::Utc)),
+        None => None,
+    };
+
+    let end = match params.end {
+        Some(e) => Some(chrono::DateTime::parse_from_rfc3339(&e)?.with_timezone(&chrono::Utc)),
+        None => None,
+    };
+
+    // Validate that start is before end
+    if let (Some(start_time), Some(end_time)) = (&start, &end) {
+        if start_time >= end_time {
+            return Err(anyhow::anyhow!("Start time must be before end time"));
+        }
+    }
+
+    Ok(EventQuery {
+        event_type: params.event_type,
+        start,
+        end,
+    })
+}
@@ -0,0 +1,57 @@
+//! Version 1 of the HTTP API.
+//!
+//! Routes are relative; the caller mounts them under `/v1` and keeps
+//! deprecated unversioned aliases of the event routes only.
+
+mod dto;
+mod events;
+
+use axum::{
+    middleware,
+    routing::{get, post},
+    Router,
+};
+
+use super::{alerts, analytics, auth, event_types, rollups, schemas, stats, webhooks, AppState};
+
+// Exports used by the OpenAPI document
+pub(super) use dto::{EventInput, EventResponse, ValidationErrorResponse};
+pub(super) use events::{__path_get_events, __path_submit_event};
+
+/// Routes served by v1, relative to the mount point.
+pub fn routes() -> Router<AppState> {
+    // ---
+    let read = Router::new()
+        .route("/stats", get(stats::get_stats))
+        .route("/event-types", get(event_types::list_event_types))
+        .route("/alerts", get(alerts::list_alerts))
+        .route("/rollups/:name", get(rollups::get_rollup))
+        .route("/schemas", get(schemas::list_schemas))
+        .route("/schemas/:event_type", get(schemas::get_schema))
+        .route_layer(middleware::from_fn_with_state(
+            auth::READ,
+            auth::require_role,
+        ));
+    event_routes()
+        .merge(read)
+        .merge(analytics::routes())
+        .merge(webhooks::routes())
+}
+
+/// The event routes, which also back the unversioned aliases.
+pub fn event_routes() -> Router<AppState> {
+    // ---
+    let ingest = Router::new()
+        .route("/events", post(events::submit_event))
+        .route_layer(middleware::from_fn_with_state(
+            auth::INGEST,
+            auth::require_role,
+        ));
+    let read = Router::new()
+        .route("/events", get(events::get_events))
+        .route_layer(middleware::from_fn_with_state(
+            auth::READ,
+            auth::require_role,
+        ));
+    ingest.merge(read)
+}
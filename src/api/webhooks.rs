@@ -0,0 +1,267 @@
+//! Webhook subscription and dead-letter endpoints.
+//!
+//! Subscribing hands out stored events, so listing subscriptions and dead
+//! letters requires the `read` role for certificate-authenticated clients,
+//! like `GET /v1/events`. Creating or deleting a subscription changes
+//! server state and requires the `ingest` or `admin` role instead.
+//! The subscription secret is returned only in the `POST` response.
+
+use axum::{
+    extract::{rejection::JsonRejection, Path, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{auth, json_error, AppState};
+use crate::domain::{DeadLetter, Subscription};
+use crate::webhooks::Webhooks;
+
+/// Request body for `POST /v1/subscriptions`
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SubscriptionInput {
+    /// Events of this type are delivered.
+    pub event_type: String,
+    /// `http://` or `https://` URL receiving the deliveries.
+    pub url: String,
+    /// Top-level payload fields an event must carry with exactly these values.
+    #[schema(value_type = Option<Object>)]
+    pub filter: Option<Value>,
+    /// HMAC key for `X-Argus-Signature`; generated when omitted.
+    pub secret: Option<String>,
+}
+
+/// A webhook subscription.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SubscriptionResponse {
+    pub id: Uuid,
+    pub event_type: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub filter: Option<Map<String, Value>>,
+    pub created_at: DateTime<Utc>,
+    /// Signing secret; only included when the subscription is created.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+impl From<Subscription> for SubscriptionResponse {
+    fn from(subscription: Subscription) -> Self {
+        Self {
+            id: subscription.id,
+            event_type: subscription.events.event_type,
+            url: subscription.url,
+            filter: subscription.events.filter,
+            created_at: subscription.created_at,
+            secret: None,
+        }
+    }
+}
+
+/// Webhook routes, relative to the root.
+pub fn routes() -> Router<AppState> {
+    // ---
+    let write = Router::new()
+        .route("/subscriptions", post(create_subscription))
+        .route("/subscriptions/:id", delete(delete_subscription))
+        .route_layer(middleware::from_fn_with_state(
+            auth::WRITE,
+            auth::require_role,
+        ));
+    let read = Router::new()
+        .route("/subscriptions", get(list_subscriptions))
+        .route("/dead-letters", get(list_dead_letters))
+        .route_layer(middleware::from_fn_with_state(
+            auth::READ,
+            auth::require_role,
+        ));
+    write.merge(read)
+}
+
+/// Checks a subscription request, returning the filter as an object.
+fn validate(
+    input: &SubscriptionInput,
+    webhooks: &Webhooks,
+) -> Result<Option<Map<String, Value>>, String> {
+    // ---
+    if input.event_type.trim().is_empty() {
+        return Err("event_type must not be empty".to_string());
+    }
+    match reqwest::Url::parse(&input.url) {
+        Ok(url) if ["http", "https"].contains(&url.scheme()) => webhooks.check_target(&url)?,
+        _ => return Err(format!("url '{}' is not an http(s) URL", input.url)),
+    }
+    if input.secret.as_deref().is_some_and(|s| s.is_empty()) {
+        return Err("secret must not be empty when given".to_string());
+    }
+    match &input.filter {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Object(filter)) => Ok(Some(filter.clone())),
+        Some(_) => Err("filter must be a JSON object".to_string()),
+    }
+}
+
+/// POST /v1/subscriptions handler
+#[utoipa::path(
+    post,
+    path = "/v1/subscriptions",
+    tag = "webhooks",
+    security((), ("api_key" = []), ("bearer" = [])),
+    request_body = SubscriptionInput,
+    responses(
+        (status = 201, description = "Subscription created; the response carries its secret", body = SubscriptionResponse),
+        (status = 400, description = "Malformed request body"),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the ingest and admin roles"),
+        (status = 422, description = "Invalid event type, URL or filter, or a private URL"),
+        (status = 500, description = "Subscription could not be saved")
+    )
+)]
+pub(super) async fn create_subscription(
+    State(state): State<AppState>,
+    input: Result<Json<SubscriptionInput>, JsonRejection>,
+) -> Response {
+    // ---
+    let input = match input {
+        Ok(Json(input)) => input,
+        Err(rejection) => return json_error(rejection.status(), rejection.body_text()),
+    };
+    let filter = match validate(&input, &state.webhooks) {
+        Ok(filter) => filter,
+        Err(message) => return json_error(StatusCode::UNPROCESSABLE_ENTITY, message),
+    };
+
+    match state
+        .webhooks
+        .subscribe(input.event_type, filter, input.url, input.secret)
+        .await
+    {
+        Ok(subscription) => {
+            let secret = subscription.secret.clone();
+            let body = SubscriptionResponse {
+                secret: Some(secret),
+                ..subscription.into()
+            };
+            (StatusCode::CREATED, Json(body)).into_response()
+        }
+        Err(err) => {
+            tracing::error!(?err, "Failed to create webhook subscription");
+            json_error(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+        }
+    }
+}
+
+/// GET /v1/subscriptions handler
+#[utoipa::path(
+    get,
+    path = "/v1/subscriptions",
+    tag = "webhooks",
+    security((), ("api_key" = []), ("bearer" = [])),
+    responses(
+        (status = 200, description = "All subscriptions, without their secrets", body = Vec<SubscriptionResponse>),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the read role")
+    )
+)]
+pub(super) async fn list_subscriptions(State(state): State<AppState>) -> Response {
+    // ---
+    let subscriptions: Vec<SubscriptionResponse> = state
+        .webhooks
+        .subscriptions()
+        .into_iter()
+        .map(Into::into)
+        .collect();
+    Json(subscriptions).into_response()
+}
+
+/// DELETE /v1/subscriptions/{id} handler
+#[utoipa::path(
+    delete,
+    path = "/v1/subscriptions/{id}",
+    tag = "webhooks",
+    security((), ("api_key" = []), ("bearer" = [])),
+    params(("id" = Uuid, Path, description = "Subscription id")),
+    responses(
+        (status = 204, description = "Subscription and its queued deliveries removed"),
+        (status = 400, description = "Malformed id"),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the ingest and admin roles"),
+        (status = 404, description = "No such subscription"),
+        (status = 500, description = "Subscription could not be removed")
+    )
+)]
+pub(super) async fn delete_subscription(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    // ---
+    match state.webhooks.unsubscribe(id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => json_error(StatusCode::NOT_FOUND, "subscription not found"),
+        Err(err) => {
+            tracing::error!(?err, "Failed to remove webhook subscription");
+            json_error(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+        }
+    }
+}
+
+/// GET /v1/dead-letters handler
+#[utoipa::path(
+    get,
+    path = "/v1/dead-letters",
+    tag = "webhooks",
+    security((), ("api_key" = []), ("bearer" = [])),
+    responses(
+        (status = 200, description = "Deliveries that were given up on, oldest first", body = Vec<DeadLetter>),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 403, description = "Client certificate lacks the read role")
+    )
+)]
+pub(super) async fn list_dead_letters(State(state): State<AppState>) -> Response {
+    // ---
+    Json(state.webhooks.dead_letters()).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+    use serde_json::json;
+
+    fn input(url: &str, filter: Option<Value>) -> SubscriptionInput {
+        // ---
+        SubscriptionInput {
+            event_type: "purchase".to_string(),
+            url: url.to_string(),
+            filter,
+            secret: None,
+        }
+    }
+
+    #[test]
+    fn subscriptions_are_validated() {
+        // ---
+        let webhooks =
+            Webhooks::in_memory(crate::infrastructure::create_metrics_for("noop").unwrap());
+        let check = |url, filter| validate(&input(url, filter), &webhooks);
+        assert!(check("https://hooks.example.com/argus", None).is_ok());
+        assert!(check("ftp://hooks.example.com", None).is_err());
+        assert!(check("not a url", None).is_err());
+        assert!(check("http://localhost/hook", None).is_err());
+        assert!(check("https://hooks.example.com/argus", Some(json!([1]))).is_err());
+        let filter = check(
+            "https://hooks.example.com/argus",
+            Some(json!({ "plan": "pro" })),
+        );
+        assert_eq!(filter.unwrap().unwrap()["plan"], "pro");
+    }
+}
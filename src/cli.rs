@@ -1,16 +1,265 @@
 //! Command-line argument parser for Argus Events server.
+//!
+//! Every setting flag is optional: when neither the flag nor its `ARGUS_*`
+//! environment variable is given, the value comes from the `--config` file,
+//! falling back to the built-in default (see `config::Settings`).
 
+use anyhow::Result;
+use chrono::{DateTime, Utc};
 use clap::Parser;
+use std::path::PathBuf;
+
+use crate::config::Settings;
 
 /// Command-line options for configuring the server.
-#[derive(Debug, Parser)]
+#[derive(Debug, Clone, Default, Parser)]
 #[command(author, version, about)]
 pub struct Args {
-    /// Address to bind to (host:port). Can also be set via ARGUS_ENDPOINT.
-    #[arg(long, env = "ARGUS_ENDPOINT", default_value = "0.0.0.0:3000")]
-    pub endpoint: String,
+    /// TOML configuration file. Can also be set via ARGUS_CONFIG.
+    #[arg(long, env = "ARGUS_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// Address to bind to (host:port) [default: 0.0.0.0:3000]. Can also be set via ARGUS_ENDPOINT.
+    #[arg(long, env = "ARGUS_ENDPOINT")]
+    pub endpoint: Option<String>,
+
+    /// Storage backend to use (memory, noop) [default: memory]. Can also be set via ARGUS_REPOSITORY.
+    #[arg(long, env = "ARGUS_REPOSITORY")]
+    pub repository: Option<String>,
+
+    /// Metrics backend to use (prom, statsd, push, noop) [default: noop]. Can also be set via ARGUS_METRICS_TYPE.
+    #[arg(long, env = "ARGUS_METRICS_TYPE")]
+    pub metrics: Option<String>,
+
+    /// StatsD agent for --metrics statsd [default: 127.0.0.1:8125].
+    /// Can also be set via ARGUS_STATSD_ADDRESS.
+    #[arg(long, env = "ARGUS_STATSD_ADDRESS")]
+    pub statsd_address: Option<String>,
+
+    /// Push gateway URL for --metrics push. Can also be set via ARGUS_PUSH_URL.
+    #[arg(long, env = "ARGUS_PUSH_URL")]
+    pub push_url: Option<String>,
+
+    /// Log filter directive [default: info]. Can also be set via RUST_LOG.
+    #[arg(long, env = "RUST_LOG")]
+    pub log_level: Option<String>,
+
+    /// Log output format (text, json) [default: text]. Can also be set via ARGUS_LOG_FORMAT.
+    #[arg(long, env = "ARGUS_LOG_FORMAT")]
+    pub log_format: Option<String>,
+
+    /// Seconds to wait for in-flight requests after a shutdown signal before giving up
+    /// [default: 30]. Can also be set via ARGUS_SHUTDOWN_TIMEOUT_SECS.
+    #[arg(long, env = "ARGUS_SHUTDOWN_TIMEOUT_SECS")]
+    pub shutdown_timeout_secs: Option<u64>,
+
+    /// Seconds to keep accepting requests after a shutdown signal while readiness reports
+    /// draining [default: 5]. Can also be set via ARGUS_DRAIN_DELAY_SECS.
+    #[arg(long, env = "ARGUS_DRAIN_DELAY_SECS")]
+    pub drain_delay_secs: Option<u64>,
+
+    /// Serve a Swagger UI at /docs. Can also be set via ARGUS_API_DOCS_UI.
+    #[arg(long, env = "ARGUS_API_DOCS_UI", num_args = 0..=1, default_missing_value = "true")]
+    pub api_docs_ui: Option<bool>,
+
+    /// Serve the /admin endpoints (runtime log filter). Can also be set via ARGUS_ADMIN_API.
+    #[arg(long, env = "ARGUS_ADMIN_API", num_args = 0..=1, default_missing_value = "true")]
+    pub admin_api: Option<bool>,
+
+    /// RFC 3339 date after which the unversioned /events aliases will be removed,
+    /// advertised in the Sunset header. Can also be set via ARGUS_LEGACY_SUNSET.
+    #[arg(long, env = "ARGUS_LEGACY_SUNSET")]
+    pub legacy_sunset: Option<DateTime<Utc>>,
+
+    /// Maximum length of an event_type [default: 128]. Can also be set via ARGUS_MAX_EVENT_TYPE_LEN.
+    #[arg(long, env = "ARGUS_MAX_EVENT_TYPE_LEN")]
+    pub max_event_type_len: Option<usize>,
+
+    /// Punctuation allowed in event_type besides ASCII letters and digits [default: _-.:].
+    /// Can also be set via ARGUS_EVENT_TYPE_CHARS.
+    #[arg(long, env = "ARGUS_EVENT_TYPE_CHARS")]
+    pub event_type_chars: Option<String>,
+
+    /// Comma-separated allow-list of event types; empty accepts any type.
+    /// Can also be set via ARGUS_ALLOWED_EVENT_TYPES.
+    #[arg(long, env = "ARGUS_ALLOWED_EVENT_TYPES", value_delimiter = ',')]
+    pub allowed_event_types: Option<Vec<String>>,
+
+    /// Seconds a timestamp may be ahead of server time [default: 300].
+    /// Can also be set via ARGUS_MAX_FUTURE_SKEW_SECS.
+    #[arg(long, env = "ARGUS_MAX_FUTURE_SKEW_SECS")]
+    pub max_future_skew_secs: Option<i64>,
+
+    /// Seconds a timestamp may be behind server time (unbounded if unset).
+    /// Can also be set via ARGUS_MAX_PAST_SKEW_SECS.
+    #[arg(long, env = "ARGUS_MAX_PAST_SKEW_SECS")]
+    pub max_past_skew_secs: Option<i64>,
+
+    /// Maximum serialized payload size in bytes [default: 65536]. Can also be set via ARGUS_MAX_PAYLOAD_BYTES.
+    #[arg(long, env = "ARGUS_MAX_PAYLOAD_BYTES")]
+    pub max_payload_bytes: Option<usize>,
+
+    /// Maximum payload nesting depth [default: 16]. Can also be set via ARGUS_MAX_PAYLOAD_DEPTH.
+    #[arg(long, env = "ARGUS_MAX_PAYLOAD_DEPTH")]
+    pub max_payload_depth: Option<usize>,
+
+    /// Maximum total number of payload object keys [default: 512]. Can also be set via ARGUS_MAX_PAYLOAD_KEYS.
+    #[arg(long, env = "ARGUS_MAX_PAYLOAD_KEYS")]
+    pub max_payload_keys: Option<usize>,
+
+    /// Purge events older than this many seconds (kept forever if unset).
+    /// Can also be set via ARGUS_RETENTION_MAX_AGE_SECS.
+    #[arg(long, env = "ARGUS_RETENTION_MAX_AGE_SECS")]
+    pub retention_max_age_secs: Option<u64>,
+
+    /// Comma-separated API keys required on event routes; empty disables auth.
+    /// Can also be set via ARGUS_API_KEYS.
+    #[arg(
+        long,
+        env = "ARGUS_API_KEYS",
+        value_delimiter = ',',
+        hide_env_values = true
+    )]
+    pub api_keys: Option<Vec<String>>,
+
+    /// PEM certificate chain; enables HTTPS together with --tls-key.
+    /// Can also be set via ARGUS_TLS_CERT.
+    #[arg(long, env = "ARGUS_TLS_CERT")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// PEM private key for --tls-cert. Can also be set via ARGUS_TLS_KEY.
+    #[arg(long, env = "ARGUS_TLS_KEY")]
+    pub tls_key: Option<PathBuf>,
+
+    /// PEM CA bundle used to verify client certificates. Can also be set via ARGUS_TLS_CLIENT_CA.
+    #[arg(long, env = "ARGUS_TLS_CLIENT_CA")]
+    pub tls_client_ca: Option<PathBuf>,
+
+    /// Require a client certificate (mutual TLS). Can also be set via ARGUS_TLS_REQUIRE_CLIENT_CERT.
+    #[arg(long, env = "ARGUS_TLS_REQUIRE_CLIENT_CERT", num_args = 0..=1, default_missing_value = "true")]
+    pub tls_require_client_cert: Option<bool>,
+
+    /// OTLP collector base URL; enables trace export. Can also be set via ARGUS_OTLP_ENDPOINT.
+    #[arg(long, env = "ARGUS_OTLP_ENDPOINT")]
+    pub otlp_endpoint: Option<String>,
+
+    /// OTLP protocol (grpc, http/protobuf, http/json) [default: http/protobuf].
+    /// Can also be set via ARGUS_OTLP_PROTOCOL.
+    #[arg(long, env = "ARGUS_OTLP_PROTOCOL")]
+    pub otlp_protocol: Option<String>,
+}
+
+impl Args {
+    // ---
+
+    /// Resolves the effective settings: defaults, then the config file,
+    /// then environment/CLI overrides, validated as a whole.
+    pub fn resolve_settings(&self) -> Result<Settings> {
+        // ---
+        let mut settings = match &self.config {
+            Some(path) => Settings::from_file(path)?,
+            None => Settings::default(),
+        };
+        self.apply_overrides(&mut settings);
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    /// Overwrites every setting that was given on the command line or environment.
+    pub fn apply_overrides(&self, settings: &mut Settings) {
+        // ---
+        fn set<T: Clone>(target: &mut T, value: &Option<T>) {
+            if let Some(value) = value {
+                *target = value.clone();
+            }
+        }
+
+        set(&mut settings.server.endpoint, &self.endpoint);
+        set(
+            &mut settings.server.shutdown_timeout_secs,
+            &self.shutdown_timeout_secs,
+        );
+        set(
+            &mut settings.server.drain_delay_secs,
+            &self.drain_delay_secs,
+        );
+        set(&mut settings.server.api_docs_ui, &self.api_docs_ui);
+        set(&mut settings.server.admin_api, &self.admin_api);
+        if self.legacy_sunset.is_some() {
+            settings.server.legacy_sunset = self.legacy_sunset;
+        }
+        set(&mut settings.logging.level, &self.log_level);
+        set(&mut settings.logging.format, &self.log_format);
+        set(&mut settings.repository.kind, &self.repository);
+        set(&mut settings.metrics.kind, &self.metrics);
+        set(&mut settings.metrics.statsd.address, &self.statsd_address);
+        set(&mut settings.metrics.push.url, &self.push_url);
+
+        let limits = &mut settings.limits;
+        set(&mut limits.max_event_type_len, &self.max_event_type_len);
+        set(&mut limits.event_type_chars, &self.event_type_chars);
+        set(&mut limits.allowed_event_types, &self.allowed_event_types);
+        set(&mut limits.max_future_skew_secs, &self.max_future_skew_secs);
+        if self.max_past_skew_secs.is_some() {
+            limits.max_past_skew_secs = self.max_past_skew_secs;
+        }
+        set(&mut limits.max_payload_bytes, &self.max_payload_bytes);
+        set(&mut limits.max_payload_depth, &self.max_payload_depth);
+        set(&mut limits.max_payload_keys, &self.max_payload_keys);
+
+        if self.retention_max_age_secs.is_some() {
+            settings.retention.max_age_secs = self.retention_max_age_secs;
+        }
+        set(&mut settings.auth.api_keys, &self.api_keys);
+
+        let tls = &mut settings.tls;
+        if self.tls_cert.is_some() {
+            tls.cert_path = self.tls_cert.clone();
+        }
+        if self.tls_key.is_some() {
+            tls.key_path = self.tls_key.clone();
+        }
+        if self.tls_client_ca.is_some() {
+            tls.client_ca_path = self.tls_client_ca.clone();
+        }
+        set(&mut tls.require_client_cert, &self.tls_require_client_cert);
+
+        if self.otlp_endpoint.is_some() {
+            settings.telemetry.otlp_endpoint = self.otlp_endpoint.clone();
+        }
+        set(&mut settings.telemetry.otlp_protocol, &self.otlp_protocol);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+
+    #[test]
+    fn cli_overrides_file_which_overrides_defaults() -> Result<()> {
+        // ---
+        let mut settings = Settings::from_toml(
+            r#"
+            [server]
+            endpoint = "127.0.0.1:4000"
+
+            [limits]
+            max_payload_bytes = 1024
+            max_payload_depth = 4
+            "#,
+        )?;
+        let args = Args {
+            max_payload_bytes: Some(2048),
+            ..Args::default()
+        };
+        args.apply_overrides(&mut settings);
 
-    /// Storage backend to use (e.g., memory, redis). Can also be set via ARGUS_REPOSITORY.
-    #[arg(long, env = "ARGUS_REPOSITORY", default_value = "memory")]
-    pub repository: String,
+        assert_eq!(settings.limits.max_payload_bytes, 2048); // CLI wins
+        assert_eq!(settings.limits.max_payload_depth, 4); // file wins over default
+        assert_eq!(settings.server.endpoint, "127.0.0.1:4000");
+        assert_eq!(settings.repository.kind, "memory"); // default
+        Ok(())
+    }
 }
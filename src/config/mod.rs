@@ -0,0 +1,11 @@
+//! Service configuration: layered settings and runtime reloads.
+
+mod reload;
+mod settings;
+
+pub use reload::{apply_reload, spawn_config_watcher, ReloadReport, ReloadTargets};
+pub use settings::{
+    AlertRuleSettings, AlertSettings, MetricsSettings, PushSettings, RollupDefinitionSettings,
+    RollupSettings, SchemaSettings, Settings, StatsdSettings, TelemetrySettings, TlsSettings,
+    WebhookSettings,
+};
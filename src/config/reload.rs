@@ -0,0 +1,265 @@
+//! Runtime configuration reloads.
+//!
+//! On SIGHUP, or when the config file's modification time changes, the
+//! settings are re-resolved through the same layers as at startup and
+//! validated. Only the safe subset is applied in place — log level,
+//! limits, retention and API keys; changes to other sections are logged
+//! and take effect on the next restart. An invalid file is rejected as a
+//! whole and the running settings stay untouched.
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::task::JoinHandle;
+
+use super::Settings;
+use crate::api::{LiveConfig, LogFilter};
+use crate::repository::RetentionPolicyHandle;
+
+/// How often the config file's modification time is checked.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Handles to the running state that reloads update.
+pub struct ReloadTargets {
+    pub live: LiveConfig,
+    pub retention: RetentionPolicyHandle,
+    pub log_filter: LogFilter,
+}
+
+/// Sections touched by a reload.
+#[derive(Debug, Default, PartialEq)]
+pub struct ReloadReport {
+    /// Sections whose new values are now in effect.
+    pub applied: Vec<&'static str>,
+
+    /// Sections that changed but only take effect after a restart.
+    pub restart_required: Vec<&'static str>,
+}
+
+/// Applies the hot-reloadable differences between `current` and `next`.
+pub fn apply_reload(
+    current: &Settings,
+    next: &Settings,
+    targets: &ReloadTargets,
+) -> Result<ReloadReport> {
+    // ---
+    let mut report = ReloadReport::default();
+
+    if current.logging.level != next.logging.level {
+        targets.log_filter.set(&next.logging.level)?;
+        report.applied.push("logging");
+    }
+    if current.limits != next.limits {
+        targets.live.set_validation(next.validation_rules());
+        report.applied.push("limits");
+    }
+    if current.retention != next.retention {
+        targets.retention.store(Arc::new(next.retention_policy()));
+        report.applied.push("retention");
+    }
+    if current.auth != next.auth {
+        targets.live.set_api_keys(next.api_keys());
+        report.applied.push("auth");
+    }
+
+    if current.logging.format != next.logging.format {
+        report.restart_required.push("logging.format");
+    }
+    if current.server != next.server {
+        report.restart_required.push("server");
+    }
+    if current.repository != next.repository {
+        report.restart_required.push("repository");
+    }
+    if current.metrics != next.metrics {
+        report.restart_required.push("metrics");
+    }
+    if current.tls != next.tls {
+        report.restart_required.push("tls");
+    }
+    if current.telemetry != next.telemetry {
+        report.restart_required.push("telemetry");
+    }
+    if current.webhooks != next.webhooks {
+        report.restart_required.push("webhooks");
+    }
+    if current.alerts != next.alerts {
+        report.restart_required.push("alerts");
+    }
+    if current.rollups != next.rollups {
+        report.restart_required.push("rollups");
+    }
+    if current.schemas != next.schemas {
+        report.restart_required.push("schemas");
+    }
+
+    Ok(report)
+}
+
+/// Spawns a task that reloads settings on SIGHUP or when `path` changes.
+///
+/// `loader` re-resolves the full settings (file plus env/CLI overrides)
+/// and must validate them; it reads files, so it runs on the blocking pool.
+/// `initial` is what the server started with.
+pub fn spawn_config_watcher<F>(
+    path: PathBuf,
+    loader: F,
+    initial: Settings,
+    targets: ReloadTargets,
+) -> JoinHandle<()>
+where
+    F: Fn() -> Result<Settings> + Send + Sync + 'static,
+{
+    // ---
+    let loader = Arc::new(loader);
+    tokio::spawn(async move {
+        let mut current = initial;
+        let mut last_modified = modified(&path);
+        let mut poll = tokio::time::interval(POLL_INTERVAL);
+        let mut hangup = hangup_signal();
+
+        loop {
+            let trigger = tokio::select! {
+                _ = poll.tick() => {
+                    let now = modified(&path);
+                    if now == last_modified {
+                        continue;
+                    }
+                    last_modified = now;
+                    "file change"
+                }
+                _ = hangup.recv() => "SIGHUP",
+            };
+
+            let load = Arc::clone(&loader);
+            let next = match tokio::task::spawn_blocking(move || load()).await {
+                Ok(Ok(next)) => next,
+                Ok(Err(err)) => {
+                    tracing::error!(
+                        trigger,
+                        "Config reload rejected, keeping current settings: {:#}",
+                        err
+                    );
+                    continue;
+                }
+                Err(err) => {
+                    tracing::error!(trigger, ?err, "Config reload task failed");
+                    continue;
+                }
+            };
+
+            match apply_reload(&current, &next, &targets) {
+                Ok(report) => {
+                    if !report.restart_required.is_empty() {
+                        tracing::warn!(
+                            sections = ?report.restart_required,
+                            "Config changes require a restart to take effect"
+                        );
+                    }
+                    tracing::info!(trigger, applied = ?report.applied, "Config reloaded");
+                    current = next;
+                }
+                Err(err) => tracing::error!(trigger, "Config reload failed: {:#}", err),
+            }
+        }
+    })
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    // ---
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(unix)]
+fn hangup_signal() -> tokio::signal::unix::Signal {
+    // ---
+    tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+        .expect("failed to install SIGHUP handler")
+}
+
+#[cfg(not(unix))]
+fn hangup_signal() -> NeverSignal {
+    NeverSignal
+}
+
+#[cfg(not(unix))]
+struct NeverSignal;
+
+#[cfg(not(unix))]
+impl NeverSignal {
+    async fn recv(&mut self) -> Option<()> {
+        std::future::pending().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+    use arc_swap::ArcSwap;
+    use std::sync::Mutex;
+
+    fn targets(levels: Arc<Mutex<Vec<String>>>) -> ReloadTargets {
+        // ---
+        ReloadTargets {
+            live: LiveConfig::default(),
+            retention: Arc::new(ArcSwap::from_pointee(
+                Settings::default().retention_policy(),
+            )),
+            log_filter: LogFilter::new("info", move |level| {
+                levels.lock().unwrap().push(level.to_string());
+                Ok(())
+            }),
+        }
+    }
+
+    #[test]
+    fn reload_applies_safe_subset() -> Result<()> {
+        // ---
+        let levels = Arc::new(Mutex::new(Vec::new()));
+        let targets = targets(levels.clone());
+        let current = Settings::default();
+        let mut next = current.clone();
+        next.logging.level = "debug".to_string();
+        next.limits.max_payload_bytes = 10;
+        next.retention.max_age_secs = Some(3600);
+        next.auth.api_keys = vec!["k1".to_string()];
+
+        let report = apply_reload(&current, &next, &targets)?;
+
+        assert_eq!(
+            report.applied,
+            vec!["logging", "limits", "retention", "auth"]
+        );
+        assert!(report.restart_required.is_empty());
+        assert_eq!(*levels.lock().unwrap(), vec!["debug".to_string()]);
+        assert_eq!(*targets.log_filter.current(), "debug");
+        assert_eq!(targets.live.validation().max_payload_bytes, 10);
+        assert_eq!(
+            targets.retention.load().max_age,
+            Some(Duration::from_secs(3600))
+        );
+        assert!(targets.live.api_keys().contains("k1"));
+        Ok(())
+    }
+
+    #[test]
+    fn restart_only_sections_are_reported_not_applied() -> Result<()> {
+        // ---
+        let levels = Arc::new(Mutex::new(Vec::new()));
+        let targets = targets(levels.clone());
+        let current = Settings::default();
+        let mut next = current.clone();
+        next.server.endpoint = "127.0.0.1:9999".to_string();
+        next.repository.kind = "noop".to_string();
+
+        let report = apply_reload(&current, &next, &targets)?;
+
+        assert!(report.applied.is_empty());
+        assert_eq!(report.restart_required, vec!["server", "repository"]);
+        assert!(levels.lock().unwrap().is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,1198 @@
+//! Layered service settings.
+//!
+//! Settings are resolved in this order, later layers winning:
+//!
+//! 1. Built-in defaults (`Settings::default()`)
+//! 2. The TOML file passed with `--config` / `ARGUS_CONFIG`
+//! 3. `ARGUS_*` environment variables
+//! 4. Command-line flags
+//!
+//! Layers 3 and 4 are merged by clap before being applied on top of the
+//! file (see `cli.rs`). The result is validated once at startup and again
+//! on every reload.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use tracing_subscriber::EnvFilter;
+
+use crate::domain::{
+    is_payload_path, AlertCondition, AlertRule, EventFilter, RetentionPolicy, Role,
+    RollupDefinition, ValidationRules,
+};
+
+/// Fully resolved service settings.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub server: ServerSettings,
+    pub logging: LoggingSettings,
+    pub repository: RepositorySettings,
+    pub metrics: MetricsSettings,
+    pub limits: LimitSettings,
+    pub retention: RetentionSettings,
+    pub auth: AuthSettings,
+    pub tls: TlsSettings,
+    pub telemetry: TelemetrySettings,
+    pub webhooks: WebhookSettings,
+    pub alerts: AlertSettings,
+    pub rollups: RollupSettings,
+    pub schemas: SchemaSettings,
+}
+
+/// `[server]` — listener and HTTP behaviour (restart required).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerSettings {
+    pub endpoint: String,
+    pub shutdown_timeout_secs: u64,
+
+    /// Seconds to keep serving after readiness flips to `draining`, so load balancers
+    /// stop routing here before the listener closes.
+    pub drain_delay_secs: u64,
+
+    pub api_docs_ui: bool,
+    pub legacy_sunset: Option<DateTime<Utc>>,
+
+    /// Mount the `/admin` endpoints (runtime log filter).
+    pub admin_api: bool,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            endpoint: "0.0.0.0:3000".to_string(),
+            shutdown_timeout_secs: 30,
+            drain_delay_secs: 5,
+            api_docs_ui: false,
+            legacy_sunset: None,
+            admin_api: false,
+        }
+    }
+}
+
+/// `[logging]` — log filter (hot-reloadable) and output format (restart required).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingSettings {
+    /// `tracing` filter directive, e.g. `info,argus_events::api=debug`.
+    pub level: String,
+
+    /// `text` for humans, `json` for one object per line.
+    pub format: String,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            format: "text".to_string(),
+        }
+    }
+}
+
+/// `[repository]` — storage backend (restart required).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RepositorySettings {
+    pub kind: String,
+}
+
+impl Default for RepositorySettings {
+    fn default() -> Self {
+        Self {
+            kind: "memory".to_string(),
+        }
+    }
+}
+
+/// `[metrics]` — metrics backend (restart required).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsSettings {
+    pub kind: String,
+
+    /// Distinct `event_type` label values before further types are counted as `__other__`.
+    pub event_type_label_limit: usize,
+
+    /// Histogram buckets (seconds) for HTTP and repository latency.
+    pub latency_buckets: Vec<f64>,
+
+    /// Histogram buckets for the number of events returned by a query.
+    pub result_size_buckets: Vec<f64>,
+
+    /// Histogram buckets (bytes) for HTTP request and response bodies.
+    pub body_size_buckets: Vec<f64>,
+
+    /// Also install the Prometheus recorder process-wide, so `metrics` macros
+    /// used outside this crate are exported too.
+    pub install_global: bool,
+
+    /// Seconds between repository stats gauge refreshes; 0 disables them.
+    pub stats_refresh_secs: u64,
+
+    /// Push target used when `kind = "statsd"`.
+    pub statsd: StatsdSettings,
+
+    /// Push target used when `kind = "push"`.
+    pub push: PushSettings,
+}
+
+impl Default for MetricsSettings {
+    fn default() -> Self {
+        Self {
+            kind: "noop".to_string(),
+            event_type_label_limit: 100,
+            latency_buckets: vec![
+                0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0,
+            ],
+            result_size_buckets: vec![0.0, 1.0, 10.0, 100.0, 1_000.0, 10_000.0, 100_000.0],
+            body_size_buckets: vec![
+                64.0,
+                256.0,
+                1_024.0,
+                4_096.0,
+                16_384.0,
+                65_536.0,
+                262_144.0,
+                1_048_576.0,
+            ],
+            install_global: false,
+            stats_refresh_secs: 30,
+            statsd: StatsdSettings::default(),
+            push: PushSettings::default(),
+        }
+    }
+}
+
+/// `[metrics.statsd]` — StatsD / DogStatsD agent that metrics are pushed to.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StatsdSettings {
+    /// `host:port` of the agent.
+    pub address: String,
+
+    /// Prepended to every metric name with a `.`; empty for none.
+    pub prefix: String,
+
+    /// Tags added to every metric.
+    pub tags: BTreeMap<String, String>,
+
+    /// `dogstatsd` sends tags as `|#key:value`; plain `statsd` drops them.
+    pub dialect: String,
+
+    /// How often buffered metrics are sent.
+    pub flush_interval_ms: u64,
+
+    /// Largest UDP payload; lines are batched into packets up to this size.
+    pub max_packet_bytes: usize,
+}
+
+impl Default for StatsdSettings {
+    fn default() -> Self {
+        Self {
+            address: "127.0.0.1:8125".to_string(),
+            prefix: "argus_events".to_string(),
+            tags: BTreeMap::new(),
+            dialect: "dogstatsd".to_string(),
+            flush_interval_ms: 1_000,
+            max_packet_bytes: 1_432,
+        }
+    }
+}
+
+/// `[metrics.push]` — endpoint that rendered Prometheus metrics are pushed to.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PushSettings {
+    /// Full target URL, e.g. `http://pushgateway:9091/metrics/job/argus-events`.
+    pub url: String,
+
+    /// `PUT` replaces the push gateway group; `POST` merges into it.
+    pub method: String,
+
+    /// How often metrics are pushed.
+    pub interval_ms: u64,
+
+    /// Timeout of a single push attempt.
+    pub timeout_ms: u64,
+
+    /// Attempts after the first before a push is given up.
+    pub max_retries: u32,
+
+    /// Delay before the first retry; doubled on each further retry.
+    pub initial_backoff_ms: u64,
+
+    /// Upper bound for the retry delay.
+    pub max_backoff_ms: u64,
+}
+
+impl Default for PushSettings {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            method: "PUT".to_string(),
+            interval_ms: 15_000,
+            timeout_ms: 5_000,
+            max_retries: 3,
+            initial_backoff_ms: 200,
+            max_backoff_ms: 5_000,
+        }
+    }
+}
+
+/// `[limits]` — event validation limits (hot-reloadable).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LimitSettings {
+    pub max_event_type_len: usize,
+    pub event_type_chars: String,
+    pub allowed_event_types: Vec<String>,
+    pub max_future_skew_secs: i64,
+    pub max_past_skew_secs: Option<i64>,
+    pub max_payload_bytes: usize,
+    pub max_payload_depth: usize,
+    pub max_payload_keys: usize,
+    pub max_string_len: usize,
+}
+
+impl Default for LimitSettings {
+    fn default() -> Self {
+        let rules = ValidationRules::default();
+        Self {
+            max_event_type_len: rules.event_type_max_len,
+            event_type_chars: rules.event_type_extra_chars,
+            allowed_event_types: Vec::new(),
+            max_future_skew_secs: rules.max_future_skew.num_seconds(),
+            max_past_skew_secs: None,
+            max_payload_bytes: rules.max_payload_bytes,
+            max_payload_depth: rules.max_payload_depth,
+            max_payload_keys: rules.max_payload_keys,
+            max_string_len: rules.max_string_len,
+        }
+    }
+}
+
+/// `[retention]` — how long raw events are kept (hot-reloadable).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetentionSettings {
+    /// Events older than this are purged; unset keeps events forever.
+    pub max_age_secs: Option<u64>,
+    pub sweep_interval_secs: u64,
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        Self {
+            max_age_secs: None,
+            sweep_interval_secs: 60,
+        }
+    }
+}
+
+/// `[auth]` — API keys accepted on event routes (hot-reloadable).
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthSettings {
+    /// Accepted keys; empty disables authentication.
+    pub api_keys: Vec<String>,
+}
+
+/// `[tls]` — native HTTPS and client certificates (restart required;
+/// certificate files themselves are reloaded when they change on disk).
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TlsSettings {
+    /// PEM certificate chain; serving HTTPS requires this and `key_path`.
+    pub cert_path: Option<PathBuf>,
+
+    /// PEM private key matching `cert_path`.
+    pub key_path: Option<PathBuf>,
+
+    /// PEM bundle of CAs trusted to sign client certificates.
+    pub client_ca_path: Option<PathBuf>,
+
+    /// Reject handshakes without a client certificate signed by `client_ca_path`.
+    pub require_client_cert: bool,
+
+    /// Client certificate subject common name → granted roles.
+    pub identities: HashMap<String, BTreeSet<Role>>,
+}
+
+impl TlsSettings {
+    // ---
+
+    /// Whether the server should terminate TLS itself.
+    pub fn enabled(&self) -> bool {
+        self.cert_path.is_some()
+    }
+}
+
+/// `[telemetry]` — OpenTelemetry trace export (restart required).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TelemetrySettings {
+    /// Base URL of the OTLP collector, e.g. `http://localhost:4318`; unset disables export.
+    pub otlp_endpoint: Option<String>,
+
+    /// `grpc`, `http/protobuf` or `http/json`.
+    pub otlp_protocol: String,
+
+    /// Reported as the `service.name` resource attribute.
+    pub service_name: String,
+}
+
+impl Default for TelemetrySettings {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            otlp_protocol: "http/protobuf".to_string(),
+            service_name: "argus-events".to_string(),
+        }
+    }
+}
+
+/// `[webhooks]` — delivery of stored events to subscribers (restart required).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WebhookSettings {
+    /// JSON file keeping subscriptions, queued deliveries and dead letters
+    /// across restarts; unset keeps them in memory only.
+    pub state_path: Option<PathBuf>,
+
+    /// Delivery attempts before a delivery is moved to the dead-letter list.
+    pub max_attempts: u32,
+
+    /// Delay before the first retry; doubled for each further one.
+    pub initial_backoff_ms: u64,
+
+    /// Upper bound of the retry delay.
+    pub max_backoff_ms: u64,
+
+    /// Timeout of a single delivery attempt.
+    pub timeout_ms: u64,
+
+    /// Dead letters kept; the oldest are dropped beyond this.
+    pub max_dead_letters: usize,
+
+    /// How often queue changes are written to `state_path`.
+    pub flush_interval_ms: u64,
+
+    /// Deliveries sent at the same time; further due ones wait for the next batch.
+    pub max_concurrent_deliveries: usize,
+
+    /// Allow subscription URLs on loopback, private and link-local addresses.
+    pub allow_private_targets: bool,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            state_path: None,
+            max_attempts: 5,
+            initial_backoff_ms: 1_000,
+            max_backoff_ms: 60_000,
+            timeout_ms: 5_000,
+            max_dead_letters: 1_000,
+            flush_interval_ms: 1_000,
+            max_concurrent_deliveries: 32,
+            allow_private_targets: false,
+        }
+    }
+}
+
+/// `[alerts]` — threshold alert rules over stored events (restart required).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertSettings {
+    /// How often rules are re-evaluated between events, so that windows
+    /// drain and `absence` rules fire.
+    pub evaluation_interval_ms: u64,
+
+    /// Attempts per firing/resolved notification before it is dropped.
+    pub notify_max_attempts: u32,
+
+    /// Timeout of a single notification attempt.
+    pub notify_timeout_ms: u64,
+
+    pub rules: Vec<AlertRuleSettings>,
+}
+
+impl Default for AlertSettings {
+    fn default() -> Self {
+        Self {
+            evaluation_interval_ms: 1_000,
+            notify_max_attempts: 3,
+            notify_timeout_ms: 5_000,
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// `[[alerts.rules]]` — one alert rule.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertRuleSettings {
+    pub name: String,
+    pub event_type: String,
+
+    /// Top-level payload fields a matching event must carry.
+    #[serde(default)]
+    pub filter: Option<Map<String, Value>>,
+
+    /// One of: count, rate, absence.
+    pub condition: AlertCondition,
+
+    /// Events in the window (`count`) or per second (`rate`) that must be exceeded.
+    #[serde(default)]
+    pub threshold: f64,
+
+    pub window_secs: u64,
+
+    /// Time the rule must stay breached before it fires.
+    #[serde(default)]
+    pub for_secs: u64,
+
+    /// Endpoint notified when the rule fires or resolves.
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+
+    /// Signs notifications with HMAC-SHA256 when set.
+    #[serde(default)]
+    pub webhook_secret: Option<String>,
+}
+
+/// `[rollups]` — pre-aggregated buckets of stored events (restart required).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RollupSettings {
+    /// JSON file keeping the buckets across restarts; unset keeps them in memory only.
+    pub state_path: Option<PathBuf>,
+
+    /// How often buckets are written to `state_path` and expired ones dropped.
+    pub flush_interval_ms: u64,
+
+    pub definitions: Vec<RollupDefinitionSettings>,
+}
+
+impl Default for RollupSettings {
+    fn default() -> Self {
+        Self {
+            state_path: None,
+            flush_interval_ms: 5_000,
+            definitions: Vec::new(),
+        }
+    }
+}
+
+/// `[schemas]` — payload schema inference and drift reports (restart required).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SchemaSettings {
+    /// Infer the payload schema of every event type as events are stored.
+    pub enabled: bool,
+
+    /// Events a type's schema learns from before departures are reported as drift.
+    pub min_events: u64,
+
+    /// Endpoint notified of each drifting event.
+    pub webhook_url: Option<String>,
+
+    /// Signs notifications with HMAC-SHA256 when set.
+    pub webhook_secret: Option<String>,
+
+    /// Attempts per drift notification before it is dropped.
+    pub notify_max_attempts: u32,
+
+    /// Timeout of a single notification attempt.
+    pub notify_timeout_ms: u64,
+}
+
+impl Default for SchemaSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_events: 10,
+            webhook_url: None,
+            webhook_secret: None,
+            notify_max_attempts: 3,
+            notify_timeout_ms: 5_000,
+        }
+    }
+}
+
+/// `[[rollups.definitions]]` — one rollup.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RollupDefinitionSettings {
+    /// Served at `GET /v1/rollups/{name}`; letters, digits, `_` and `-` only.
+    pub name: String,
+    pub event_type: String,
+
+    /// Top-level payload fields a matching event must carry.
+    #[serde(default)]
+    pub filter: Option<Map<String, Value>>,
+
+    /// Bucket length.
+    pub interval_secs: u64,
+
+    /// Dotted payload paths splitting each bucket into groups.
+    #[serde(default)]
+    pub group_by: Vec<String>,
+
+    /// Dotted payload paths of numeric fields to aggregate (count, sum, min, max, avg).
+    #[serde(default)]
+    pub fields: Vec<String>,
+
+    /// Buckets older than this are dropped; unset keeps them all.
+    #[serde(default)]
+    pub max_age_secs: Option<u64>,
+}
+
+impl Settings {
+    // ---
+
+    /// Reads settings from a TOML file, filling unspecified fields with defaults.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        // ---
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        Self::from_toml(&text).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+
+    /// Parses settings from TOML text.
+    pub fn from_toml(text: &str) -> Result<Self> {
+        // ---
+        Ok(toml::from_str(text)?)
+    }
+
+    /// Checks cross-field constraints, reporting the offending key.
+    pub fn validate(&self) -> Result<()> {
+        // ---
+        if !is_host_port(&self.server.endpoint) {
+            bail!(
+                "server.endpoint '{}' is not host:port",
+                self.server.endpoint
+            );
+        }
+
+        EnvFilter::try_new(&self.logging.level).with_context(|| {
+            format!(
+                "logging.level '{}' is not a valid filter",
+                self.logging.level
+            )
+        })?;
+        if !["text", "json"].contains(&self.logging.format.as_str()) {
+            bail!(
+                "logging.format '{}' is not one of: text, json",
+                self.logging.format
+            );
+        }
+
+        if !["memory", "noop"].contains(&self.repository.kind.as_str()) {
+            bail!(
+                "repository.kind '{}' is not one of: memory, noop",
+                self.repository.kind
+            );
+        }
+        if !["prom", "statsd", "push", "noop"].contains(&self.metrics.kind.as_str()) {
+            bail!(
+                "metrics.kind '{}' is not one of: prom, statsd, push, noop",
+                self.metrics.kind
+            );
+        }
+        if self.metrics.event_type_label_limit == 0 {
+            bail!("metrics.event_type_label_limit must be greater than 0");
+        }
+        for (key, buckets) in [
+            ("metrics.latency_buckets", &self.metrics.latency_buckets),
+            (
+                "metrics.result_size_buckets",
+                &self.metrics.result_size_buckets,
+            ),
+            ("metrics.body_size_buckets", &self.metrics.body_size_buckets),
+        ] {
+            if buckets.is_empty() || buckets.windows(2).any(|w| w[0] >= w[1]) {
+                bail!("{} must be a non-empty, strictly increasing list", key);
+            }
+        }
+
+        let statsd = &self.metrics.statsd;
+        if !["dogstatsd", "statsd"].contains(&statsd.dialect.as_str()) {
+            bail!(
+                "metrics.statsd.dialect '{}' is not one of: dogstatsd, statsd",
+                statsd.dialect
+            );
+        }
+        if statsd.flush_interval_ms == 0 {
+            bail!("metrics.statsd.flush_interval_ms must be greater than 0");
+        }
+        if statsd.max_packet_bytes < 64 {
+            bail!("metrics.statsd.max_packet_bytes must be at least 64");
+        }
+
+        let push = &self.metrics.push;
+        if self.metrics.kind == "push"
+            && !push.url.starts_with("http://")
+            && !push.url.starts_with("https://")
+        {
+            bail!(
+                "metrics.push.url must be an http:// or https:// URL when metrics.kind is 'push'"
+            );
+        }
+        if !["PUT", "POST"].contains(&push.method.as_str()) {
+            bail!(
+                "metrics.push.method '{}' is not one of: PUT, POST",
+                push.method
+            );
+        }
+        for (key, value) in [
+            ("metrics.push.interval_ms", push.interval_ms),
+            ("metrics.push.timeout_ms", push.timeout_ms),
+            ("metrics.push.initial_backoff_ms", push.initial_backoff_ms),
+        ] {
+            if value == 0 {
+                bail!("{} must be greater than 0", key);
+            }
+        }
+        if push.max_backoff_ms < push.initial_backoff_ms {
+            bail!("metrics.push.max_backoff_ms must not be less than initial_backoff_ms");
+        }
+
+        let limits = &self.limits;
+        for (key, value) in [
+            ("limits.max_event_type_len", limits.max_event_type_len),
+            ("limits.max_payload_bytes", limits.max_payload_bytes),
+            ("limits.max_payload_keys", limits.max_payload_keys),
+            ("limits.max_string_len", limits.max_string_len),
+        ] {
+            if value == 0 {
+                bail!("{} must be greater than 0", key);
+            }
+        }
+        if limits.max_future_skew_secs < 0 {
+            bail!("limits.max_future_skew_secs must not be negative");
+        }
+        if limits.max_past_skew_secs.is_some_and(|s| s <= 0) {
+            bail!("limits.max_past_skew_secs must be greater than 0 when set");
+        }
+        if let Some(c) = limits
+            .event_type_chars
+            .chars()
+            .find(|c| c.is_ascii_alphanumeric() || c.is_whitespace())
+        {
+            bail!(
+                "limits.event_type_chars must only list punctuation, found {:?}",
+                c
+            );
+        }
+
+        if self.retention.max_age_secs == Some(0) {
+            bail!("retention.max_age_secs must be greater than 0 when set");
+        }
+        if self.retention.sweep_interval_secs == 0 {
+            bail!("retention.sweep_interval_secs must be greater than 0");
+        }
+
+        if self.auth.api_keys.iter().any(|k| k.trim().is_empty()) {
+            bail!("auth.api_keys must not contain empty keys");
+        }
+
+        let tls = &self.tls;
+        if tls.cert_path.is_some() != tls.key_path.is_some() {
+            bail!("tls.cert_path and tls.key_path must be set together");
+        }
+        if tls.client_ca_path.is_some() && !tls.enabled() {
+            bail!("tls.client_ca_path requires tls.cert_path and tls.key_path");
+        }
+        if tls.require_client_cert && tls.client_ca_path.is_none() {
+            bail!("tls.require_client_cert requires tls.client_ca_path");
+        }
+        if !tls.identities.is_empty() && tls.client_ca_path.is_none() {
+            bail!("tls.identities requires tls.client_ca_path");
+        }
+
+        let telemetry = &self.telemetry;
+        if !["grpc", "http/protobuf", "http/json"].contains(&telemetry.otlp_protocol.as_str()) {
+            bail!(
+                "telemetry.otlp_protocol '{}' is not one of: grpc, http/protobuf, http/json",
+                telemetry.otlp_protocol
+            );
+        }
+        if let Some(endpoint) = &telemetry.otlp_endpoint {
+            if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+                bail!(
+                    "telemetry.otlp_endpoint '{}' must be an http:// or https:// URL",
+                    endpoint
+                );
+            }
+        }
+        if telemetry.service_name.trim().is_empty() {
+            bail!("telemetry.service_name must not be empty");
+        }
+
+        let webhooks = &self.webhooks;
+        for (key, value) in [
+            ("webhooks.max_attempts", u64::from(webhooks.max_attempts)),
+            ("webhooks.initial_backoff_ms", webhooks.initial_backoff_ms),
+            ("webhooks.timeout_ms", webhooks.timeout_ms),
+            ("webhooks.flush_interval_ms", webhooks.flush_interval_ms),
+            (
+                "webhooks.max_concurrent_deliveries",
+                webhooks.max_concurrent_deliveries as u64,
+            ),
+        ] {
+            if value == 0 {
+                bail!("{} must be greater than 0", key);
+            }
+        }
+        if webhooks.max_backoff_ms < webhooks.initial_backoff_ms {
+            bail!("webhooks.max_backoff_ms must not be less than initial_backoff_ms");
+        }
+
+        let alerts = &self.alerts;
+        for (key, value) in [
+            (
+                "alerts.evaluation_interval_ms",
+                alerts.evaluation_interval_ms,
+            ),
+            (
+                "alerts.notify_max_attempts",
+                u64::from(alerts.notify_max_attempts),
+            ),
+            ("alerts.notify_timeout_ms", alerts.notify_timeout_ms),
+        ] {
+            if value == 0 {
+                bail!("{} must be greater than 0", key);
+            }
+        }
+        let mut names = HashSet::new();
+        for rule in &alerts.rules {
+            if rule.name.trim().is_empty() {
+                bail!("alerts.rules: every rule needs a name");
+            }
+            if !names.insert(rule.name.as_str()) {
+                bail!("alerts.rules: duplicate rule name '{}'", rule.name);
+            }
+            if rule.event_type.trim().is_empty() {
+                bail!("alerts.rules '{}': event_type must not be empty", rule.name);
+            }
+            if rule.window_secs == 0 {
+                bail!(
+                    "alerts.rules '{}': window_secs must be greater than 0",
+                    rule.name
+                );
+            }
+            if !rule.threshold.is_finite() || rule.threshold < 0.0 {
+                bail!(
+                    "alerts.rules '{}': threshold must be a non-negative number",
+                    rule.name
+                );
+            }
+            if let Some(url) = &rule.webhook_url {
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    bail!(
+                        "alerts.rules '{}': webhook_url '{}' must be an http:// or https:// URL",
+                        rule.name,
+                        url
+                    );
+                }
+            }
+        }
+
+        let rollups = &self.rollups;
+        if rollups.flush_interval_ms == 0 {
+            bail!("rollups.flush_interval_ms must be greater than 0");
+        }
+        let mut names = HashSet::new();
+        for rollup in &rollups.definitions {
+            let valid_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+            if rollup.name.is_empty() || !rollup.name.chars().all(valid_char) {
+                bail!(
+                    "rollups.definitions: name '{}' must be letters, digits, '_' or '-'",
+                    rollup.name
+                );
+            }
+            if !names.insert(rollup.name.as_str()) {
+                bail!("rollups.definitions: duplicate name '{}'", rollup.name);
+            }
+            if rollup.event_type.trim().is_empty() {
+                bail!(
+                    "rollups.definitions '{}': event_type must not be empty",
+                    rollup.name
+                );
+            }
+            if rollup.interval_secs == 0 {
+                bail!(
+                    "rollups.definitions '{}': interval_secs must be greater than 0",
+                    rollup.name
+                );
+            }
+            if rollup.max_age_secs == Some(0) {
+                bail!(
+                    "rollups.definitions '{}': max_age_secs must be greater than 0",
+                    rollup.name
+                );
+            }
+            for path in rollup.group_by.iter().chain(&rollup.fields) {
+                if !is_payload_path(path) {
+                    bail!(
+                        "rollups.definitions '{}': '{}' is not a dotted payload path",
+                        rollup.name,
+                        path
+                    );
+                }
+            }
+        }
+
+        let schemas = &self.schemas;
+        for (key, value) in [
+            (
+                "schemas.notify_max_attempts",
+                u64::from(schemas.notify_max_attempts),
+            ),
+            ("schemas.notify_timeout_ms", schemas.notify_timeout_ms),
+        ] {
+            if value == 0 {
+                bail!("{} must be greater than 0", key);
+            }
+        }
+        if let Some(url) = &schemas.webhook_url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                bail!(
+                    "schemas.webhook_url '{}' must be an http:// or https:// URL",
+                    url
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Event validation rules described by `[limits]`.
+    pub fn validation_rules(&self) -> ValidationRules {
+        // ---
+        let limits = &self.limits;
+        let allowed: HashSet<String> = limits
+            .allowed_event_types
+            .iter()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+
+        ValidationRules {
+            event_type_max_len: limits.max_event_type_len,
+            event_type_extra_chars: limits.event_type_chars.clone(),
+            allowed_event_types: (!allowed.is_empty()).then_some(allowed),
+            max_future_skew: Duration::seconds(limits.max_future_skew_secs),
+            max_past_skew: limits.max_past_skew_secs.map(Duration::seconds),
+            max_payload_bytes: limits.max_payload_bytes,
+            max_payload_depth: limits.max_payload_depth,
+            max_payload_keys: limits.max_payload_keys,
+            max_string_len: limits.max_string_len,
+            ..ValidationRules::default()
+        }
+    }
+
+    /// Retention policy described by `[retention]`.
+    pub fn retention_policy(&self) -> RetentionPolicy {
+        // ---
+        RetentionPolicy {
+            max_age: self
+                .retention
+                .max_age_secs
+                .map(std::time::Duration::from_secs),
+            sweep_interval: std::time::Duration::from_secs(self.retention.sweep_interval_secs),
+        }
+    }
+
+    /// Alert rules described by `[alerts]`.
+    pub fn alert_rules(&self) -> Vec<AlertRule> {
+        // ---
+        self.alerts
+            .rules
+            .iter()
+            .map(|rule| AlertRule {
+                name: rule.name.clone(),
+                events: EventFilter {
+                    event_type: rule.event_type.clone(),
+                    filter: rule.filter.clone(),
+                },
+                condition: rule.condition,
+                threshold: rule.threshold,
+                window: std::time::Duration::from_secs(rule.window_secs),
+                for_duration: std::time::Duration::from_secs(rule.for_secs),
+                webhook_url: rule.webhook_url.clone(),
+                webhook_secret: rule.webhook_secret.clone(),
+            })
+            .collect()
+    }
+
+    /// Rollups described by `[rollups]`.
+    pub fn rollup_definitions(&self) -> Vec<RollupDefinition> {
+        // ---
+        self.rollups
+            .definitions
+            .iter()
+            .map(|rollup| RollupDefinition {
+                name: rollup.name.clone(),
+                events: EventFilter {
+                    event_type: rollup.event_type.clone(),
+                    filter: rollup.filter.clone(),
+                },
+                interval: std::time::Duration::from_secs(rollup.interval_secs),
+                group_by: rollup.group_by.clone(),
+                fields: rollup.fields.clone(),
+                max_age: rollup.max_age_secs.map(std::time::Duration::from_secs),
+            })
+            .collect()
+    }
+
+    /// API keys described by `[auth]`.
+    pub fn api_keys(&self) -> HashSet<String> {
+        // ---
+        self.auth.api_keys.iter().cloned().collect()
+    }
+}
+
+/// Whether `endpoint` is shaped like `host:port`; the host is resolved when the listener binds.
+fn is_host_port(endpoint: &str) -> bool {
+    // ---
+    if endpoint.parse::<SocketAddr>().is_ok() {
+        return true;
+    }
+    endpoint.rsplit_once(':').is_some_and(|(host, port)| {
+        !host.is_empty() && !host.contains(':') && port.parse::<u16>().is_ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+
+    #[test]
+    fn partial_file_keeps_defaults() -> Result<()> {
+        // ---
+        let settings = Settings::from_toml(
+            r#"
+            [limits]
+            max_payload_bytes = 1024
+
+            [auth]
+            api_keys = ["secret"]
+            "#,
+        )?;
+
+        assert_eq!(settings.limits.max_payload_bytes, 1024);
+        assert_eq!(settings.limits.max_payload_depth, 16);
+        assert_eq!(settings.server, ServerSettings::default());
+        assert!(settings.api_keys().contains("secret"));
+        settings.validate()
+    }
+
+    #[test]
+    fn tls_identities_map_subjects_to_roles() -> Result<()> {
+        // ---
+        let settings = Settings::from_toml(
+            r#"
+            [tls]
+            cert_path = "server.pem"
+            key_path = "server.key"
+            client_ca_path = "clients-ca.pem"
+
+            [tls.identities]
+            "collector-1" = ["ingest"]
+            "dashboard" = ["read", "ingest"]
+            "#,
+        )?;
+
+        let dashboard = &settings.tls.identities["dashboard"];
+        assert!(dashboard.contains(&Role::Read) && dashboard.contains(&Role::Ingest));
+        assert!(!settings.tls.identities["collector-1"].contains(&Role::Read));
+        settings.validate()
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        // ---
+        let err = Settings::from_toml("[limits]\nmax_payload_byte = 1\n").unwrap_err();
+        assert!(err.to_string().contains("max_payload_byte"), "{}", err);
+    }
+
+    #[test]
+    fn validation_names_the_offending_key() {
+        // ---
+        let mut settings = Settings::default();
+        settings.server.endpoint = "localhost:3000".to_string();
+        settings.validate().unwrap();
+        // Names are only resolved when the listener binds
+        settings.server.endpoint = "no-such-host.invalid:3000".to_string();
+        settings.validate().unwrap();
+        settings.server.endpoint = "[::1]:3000".to_string();
+        settings.validate().unwrap();
+        for endpoint in ["localhost", "localhost:http", ":3000", "::1:3000"] {
+            settings.server.endpoint = endpoint.to_string();
+            let err = settings.validate().unwrap_err();
+            assert!(err.to_string().contains("server.endpoint"), "{}", err);
+        }
+
+        let mut settings = Settings::default();
+        settings.repository.kind = "redis".to_string();
+        let err = settings.validate().unwrap_err();
+        assert!(err.to_string().contains("repository.kind"), "{}", err);
+
+        let mut settings = Settings::default();
+        settings.limits.max_payload_bytes = 0;
+        let err = settings.validate().unwrap_err();
+        assert!(
+            err.to_string().contains("limits.max_payload_bytes"),
+            "{}",
+            err
+        );
+
+        let mut settings = Settings::default();
+        settings.logging.level = "info,[".to_string();
+        let err = settings.validate().unwrap_err();
+        assert!(err.to_string().contains("logging.level"), "{}", err);
+
+        let mut settings = Settings::default();
+        settings.logging.format = "logfmt".to_string();
+        let err = settings.validate().unwrap_err();
+        assert!(err.to_string().contains("logging.format"), "{}", err);
+
+        let mut settings = Settings::default();
+        settings.metrics.latency_buckets = vec![0.5, 0.1];
+        let err = settings.validate().unwrap_err();
+        assert!(
+            err.to_string().contains("metrics.latency_buckets"),
+            "{}",
+            err
+        );
+
+        let mut settings = Settings::default();
+        settings.metrics.body_size_buckets = Vec::new();
+        let err = settings.validate().unwrap_err();
+        assert!(
+            err.to_string().contains("metrics.body_size_buckets"),
+            "{}",
+            err
+        );
+
+        let mut settings = Settings::default();
+        settings.tls.cert_path = Some("server.pem".into());
+        let err = settings.validate().unwrap_err();
+        assert!(err.to_string().contains("tls.key_path"), "{}", err);
+
+        let mut settings = Settings::default();
+        settings.telemetry.otlp_protocol = "thrift".to_string();
+        let err = settings.validate().unwrap_err();
+        assert!(
+            err.to_string().contains("telemetry.otlp_protocol"),
+            "{}",
+            err
+        );
+
+        let mut settings = Settings::default();
+        settings.metrics.kind = "push".to_string();
+        let err = settings.validate().unwrap_err();
+        assert!(err.to_string().contains("metrics.push.url"), "{}", err);
+
+        let mut settings = Settings::default();
+        settings.webhooks.max_attempts = 0;
+        let err = settings.validate().unwrap_err();
+        assert!(err.to_string().contains("webhooks.max_attempts"), "{}", err);
+
+        let mut settings = Settings::default();
+        settings.schemas.webhook_url = Some("ftp://example.com".to_string());
+        let err = settings.validate().unwrap_err();
+        assert!(err.to_string().contains("schemas.webhook_url"), "{}", err);
+    }
+
+    #[test]
+    fn alert_rules_are_parsed_and_checked() -> Result<()> {
+        // ---
+        let settings = Settings::from_toml(
+            r#"
+            [[alerts.rules]]
+            name = "payment_failures"
+            event_type = "payment_failed"
+            filter = { region = "eu" }
+            condition = "count"
+            threshold = 50
+            window_secs = 300
+            for_secs = 60
+            "#,
+        )?;
+        settings.validate()?;
+        let rules = settings.alert_rules();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].condition, AlertCondition::Count);
+        assert_eq!(rules[0].window, std::time::Duration::from_secs(300));
+        assert_eq!(rules[0].events.filter.as_ref().unwrap()["region"], "eu");
+
+        assert!(Settings::from_toml(
+            "[[alerts.rules]]\nname = \"x\"\nevent_type = \"a\"\ncondition = \"spike\"\nwindow_secs = 1"
+        )
+        .is_err());
+
+        let mut duplicate = settings.clone();
+        duplicate
+            .alerts
+            .rules
+            .push(settings.alerts.rules[0].clone());
+        let err = duplicate.validate().unwrap_err();
+        assert!(err.to_string().contains("duplicate rule name"), "{}", err);
+
+        let mut no_window = settings;
+        no_window.alerts.rules[0].window_secs = 0;
+        let err = no_window.validate().unwrap_err();
+        assert!(err.to_string().contains("window_secs"), "{}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn rollup_definitions_are_parsed_and_checked() -> Result<()> {
+        // ---
+        let settings = Settings::from_toml(
+            r#"
+            [rollups]
+            state_path = "/var/lib/argus/rollups.json"
+
+            [[rollups.definitions]]
+            name = "revenue_hourly"
+            event_type = "purchase"
+            interval_secs = 3600
+            group_by = ["country"]
+            fields = ["order.amount"]
+            max_age_secs = 7776000
+            "#,
+        )?;
+        settings.validate()?;
+        let definitions = settings.rollup_definitions();
+        assert_eq!(definitions.len(), 1);
+        assert_eq!(
+            definitions[0].interval,
+            std::time::Duration::from_secs(3600)
+        );
+        assert_eq!(definitions[0].fields, ["order.amount"]);
+        assert_eq!(settings.rollups.flush_interval_ms, 5_000);
+
+        let mut bad_name = settings.clone();
+        bad_name.rollups.definitions[0].name = "revenue/hourly".to_string();
+        let err = bad_name.validate().unwrap_err();
+        assert!(err.to_string().contains("name 'revenue/hourly'"), "{}", err);
+
+        let mut bad_path = settings;
+        bad_path.rollups.definitions[0].group_by = vec!["geo.".to_string()];
+        let err = bad_path.validate().unwrap_err();
+        assert!(err.to_string().contains("dotted payload path"), "{}", err);
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+//! Threshold alert rules over the stream of stored events.
+//!
+//! A rule watches the events of one `event_type` (optionally narrowed by
+//! payload fields) over a sliding window and is breached when:
+//!
+//! - `count`: more than `threshold` matching events arrived in the window
+//! - `rate`: more than `threshold` matching events per second arrived in the window
+//! - `absence`: no matching event arrived in the window
+//!
+//! A breached rule is `pending` until it has stayed breached for `for_duration`,
+//! then `firing`; once no longer breached a firing rule is `resolved`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use utoipa::ToSchema;
+
+use super::{Event, EventFilter};
+
+/// What breaches an alert rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertCondition {
+    Count,
+    Rate,
+    Absence,
+}
+
+/// Lifecycle state of an alert rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertState {
+    /// Never breached, or stopped being breached before firing.
+    Inactive,
+    /// Breached, waiting for `for_duration` to pass.
+    Pending,
+    Firing,
+    /// Fired, and no longer breached.
+    Resolved,
+}
+
+impl AlertState {
+    // ---
+
+    /// Every state, in lifecycle order.
+    pub const ALL: [AlertState; 4] = [
+        AlertState::Inactive,
+        AlertState::Pending,
+        AlertState::Firing,
+        AlertState::Resolved,
+    ];
+
+    /// Lower-case name, as used in JSON and metric labels.
+    pub fn as_str(self) -> &'static str {
+        // ---
+        match self {
+            AlertState::Inactive => "inactive",
+            AlertState::Pending => "pending",
+            AlertState::Firing => "firing",
+            AlertState::Resolved => "resolved",
+        }
+    }
+}
+
+/// A configured alert rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertRule {
+    // ---
+    /// Unique name, used in `GET /v1/alerts`, metric labels and notifications.
+    pub name: String,
+
+    /// Events that are watched.
+    pub events: EventFilter,
+
+    pub condition: AlertCondition,
+
+    /// Limit of a `count` or `rate` condition; unused by `absence`.
+    pub threshold: f64,
+
+    /// Length of the sliding window.
+    pub window: Duration,
+
+    /// How long the rule must stay breached before it fires.
+    pub for_duration: Duration,
+
+    /// Endpoint notified when the rule fires or resolves.
+    pub webhook_url: Option<String>,
+
+    /// Key of the HMAC-SHA256 signature sent with notifications.
+    pub webhook_secret: Option<String>,
+}
+
+impl AlertRule {
+    // ---
+
+    /// Whether `event` counts towards this rule.
+    pub fn matches(&self, event: &Event) -> bool {
+        self.events.matches(event)
+    }
+}
+
+/// Current state of one alert rule, as reported by `GET /v1/alerts`.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct AlertStatus {
+    // ---
+    pub rule: String,
+    pub event_type: String,
+    pub condition: AlertCondition,
+    pub threshold: f64,
+    pub window_secs: u64,
+    pub state: AlertState,
+
+    /// Matching events in the window (`count`), events per second (`rate`)
+    /// or seconds since the last matching event (`absence`).
+    pub value: f64,
+
+    /// When the rule entered its current state.
+    pub since: DateTime<Utc>,
+}
@@ -0,0 +1,331 @@
+//! Cohort retention over stored events.
+//!
+//! Actors (identified by a payload key) are grouped into cohorts by the
+//! calendar period (UTC day, ISO week or month) of their first "birth"
+//! event. For each cohort, every period from the birth period onwards
+//! counts the actors that performed a "return" event in it. The time range
+//! selects cohorts by birth time; returns are counted whenever they happen.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use utoipa::ToSchema;
+
+use super::{Event, EventQuery};
+
+/// Length of a cohort period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CohortPeriod {
+    /// UTC calendar day.
+    Day,
+    /// ISO week, starting on Monday.
+    Week,
+    /// Calendar month.
+    Month,
+}
+
+impl CohortPeriod {
+    // ---
+
+    /// First day of the period containing `date`.
+    fn start_of(self, date: NaiveDate) -> NaiveDate {
+        // ---
+        match self {
+            CohortPeriod::Day => date,
+            CohortPeriod::Week => {
+                date - Duration::days(i64::from(date.weekday().num_days_from_monday()))
+            }
+            CohortPeriod::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// Whole periods from the period starting `from` to the one starting `to`.
+    fn between(self, from: NaiveDate, to: NaiveDate) -> i64 {
+        // ---
+        match self {
+            CohortPeriod::Day => (to - from).num_days(),
+            CohortPeriod::Week => (to - from).num_days() / 7,
+            CohortPeriod::Month => {
+                let months =
+                    |date: NaiveDate| i64::from(date.year()) * 12 + i64::from(date.month0());
+                months(to) - months(from)
+            }
+        }
+    }
+}
+
+/// A retention table to compute.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CohortQuery {
+    // ---
+    /// Event type whose first occurrence places an actor in a cohort.
+    pub birth_event: String,
+
+    /// Event type that counts as coming back.
+    pub return_event: String,
+
+    /// Dotted payload path identifying the actor (e.g. `user_id`).
+    pub key: String,
+
+    pub period: CohortPeriod,
+
+    /// Periods reported after the birth period.
+    pub periods: usize,
+
+    /// Earliest birth (inclusive).
+    pub start: Option<DateTime<Utc>>,
+
+    /// Latest birth (inclusive).
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl CohortQuery {
+    // ---
+
+    /// Event queries returning every event the table looks at.
+    ///
+    /// Births are read without a lower bound, so an actor born before
+    /// `start` is not mistaken for a new one; returns cannot precede the
+    /// period containing `start`.
+    pub fn event_queries(&self) -> Vec<EventQuery> {
+        // ---
+        if self.birth_event == self.return_event {
+            return vec![EventQuery {
+                event_type: Some(self.birth_event.clone()),
+                start: None,
+                end: None,
+            }];
+        }
+        vec![
+            EventQuery {
+                event_type: Some(self.birth_event.clone()),
+                start: None,
+                end: self.end,
+            },
+            EventQuery {
+                event_type: Some(self.return_event.clone()),
+                start: self.returns_from(),
+                end: None,
+            },
+        ]
+    }
+
+    /// Start of the period containing `start`: the earliest time a return can count.
+    fn returns_from(&self) -> Option<DateTime<Utc>> {
+        // ---
+        self.start.map(|start| {
+            self.period
+                .start_of(start.date_naive())
+                .and_time(NaiveTime::MIN)
+                .and_utc()
+        })
+    }
+}
+
+/// Retention of one cohort.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct Cohort {
+    // ---
+    /// Start of the birth period.
+    pub start: DateTime<Utc>,
+
+    /// Actors born in the period.
+    pub size: u64,
+
+    /// Actors with a return event in each period; index 0 is the birth period.
+    pub retained: Vec<u64>,
+
+    /// `retained` as a share of `size`.
+    pub retention_rates: Vec<f64>,
+}
+
+/// Result of a retention computation.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct CohortReport {
+    // ---
+    pub period: CohortPeriod,
+
+    /// Cohorts by birth period, oldest first.
+    pub cohorts: Vec<Cohort>,
+}
+
+/// Accumulates events, in any order, into a retention table.
+pub struct CohortCounter<'q> {
+    query: &'q CohortQuery,
+    births: HashMap<String, DateTime<Utc>>,
+    returns: HashMap<String, Vec<DateTime<Utc>>>,
+    returns_from: Option<DateTime<Utc>>,
+}
+
+impl<'q> CohortCounter<'q> {
+    // ---
+
+    pub fn new(query: &'q CohortQuery) -> Self {
+        Self {
+            query,
+            births: HashMap::new(),
+            returns: HashMap::new(),
+            returns_from: query.returns_from(),
+        }
+    }
+
+    /// Records `event` as a birth and/or return, if it is of those types and carries the key.
+    pub fn add(&mut self, event: &Event) {
+        // ---
+        let is_birth = event.event_type == self.query.birth_event;
+        let is_return = event.event_type == self.query.return_event;
+        if !is_birth && !is_return {
+            return;
+        }
+        let Some(actor) = event.payload_key(&self.query.key) else {
+            return;
+        };
+        if is_return && self.returns_from.is_none_or(|from| event.timestamp >= from) {
+            self.returns
+                .entry(actor.clone())
+                .or_default()
+                .push(event.timestamp);
+        }
+        if is_birth {
+            let birth = self.births.entry(actor).or_insert(event.timestamp);
+            *birth = (*birth).min(event.timestamp);
+        }
+    }
+
+    pub fn finish(self) -> CohortReport {
+        // ---
+        let period = self.query.period;
+        let range = EventQuery {
+            event_type: None,
+            start: self.query.start,
+            end: self.query.end,
+        };
+
+        // Birth period → (size, actors retained per period)
+        let mut cohorts: BTreeMap<NaiveDate, (u64, Vec<u64>)> = BTreeMap::new();
+        for (actor, birth) in &self.births {
+            if !range.contains(*birth) {
+                continue;
+            }
+            let cohort_start = period.start_of(birth.date_naive());
+            let mut seen = vec![false; self.query.periods + 1];
+            for returned in self.returns.get(actor).into_iter().flatten() {
+                let index = period.between(cohort_start, period.start_of(returned.date_naive()));
+                if let Some(slot) = usize::try_from(index)
+                    .ok()
+                    .and_then(|index| seen.get_mut(index))
+                {
+                    *slot = true;
+                }
+            }
+
+            let (size, retained) = cohorts
+                .entry(cohort_start)
+                .or_insert_with(|| (0, vec![0; self.query.periods + 1]));
+            *size += 1;
+            for (count, seen) in retained.iter_mut().zip(seen) {
+                *count += u64::from(seen);
+            }
+        }
+
+        let cohorts = cohorts
+            .into_iter()
+            .map(|(start, (size, retained))| Cohort {
+                start: start.and_time(NaiveTime::MIN).and_utc(),
+                size,
+                retention_rates: retained
+                    .iter()
+                    .map(|count| *count as f64 / size as f64)
+                    .collect(),
+                retained,
+            })
+            .collect();
+        CohortReport { period, cohorts }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+    use crate::domain::EventBuilder;
+    use serde_json::json;
+
+    fn query(period: CohortPeriod) -> CohortQuery {
+        CohortQuery {
+            birth_event: "signup".to_string(),
+            return_event: "login".to_string(),
+            key: "user_id".to_string(),
+            period,
+            periods: 2,
+            start: None,
+            end: None,
+        }
+    }
+
+    #[test]
+    fn weekly_cohorts_count_returns_per_week() {
+        // ---
+        let query = query(CohortPeriod::Week);
+        let mut counter = CohortCounter::new(&query);
+        // 2025-06-16 and 2025-06-23 are Mondays
+        for (event_type, user, date) in [
+            ("login", "a", "2025-06-24"),
+            ("signup", "a", "2025-06-18"),
+            ("login", "a", "2025-06-19"),
+            ("login", "a", "2025-06-20"),
+            ("signup", "b", "2025-06-22"),
+            ("login", "b", "2025-07-01"),
+            // A later signup does not move b to another cohort
+            ("signup", "b", "2025-06-30"),
+            ("signup", "c", "2025-06-23"),
+            // Returns beyond the reported periods are ignored
+            ("login", "c", "2025-08-01"),
+        ] {
+            let event = EventBuilder::new(event_type)
+                .at(&format!("{}T12:00:00Z", date))
+                .payload(json!({ "user_id": user }))
+                .build();
+            counter.add(&event);
+        }
+
+        let report = counter.finish();
+        assert_eq!(report.cohorts.len(), 2);
+        let first = &report.cohorts[0];
+        assert_eq!(first.start.to_rfc3339(), "2025-06-16T00:00:00+00:00");
+        assert_eq!(first.size, 2);
+        assert_eq!(first.retained, [1, 1, 1]);
+        assert_eq!(first.retention_rates, [0.5, 0.5, 0.5]);
+        let second = &report.cohorts[1];
+        assert_eq!((second.size, second.retained.clone()), (1, vec![0, 0, 0]));
+    }
+
+    #[test]
+    fn monthly_periods_follow_the_calendar() {
+        // ---
+        let mut query = query(CohortPeriod::Month);
+        query.start = Some("2025-01-01T00:00:00Z".parse().unwrap());
+        let mut counter = CohortCounter::new(&query);
+        for (event_type, user, date) in [
+            ("signup", "a", "2025-01-31"),
+            ("login", "a", "2025-02-01"),
+            ("login", "a", "2025-03-31"),
+            // Born before the range: not a cohort member
+            ("signup", "b", "2024-12-31"),
+            ("signup", "b", "2025-01-02"),
+        ] {
+            let event = EventBuilder::new(event_type)
+                .at(&format!("{}T12:00:00Z", date))
+                .payload(json!({ "user_id": user }))
+                .build();
+            counter.add(&event);
+        }
+
+        let report = counter.finish();
+        assert_eq!(report.cohorts.len(), 1);
+        assert_eq!(report.cohorts[0].size, 1);
+        assert_eq!(report.cohorts[0].retained, [0, 1, 1]);
+    }
+}
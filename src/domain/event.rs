@@ -6,10 +6,12 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 /// Represents a single event submitted to the tracking system.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Event {
     // ---
     /// Unique identifier for the event.
@@ -22,5 +24,102 @@ pub struct Event {
     pub timestamp: DateTime<Utc>,
 
     /// Arbitrary structured payload data associated with the event.
+    #[schema(value_type = Object)]
     pub payload: serde_json::Value,
+
+    /// Trace context of the request that submitted the event, if it was traced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<TraceContext>,
+}
+
+impl Event {
+    // ---
+
+    /// Whether the payload carries every top-level field of `filter` with exactly its value.
+    pub fn has_payload_fields(&self, filter: &Map<String, Value>) -> bool {
+        // ---
+        filter
+            .iter()
+            .all(|(key, expected)| self.payload.get(key) == Some(expected))
+    }
+
+    /// Value at a dotted payload path (e.g. `user.id`).
+    pub fn payload_value(&self, path: &str) -> Option<&Value> {
+        // ---
+        path.split('.')
+            .try_fold(&self.payload, |value, field| value.get(field))
+    }
+
+    /// Value at a dotted payload path (e.g. `user.id`) as a grouping key.
+    ///
+    /// Strings are used as they are and other values in their JSON form;
+    /// a missing or `null` value gives `None`.
+    pub fn payload_key(&self, path: &str) -> Option<String> {
+        // ---
+        match self.payload_value(path)? {
+            Value::Null => None,
+            Value::String(s) => Some(s.clone()),
+            other => Some(other.to_string()),
+        }
+    }
+}
+
+/// Whether `path` is a dotted payload path, as read by [`Event::payload_value`].
+pub fn is_payload_path(path: &str) -> bool {
+    // ---
+    !path.is_empty() && !path.split('.').any(str::is_empty)
+}
+
+/// Builds events for unit tests, defaulting what a test does not care about.
+#[cfg(test)]
+pub(crate) struct EventBuilder {
+    event: Event,
+}
+
+#[cfg(test)]
+impl EventBuilder {
+    // ---
+
+    /// An event of `event_type` with an empty payload, timestamped now.
+    pub fn new(event_type: &str) -> Self {
+        Self {
+            event: Event {
+                id: Uuid::new_v4(),
+                event_type: event_type.to_string(),
+                timestamp: Utc::now(),
+                payload: Value::Object(Map::new()),
+                trace: None,
+            },
+        }
+    }
+
+    pub fn payload(mut self, payload: Value) -> Self {
+        self.event.payload = payload;
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.event.timestamp = timestamp;
+        self
+    }
+
+    /// Sets the timestamp from RFC 3339 text; panics if it does not parse.
+    pub fn at(self, timestamp: &str) -> Self {
+        self.timestamp(timestamp.parse().expect("RFC 3339 timestamp"))
+    }
+
+    pub fn build(self) -> Event {
+        self.event
+    }
+}
+
+/// W3C trace and span IDs linking an event to the request that produced it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TraceContext {
+    // ---
+    /// 32 hex digit trace ID.
+    pub trace_id: String,
+
+    /// 16 hex digit ID of the span that handled the request.
+    pub span_id: String,
 }
@@ -0,0 +1,33 @@
+//! Selection of stored events by type and payload fields.
+//!
+//! Webhook subscriptions, alert rules and rollups each act on the events
+//! one `EventFilter` selects.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::Event;
+
+/// Events of one type, optionally narrowed by top-level payload fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventFilter {
+    // ---
+    pub event_type: String,
+
+    /// Top-level payload fields an event must carry with exactly these values.
+    pub filter: Option<Map<String, Value>>,
+}
+
+impl EventFilter {
+    // ---
+
+    /// Whether `event` is selected.
+    pub fn matches(&self, event: &Event) -> bool {
+        // ---
+        event.event_type == self.event_type
+            && self
+                .filter
+                .as_ref()
+                .is_none_or(|filter| event.has_payload_fields(filter))
+    }
+}
@@ -19,3 +19,14 @@ pub struct EventQuery {
     /// Optional end of time range (inclusive).
     pub end: Option<DateTime<Utc>>,
 }
+
+impl EventQuery {
+    // ---
+
+    /// Whether `timestamp` lies in the time range.
+    pub fn contains(&self, timestamp: DateTime<Utc>) -> bool {
+        // ---
+        self.start.is_none_or(|start| timestamp >= start)
+            && self.end.is_none_or(|end| timestamp <= end)
+    }
+}
@@ -0,0 +1,174 @@
+//! Catalog of the stored event types.
+//!
+//! Each type is reported with its event count, first and last timestamps
+//! and the shape of its payloads. The shape is inferred from the
+//! `SHAPE_SAMPLE_SIZE` most recent events of the type, so fields a
+//! producer stopped sending long ago don't linger in it.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+use super::{Event, PayloadField, PayloadShape};
+
+/// Most recent events per type whose payloads make up its shape.
+pub const SHAPE_SAMPLE_SIZE: usize = 100;
+
+/// One stored event type.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct EventTypeSummary {
+    // ---
+    pub event_type: String,
+
+    /// Number of stored events.
+    pub count: u64,
+
+    /// Earliest event timestamp.
+    pub first_seen: DateTime<Utc>,
+
+    /// Latest event timestamp.
+    pub last_seen: DateTime<Utc>,
+
+    /// Recent events the payload shape was inferred from.
+    pub sampled: u64,
+
+    /// Fields of the sampled payloads, ordered by path.
+    pub fields: Vec<PayloadField>,
+}
+
+/// Counts and recent payloads of one type.
+struct TypeEntry {
+    count: u64,
+    first_seen: DateTime<Utc>,
+    last_seen: DateTime<Utc>,
+
+    /// Candidate sample, trimmed to the most recent once it doubles in size.
+    recent: Vec<(DateTime<Utc>, Value)>,
+
+    /// Events before this are too old to enter the sample.
+    cutoff: Option<DateTime<Utc>>,
+}
+
+/// Accumulates events, in any order, into per-type summaries.
+pub struct EventTypeCounter {
+    sample_size: usize,
+    types: BTreeMap<String, TypeEntry>,
+}
+
+impl EventTypeCounter {
+    // ---
+
+    /// A counter inferring shapes from the `sample_size` most recent events per type.
+    pub fn new(sample_size: usize) -> Self {
+        Self {
+            sample_size: sample_size.max(1),
+            types: BTreeMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, event: &Event) {
+        // ---
+        let timestamp = event.timestamp;
+        let entry = self
+            .types
+            .entry(event.event_type.clone())
+            .or_insert_with(|| TypeEntry {
+                count: 0,
+                first_seen: timestamp,
+                last_seen: timestamp,
+                recent: Vec::new(),
+                cutoff: None,
+            });
+        entry.count += 1;
+        entry.first_seen = entry.first_seen.min(timestamp);
+        entry.last_seen = entry.last_seen.max(timestamp);
+
+        if entry.cutoff.is_some_and(|cutoff| timestamp < cutoff) {
+            return;
+        }
+        entry.recent.push((timestamp, event.payload.clone()));
+        if entry.recent.len() >= 2 * self.sample_size {
+            trim(&mut entry.recent, self.sample_size);
+            entry.cutoff = entry.recent.last().map(|(timestamp, _)| *timestamp);
+        }
+    }
+
+    /// Summaries ordered by event type.
+    pub fn finish(self) -> Vec<EventTypeSummary> {
+        // ---
+        self.types
+            .into_iter()
+            .map(|(event_type, mut entry)| {
+                trim(&mut entry.recent, self.sample_size);
+                let mut shape = PayloadShape::default();
+                entry
+                    .recent
+                    .iter()
+                    .for_each(|(_, payload)| shape.add(payload));
+                EventTypeSummary {
+                    event_type,
+                    count: entry.count,
+                    first_seen: entry.first_seen,
+                    last_seen: entry.last_seen,
+                    sampled: shape.samples(),
+                    fields: shape.fields(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Keeps the `size` most recent payloads, newest first.
+fn trim(recent: &mut Vec<(DateTime<Utc>, Value)>, size: usize) {
+    // ---
+    recent.sort_by_key(|(timestamp, _)| std::cmp::Reverse(*timestamp));
+    recent.truncate(size);
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+    use crate::domain::EventBuilder;
+    use serde_json::json;
+
+    #[test]
+    fn types_are_summarised_from_their_most_recent_payloads() {
+        // ---
+        let mut counter = EventTypeCounter::new(3);
+        // Newest last, but added in reverse to show order doesn't matter
+        for minute in (0..10).rev() {
+            let payload = if minute < 5 {
+                json!({ "legacy": true })
+            } else {
+                json!({ "user_id": "u1", "plan": minute })
+            };
+            counter.add(
+                &EventBuilder::new("signup")
+                    .at(&format!("2025-06-16T10:{:02}:00Z", minute))
+                    .payload(payload)
+                    .build(),
+            );
+        }
+        counter.add(
+            &EventBuilder::new("login")
+                .at("2025-06-16T10:30:00Z")
+                .payload(json!({ "user_id": "u1" }))
+                .build(),
+        );
+
+        let summaries = counter.finish();
+        let types: Vec<&str> = summaries.iter().map(|s| s.event_type.as_str()).collect();
+        assert_eq!(types, ["login", "signup"]);
+
+        let signup = &summaries[1];
+        assert_eq!((signup.count, signup.sampled), (10, 3));
+        assert_eq!(signup.first_seen.to_rfc3339(), "2025-06-16T10:00:00+00:00");
+        assert_eq!(signup.last_seen.to_rfc3339(), "2025-06-16T10:09:00+00:00");
+        let fields: Vec<&str> = signup.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(fields, ["plan", "user_id"]);
+    }
+}
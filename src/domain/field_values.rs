@@ -0,0 +1,273 @@
+//! Distinct and most frequent values of a payload field.
+//!
+//! Values are counted exactly while there are few of them. An
+//! `approximate` query, or one that runs into more than
+//! `MAX_EXACT_VALUES` distinct values, is answered from a `FieldSketch`
+//! instead, whose memory does not grow with the number of values.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use super::sketch::{CountMinSketch, HyperLogLog};
+use super::{Event, EventQuery};
+
+/// Distinct values counted exactly before falling back to a sketch.
+pub const MAX_EXACT_VALUES: usize = 100_000;
+
+/// Values of one payload field to summarise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldValueQuery {
+    // ---
+    pub event_type: String,
+
+    /// Dotted payload path (e.g. `user.id`).
+    pub field: String,
+
+    /// Earliest event (inclusive).
+    pub start: Option<DateTime<Utc>>,
+
+    /// Latest event (inclusive).
+    pub end: Option<DateTime<Utc>>,
+
+    /// Most frequent values reported.
+    pub limit: usize,
+
+    /// Use sketches even when exact counting would fit.
+    pub approximate: bool,
+}
+
+impl FieldValueQuery {
+    // ---
+
+    /// Events the summary is computed from.
+    pub fn event_query(&self) -> EventQuery {
+        // ---
+        EventQuery {
+            event_type: Some(self.event_type.clone()),
+            start: self.start,
+            end: self.end,
+        }
+    }
+}
+
+/// A value and how many events carried it.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ValueCount {
+    pub value: String,
+    pub count: u64,
+}
+
+/// Summary of a payload field's values.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct FieldValueReport {
+    // ---
+    pub event_type: String,
+    pub field: String,
+
+    /// Events carrying the field.
+    pub events: u64,
+
+    /// Distinct values.
+    pub distinct: u64,
+
+    /// Most frequent values, most frequent first, at most `limit`.
+    pub top: Vec<ValueCount>,
+
+    /// Whether `distinct` and the counts in `top` are estimates.
+    pub approximate: bool,
+}
+
+/// Sketches of one field's values.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FieldSketch {
+    pub events: u64,
+    distinct: HyperLogLog,
+    frequent: CountMinSketch,
+}
+
+impl FieldSketch {
+    // ---
+
+    /// Counts `value` carried by `count` events.
+    pub fn add(&mut self, value: &str, count: u64) {
+        // ---
+        self.events += count;
+        self.distinct.insert(value);
+        self.frequent.add(value, count);
+    }
+
+    pub fn merge(&mut self, other: &FieldSketch) {
+        // ---
+        self.events += other.events;
+        self.distinct.merge(&other.distinct);
+        self.frequent.merge(&other.frequent);
+    }
+}
+
+/// Exact counts, or a sketch once they are too many or not wanted.
+enum Tally {
+    Exact(HashMap<String, u64>),
+    Sketched(FieldSketch),
+}
+
+/// Accumulates events and sketches into a `FieldValueReport`.
+pub struct FieldValueCounter<'q> {
+    query: &'q FieldValueQuery,
+    tally: Tally,
+}
+
+impl<'q> FieldValueCounter<'q> {
+    // ---
+
+    pub fn new(query: &'q FieldValueQuery) -> Self {
+        // ---
+        let tally = if query.approximate {
+            Tally::Sketched(FieldSketch::default())
+        } else {
+            Tally::Exact(HashMap::new())
+        };
+        Self { query, tally }
+    }
+
+    /// Counts the field's value in `event`, if it carries one; type and range are the caller's to check.
+    pub fn add(&mut self, event: &Event) {
+        // ---
+        let Some(value) = event.payload_key(&self.query.field) else {
+            return;
+        };
+        match &mut self.tally {
+            Tally::Exact(counts) => {
+                *counts.entry(value).or_default() += 1;
+                if counts.len() > MAX_EXACT_VALUES {
+                    self.sketched();
+                }
+            }
+            Tally::Sketched(sketch) => sketch.add(&value, 1),
+        }
+    }
+
+    /// Adds the values summarised by `sketch`, switching to approximate counting.
+    pub fn merge(&mut self, sketch: &FieldSketch) {
+        self.sketched().merge(sketch);
+    }
+
+    /// The sketch, built from the exact counts so far if there is none yet.
+    fn sketched(&mut self) -> &mut FieldSketch {
+        // ---
+        if let Tally::Exact(counts) = &mut self.tally {
+            let mut sketch = FieldSketch::default();
+            for (value, count) in counts.drain() {
+                sketch.add(&value, count);
+            }
+            self.tally = Tally::Sketched(sketch);
+        }
+        match &mut self.tally {
+            Tally::Sketched(sketch) => sketch,
+            Tally::Exact(_) => unreachable!("tally was just sketched"),
+        }
+    }
+
+    pub fn finish(self) -> FieldValueReport {
+        // ---
+        let limit = self.query.limit;
+        let (events, distinct, top, approximate) = match self.tally {
+            Tally::Exact(counts) => {
+                let mut top: Vec<(String, u64)> = counts.into_iter().collect();
+                let distinct = top.len() as u64;
+                let events = top.iter().map(|(_, count)| count).sum();
+                top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                top.truncate(limit);
+                (events, distinct, top, false)
+            }
+            Tally::Sketched(sketch) => {
+                // The estimate can't exceed the events counted
+                let distinct = sketch.distinct.estimate().min(sketch.events);
+                (sketch.events, distinct, sketch.frequent.top(limit), true)
+            }
+        };
+        FieldValueReport {
+            event_type: self.query.event_type.clone(),
+            field: self.query.field.clone(),
+            events,
+            distinct,
+            top: top
+                .into_iter()
+                .map(|(value, count)| ValueCount { value, count })
+                .collect(),
+            approximate,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+    use crate::domain::EventBuilder;
+    use serde_json::json;
+
+    fn query(approximate: bool) -> FieldValueQuery {
+        FieldValueQuery {
+            event_type: "login".to_string(),
+            field: "source".to_string(),
+            start: None,
+            end: None,
+            limit: 2,
+            approximate,
+        }
+    }
+
+    #[test]
+    fn exact_and_approximate_counts_agree_on_small_inputs() {
+        // ---
+        let events: Vec<Event> = ["ads", "direct", "ads", "email", "direct", "ads"]
+            .into_iter()
+            .map(|source| {
+                EventBuilder::new("login")
+                    .payload(json!({ "source": source }))
+                    .build()
+            })
+            .chain([EventBuilder::new("login").payload(json!({})).build()])
+            .collect();
+
+        for approximate in [false, true] {
+            let query = query(approximate);
+            let mut counter = FieldValueCounter::new(&query);
+            events.iter().for_each(|event| counter.add(event));
+            let report = counter.finish();
+
+            assert_eq!(report.approximate, approximate);
+            assert_eq!((report.events, report.distinct), (6, 3));
+            let top: Vec<(&str, u64)> = report
+                .top
+                .iter()
+                .map(|v| (v.value.as_str(), v.count))
+                .collect();
+            assert_eq!(top, [("ads", 3), ("direct", 2)]);
+        }
+    }
+
+    #[test]
+    fn merging_a_sketch_makes_the_report_approximate() {
+        // ---
+        let query = query(false);
+        let mut sketch = FieldSketch::default();
+        sketch.add("ads", 5);
+
+        let mut counter = FieldValueCounter::new(&query);
+        counter.add(
+            &EventBuilder::new("login")
+                .payload(json!({ "source": "direct" }))
+                .build(),
+        );
+        counter.merge(&sketch);
+        let report = counter.finish();
+
+        assert!(report.approximate);
+        assert_eq!((report.events, report.distinct), (6, 2));
+        assert_eq!(report.top[0].value, "ads");
+    }
+}
@@ -0,0 +1,288 @@
+//! Ordered conversion funnels over stored events.
+//!
+//! Events are grouped into actors by a payload key (e.g. `user_id`). An
+//! actor enters the funnel with an event of the first step type and
+//! reaches each further step with an event of that type that follows the
+//! previous step, within `window` of the entry. Only events inside the
+//! time range are considered. When an actor entered several times, the
+//! entry that got furthest counts.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use super::{Event, EventQuery};
+
+/// A funnel to compute.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunnelQuery {
+    // ---
+    /// Event types of the steps, in order.
+    pub steps: Vec<String>,
+
+    /// Dotted payload path identifying the actor (e.g. `user_id`).
+    pub key: String,
+
+    /// Longest time from entering the funnel to reaching a later step.
+    pub window: Duration,
+
+    /// Start of the time range (inclusive).
+    pub start: Option<DateTime<Utc>>,
+
+    /// End of the time range (inclusive).
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl FunnelQuery {
+    // ---
+
+    /// Event queries returning every event the funnel looks at, one per step type.
+    pub fn event_queries(&self) -> Vec<EventQuery> {
+        // ---
+        let mut types: Vec<&String> = self.steps.iter().collect();
+        types.sort();
+        types.dedup();
+        types
+            .into_iter()
+            .map(|event_type| EventQuery {
+                event_type: Some(event_type.clone()),
+                start: self.start,
+                end: self.end,
+            })
+            .collect()
+    }
+}
+
+/// Actors reaching one step.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct FunnelStep {
+    // ---
+    pub event_type: String,
+
+    /// Actors that reached this step.
+    pub count: u64,
+
+    /// Share of the previous step's actors that reached this one (1 for the first step).
+    pub conversion_rate: f64,
+
+    /// Share of the actors entering the funnel that reached this step.
+    pub overall_conversion_rate: f64,
+}
+
+/// Result of a funnel computation.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct FunnelReport {
+    // ---
+    pub steps: Vec<FunnelStep>,
+
+    /// Actors that entered the funnel.
+    pub entered: u64,
+
+    /// Actors that reached the last step.
+    pub completed: u64,
+}
+
+/// Time of an actor's event and the indexes of the steps it matches.
+type StepHit = (DateTime<Utc>, Vec<usize>);
+
+/// Accumulates events, in any order, into a funnel report.
+///
+/// Only the timestamp and matching steps of each event are kept, grouped by
+/// actor, so repositories can feed it stored events by reference.
+pub struct FunnelCounter<'q> {
+    query: &'q FunnelQuery,
+    range: EventQuery,
+    actors: HashMap<String, Vec<StepHit>>,
+}
+
+impl<'q> FunnelCounter<'q> {
+    // ---
+
+    pub fn new(query: &'q FunnelQuery) -> Self {
+        Self {
+            query,
+            range: EventQuery {
+                event_type: None,
+                start: query.start,
+                end: query.end,
+            },
+            actors: HashMap::new(),
+        }
+    }
+
+    /// Counts `event` if it is in range, is of a step type and carries the key.
+    pub fn add(&mut self, event: &Event) {
+        // ---
+        if !self.range.contains(event.timestamp) {
+            return;
+        }
+        let steps: Vec<usize> = self
+            .query
+            .steps
+            .iter()
+            .enumerate()
+            .filter(|(_, step)| **step == event.event_type)
+            .map(|(index, _)| index)
+            .collect();
+        if steps.is_empty() {
+            return;
+        }
+        if let Some(actor) = event.payload_key(&self.query.key) {
+            self.actors
+                .entry(actor)
+                .or_default()
+                .push((event.timestamp, steps));
+        }
+    }
+
+    pub fn finish(self) -> FunnelReport {
+        // ---
+        let step_count = self.query.steps.len();
+        let mut counts = vec![0u64; step_count];
+        for (_, mut events) in self.actors {
+            events.sort_by_key(|(timestamp, _)| *timestamp);
+            let depth = deepest_step(&events, step_count, self.query.window);
+            for count in &mut counts[..depth] {
+                *count += 1;
+            }
+        }
+
+        let entered = counts.first().copied().unwrap_or(0);
+        let rate = |part: u64, whole: u64| {
+            if whole == 0 {
+                0.0
+            } else {
+                part as f64 / whole as f64
+            }
+        };
+        let steps = self
+            .query
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(index, event_type)| FunnelStep {
+                event_type: event_type.clone(),
+                count: counts[index],
+                conversion_rate: match index {
+                    0 => rate(counts[0], counts[0]),
+                    _ => rate(counts[index], counts[index - 1]),
+                },
+                overall_conversion_rate: rate(counts[index], entered),
+            })
+            .collect();
+        FunnelReport {
+            steps,
+            entered,
+            completed: counts.last().copied().unwrap_or(0),
+        }
+    }
+}
+
+/// Number of steps one actor reached, given their events sorted by time.
+///
+/// `reached[i]` holds the latest entry time of a chain that got to step
+/// `i`; the latest entry leaves the most of the window for later steps.
+fn deepest_step(events: &[StepHit], step_count: usize, window: Duration) -> usize {
+    // ---
+    let mut reached: Vec<Option<DateTime<Utc>>> = vec![None; step_count];
+    for (timestamp, steps) in events {
+        // Later steps first, so one event never advances a chain twice
+        for &step in steps.iter().rev() {
+            if step == 0 {
+                reached[0] = Some(*timestamp);
+            } else if let Some(entry) = reached[step - 1] {
+                if *timestamp - entry <= window {
+                    reached[step] = reached[step].max(Some(entry));
+                }
+            }
+        }
+    }
+    reached
+        .iter()
+        .rposition(Option::is_some)
+        .map_or(0, |last| last + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+    use crate::domain::EventBuilder;
+    use serde_json::json;
+
+    fn query(window_minutes: i64) -> FunnelQuery {
+        FunnelQuery {
+            steps: vec!["signup".into(), "activate".into(), "purchase".into()],
+            key: "user_id".to_string(),
+            window: Duration::minutes(window_minutes),
+            start: None,
+            end: None,
+        }
+    }
+
+    #[test]
+    fn steps_must_follow_in_order_within_the_window() {
+        // ---
+        let query = query(60);
+        let mut counter = FunnelCounter::new(&query);
+        let events = [
+            // a: completes, fed out of order
+            ("purchase", "a", 30),
+            ("signup", "a", 0),
+            ("activate", "a", 10),
+            // b: purchase before activation does not count
+            ("signup", "b", 0),
+            ("purchase", "b", 5),
+            ("activate", "b", 10),
+            // c: activates too late
+            ("signup", "c", 0),
+            ("activate", "c", 61),
+            // d: a second signup gets further than the first
+            ("signup", "d", 0),
+            ("signup", "d", 100),
+            ("activate", "d", 120),
+            ("purchase", "d", 150),
+            // never entered
+            ("activate", "e", 0),
+        ];
+        for (event_type, user, minute) in events {
+            let event = EventBuilder::new(event_type)
+                .timestamp(DateTime::<Utc>::UNIX_EPOCH + Duration::minutes(minute))
+                .payload(json!({ "user_id": user }))
+                .build();
+            counter.add(&event);
+        }
+
+        let report = counter.finish();
+        let counts: Vec<u64> = report.steps.iter().map(|s| s.count).collect();
+        assert_eq!(counts, [4, 3, 2]);
+        assert_eq!((report.entered, report.completed), (4, 2));
+        assert_eq!(report.steps[0].conversion_rate, 1.0);
+        assert_eq!(report.steps[1].conversion_rate, 0.75);
+        assert!((report.steps[2].conversion_rate - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(report.steps[2].overall_conversion_rate, 0.5);
+    }
+
+    #[test]
+    fn repeated_step_types_need_separate_events() {
+        // ---
+        let query = FunnelQuery {
+            steps: vec!["visit".into(), "visit".into()],
+            ..query(60)
+        };
+        let mut counter = FunnelCounter::new(&query);
+        for (user, minute) in [("a", 0), ("b", 0), ("b", 5)] {
+            let event = EventBuilder::new("visit")
+                .timestamp(DateTime::<Utc>::UNIX_EPOCH + Duration::minutes(minute))
+                .payload(json!({ "user_id": user }))
+                .build();
+            counter.add(&event);
+        }
+
+        let report = counter.finish();
+        assert_eq!((report.entered, report.completed), (2, 1));
+        assert_eq!(query.event_queries().len(), 1);
+    }
+}
@@ -0,0 +1,55 @@
+//! Health reporting shared by pluggable backends.
+//!
+//! Repositories and metrics backends report their own state through
+//! `ComponentHealth`; the API aggregates these for readiness probes.
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Health of a single backend component.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ComponentHealth {
+    // ---
+    /// Component name (e.g. "repository", "metrics").
+    pub component: String,
+
+    /// Backend implementation in use (e.g. "memory", "prometheus").
+    pub backend: String,
+
+    /// Whether the component can currently serve requests.
+    pub healthy: bool,
+
+    /// Optional human-readable detail.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl ComponentHealth {
+    // ---
+
+    /// A healthy component.
+    pub fn healthy(component: &str, backend: &str) -> Self {
+        Self {
+            component: component.to_string(),
+            backend: backend.to_string(),
+            healthy: true,
+            detail: None,
+        }
+    }
+
+    /// An unhealthy component, with the reason.
+    pub fn unhealthy(component: &str, backend: &str, detail: impl Into<String>) -> Self {
+        Self {
+            component: component.to_string(),
+            backend: backend.to_string(),
+            healthy: false,
+            detail: Some(detail.into()),
+        }
+    }
+
+    /// Attaches a detail message.
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
@@ -0,0 +1,59 @@
+//! Authenticated client identities.
+//!
+//! When the server terminates mutual TLS, the client certificate's subject
+//! common name is mapped to a set of roles. The resulting `ClientIdentity`
+//! travels with each request so handlers can authorize ingest and read
+//! operations separately.
+
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Operation class a client may be permitted to perform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Submit events.
+    Ingest,
+
+    /// Query events and derived data.
+    Read,
+
+    /// Change runtime settings through the admin endpoints.
+    Admin,
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Role::Ingest => "ingest",
+            Role::Read => "read",
+            Role::Admin => "admin",
+        })
+    }
+}
+
+/// Identity established from a verified client certificate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientIdentity {
+    // ---
+    /// Subject common name of the client certificate.
+    pub subject: String,
+
+    /// Roles mapped to the subject; empty when the subject is unknown.
+    pub roles: BTreeSet<Role>,
+}
+
+impl ClientIdentity {
+    // ---
+
+    /// Whether this identity may perform operations of the given role.
+    pub fn has_role(&self, role: Role) -> bool {
+        self.roles.contains(&role)
+    }
+}
+
+/// Marks a request whose connection presented no usable client certificate
+/// while `[tls.identities]` is configured; role checks deny such requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnonymousClient;
@@ -1,17 +1,60 @@
+use async_trait::async_trait;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+use super::{AlertStatus, ComponentHealth, RepositoryStats};
 
 /// Abstraction for application metrics (counters, histograms).
+#[async_trait]
 pub trait Metrics: Send + Sync + 'static {
     // ---
     /// Render current metrics in Prometheus text format.
     fn render(&self) -> anyhow::Result<String>;
 
-    /// Record a "event created" event.
-    fn record_event_created(&self);
+    /// Record a "event created" event of the given type.
+    fn record_event_created(&self, event_type: &str);
 
-    /// Record HTTP request duration and labels.
+    /// Record HTTP request duration, labeled by route template, method and status.
     fn record_http_request(&self, start: Instant, path: &str, method: &str, status: u16);
+
+    /// Adjust the number of requests currently in flight for a route template.
+    fn record_http_in_flight(&self, path: &str, delta: i64);
+
+    /// Record request and response body sizes in bytes, where known.
+    fn record_http_body_sizes(
+        &self,
+        path: &str,
+        method: &str,
+        request_bytes: Option<u64>,
+        response_bytes: Option<u64>,
+    );
+
+    /// Record the latency and outcome of a repository call (e.g. "store_event").
+    fn record_repository_operation(&self, operation: &str, elapsed: Duration, success: bool);
+
+    /// Record how many events a repository query returned.
+    fn record_query_result_size(&self, count: usize);
+
+    /// Record a rejected event submission, labeled by the validation rule that failed.
+    fn record_validation_failure(&self, rule: &str);
+
+    /// Publish a repository stats snapshot as gauges (events per type, time range, size).
+    fn record_repository_stats(&self, stats: &RepositoryStats);
+
+    /// Record the outcome ("delivered", "retry" or "dead_letter") and latency of a webhook delivery attempt.
+    fn record_webhook_delivery(&self, outcome: &str, elapsed: Duration);
+
+    /// Publish an alert rule's state and current value as gauges.
+    fn record_alert(&self, status: &AlertStatus);
+
+    /// Record one change ("field_added", "required_field_missing" or "type_changed") in an event type's payload schema.
+    fn record_schema_drift(&self, event_type: &str, change: &str);
+
+    /// Sends anything buffered by a push-based backend; a no-op for scraped backends.
+    async fn flush(&self);
+
+    /// Reports whether the metrics backend is initialised and able to render.
+    fn health(&self) -> ComponentHealth;
 }
 
 /// Type alias for any backend that implements Metrics.
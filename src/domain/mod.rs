@@ -4,14 +4,59 @@
 //! used by the service layer and storage implementations.
 
 // Bring all submodules into scope
+mod alert;
+mod cohort;
 mod event;
+mod event_filter;
 mod event_query;
+mod event_types;
+mod field_values;
+mod funnel;
+mod health;
+mod identity;
 mod metrics;
+mod numeric_stats;
+mod observer;
+mod payload_shape;
 mod repository;
+mod retention;
+mod rollup;
+mod schema;
+mod session;
+mod sketch;
+mod stats;
+mod validation;
+mod webhook;
 
 // Public exports (visible outside this module)
 pub use crate::repository::create_repository;
-pub use event::Event;
+pub use alert::{AlertCondition, AlertRule, AlertState, AlertStatus};
+pub use cohort::{Cohort, CohortCounter, CohortPeriod, CohortQuery, CohortReport};
+#[cfg(test)]
+pub(crate) use event::EventBuilder;
+pub use event::{is_payload_path, Event, TraceContext};
+pub use event_filter::EventFilter;
 pub use event_query::EventQuery;
+pub use event_types::{EventTypeCounter, EventTypeSummary, SHAPE_SAMPLE_SIZE};
+pub use field_values::{
+    FieldSketch, FieldValueCounter, FieldValueQuery, FieldValueReport, ValueCount,
+};
+pub use funnel::{FunnelCounter, FunnelQuery, FunnelReport, FunnelStep};
+pub use health::ComponentHealth;
+pub use identity::{AnonymousClient, ClientIdentity, Role};
 pub use metrics::{Metrics, MetricsPtr};
+pub use numeric_stats::{
+    NonNumericField, NumericBucket, NumericStats, NumericStatsCounter, NumericStatsQuery,
+    NumericStatsReport,
+};
+pub use observer::{EventObserver, EventObserverPtr};
+pub use payload_shape::{payload_fields, JsonType, PayloadField, PayloadShape};
 pub use repository::{EventRepository, EventRepositoryPtr};
+pub use retention::RetentionPolicy;
+pub use rollup::{FieldStats, RollupBucket, RollupDefinition, RollupReport};
+pub use schema::{FieldSchema, InferredSchema, PayloadSchema, SchemaChange, SchemaDrift};
+pub use session::{Session, SessionQuery, SessionReport, SessionSummary, Sessionizer};
+pub use sketch::SKETCH_TOP_VALUES;
+pub use stats::{EventTypeStats, RepositoryStats};
+pub use validation::{FieldViolation, ValidationRules};
+pub use webhook::{DeadLetter, Delivery, Subscription};
@@ -0,0 +1,307 @@
+//! Statistics of a numeric payload field per time bucket.
+//!
+//! Matching events are grouped into buckets aligned to the Unix epoch by
+//! their `timestamp`. Each bucket keeps exact count, sum, min and max and a
+//! `QuantileSketch` for the percentiles; the buckets' sketches are merged
+//! for the statistics of the whole range. Events without the field are
+//! skipped, while a value that is not a number fails the computation.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::sketch::QuantileSketch;
+use super::{Event, EventQuery};
+
+/// Statistics to compute.
+#[derive(Debug, Clone)]
+pub struct NumericStatsQuery {
+    // ---
+    /// Events of this type and time range are read.
+    pub events: EventQuery,
+
+    /// Dotted payload path of the numeric field (e.g. `order.amount`).
+    pub field: String,
+
+    /// Length of a bucket.
+    pub interval: Duration,
+}
+
+/// Statistics of the values in a bucket or range.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct NumericStats {
+    // ---
+    /// Events carrying the field.
+    pub count: u64,
+    pub sum: f64,
+    pub avg: f64,
+    pub min: f64,
+    pub max: f64,
+
+    /// Percentiles, estimated within 1% relative error.
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+}
+
+/// Statistics of one time bucket.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct NumericBucket {
+    // ---
+    /// Start of the bucket (inclusive); it ends one interval later.
+    pub start: DateTime<Utc>,
+
+    #[serde(flatten)]
+    pub stats: NumericStats,
+}
+
+/// Result of a statistics computation.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct NumericStatsReport {
+    // ---
+    pub field: String,
+    pub interval_secs: u64,
+
+    /// Buckets holding at least one value, oldest first.
+    pub buckets: Vec<NumericBucket>,
+
+    /// Statistics of every value in the range; absent when there are none.
+    pub total: Option<NumericStats>,
+}
+
+/// A matching event whose field holds something other than a number.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NonNumericField {
+    pub field: String,
+    pub event_id: Uuid,
+    pub value: Value,
+}
+
+impl fmt::Display for NonNumericField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "field '{}' is not numeric: event {} has {}",
+            self.field, self.event_id, self.value
+        )
+    }
+}
+
+impl std::error::Error for NonNumericField {}
+
+/// Exact aggregates plus a sketch of the values.
+#[derive(Debug, Clone, Default)]
+struct Summary {
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+    sketch: QuantileSketch,
+}
+
+impl Summary {
+    // ---
+
+    fn insert(&mut self, value: f64) {
+        // ---
+        if self.count == 0 {
+            (self.min, self.max) = (value, value);
+        }
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sketch.insert(value);
+    }
+
+    fn merge(&mut self, other: &Summary) {
+        // ---
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            (self.min, self.max) = (other.min, other.max);
+        }
+        self.count += other.count;
+        self.sum += other.sum;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.sketch.merge(&other.sketch);
+    }
+
+    fn stats(&self) -> NumericStats {
+        // ---
+        // Estimates are kept within the exact bounds
+        let quantile = |q| {
+            self.sketch
+                .quantile(q)
+                .map_or(0.0, |v: f64| v.clamp(self.min, self.max))
+        };
+        NumericStats {
+            count: self.count,
+            sum: self.sum,
+            avg: self.sum / self.count as f64,
+            min: self.min,
+            max: self.max,
+            p50: quantile(0.5),
+            p95: quantile(0.95),
+            p99: quantile(0.99),
+        }
+    }
+}
+
+/// Accumulates events, in any order, into per-bucket statistics.
+pub struct NumericStatsCounter<'q> {
+    query: &'q NumericStatsQuery,
+    buckets: BTreeMap<i64, Summary>,
+    non_numeric: Option<NonNumericField>,
+}
+
+impl<'q> NumericStatsCounter<'q> {
+    // ---
+
+    pub fn new(query: &'q NumericStatsQuery) -> Self {
+        Self {
+            query,
+            buckets: BTreeMap::new(),
+            non_numeric: None,
+        }
+    }
+
+    fn interval_secs(&self) -> i64 {
+        self.query.interval.as_secs().max(1) as i64
+    }
+
+    /// Adds the field's value in `event`; type and range are the caller's to check.
+    pub fn add(&mut self, event: &Event) {
+        // ---
+        let value = match event.payload_value(&self.query.field) {
+            None | Some(Value::Null) => return,
+            Some(value) => value,
+        };
+        let Some(number) = value.as_f64().filter(|n| n.is_finite()) else {
+            self.non_numeric.get_or_insert_with(|| NonNumericField {
+                field: self.query.field.clone(),
+                event_id: event.id,
+                value: value.clone(),
+            });
+            return;
+        };
+        let interval = self.interval_secs();
+        let start = event.timestamp.timestamp().div_euclid(interval) * interval;
+        self.buckets.entry(start).or_default().insert(number);
+    }
+
+    /// The statistics, or the first non-numeric value found.
+    pub fn finish(self) -> Result<NumericStatsReport, NonNumericField> {
+        // ---
+        if let Some(non_numeric) = self.non_numeric {
+            return Err(non_numeric);
+        }
+        let mut total = Summary::default();
+        let buckets = self
+            .buckets
+            .iter()
+            .map(|(start, summary)| {
+                total.merge(summary);
+                NumericBucket {
+                    start: DateTime::from_timestamp(*start, 0).unwrap_or_default(),
+                    stats: summary.stats(),
+                }
+            })
+            .collect();
+        Ok(NumericStatsReport {
+            field: self.query.field.clone(),
+            interval_secs: self.query.interval.as_secs(),
+            buckets,
+            total: (total.count > 0).then(|| total.stats()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+    use crate::domain::EventBuilder;
+    use serde_json::json;
+
+    fn query() -> NumericStatsQuery {
+        NumericStatsQuery {
+            events: EventQuery::default(),
+            field: "order.amount".to_string(),
+            interval: Duration::from_secs(3600),
+        }
+    }
+
+    #[test]
+    fn values_are_summarised_per_bucket_and_in_total() {
+        // ---
+        let query = query();
+        let mut counter = NumericStatsCounter::new(&query);
+        for minute in 0..100 {
+            let timestamp = format!("2025-06-16T10:{:02}:00Z", minute % 60);
+            counter.add(
+                &EventBuilder::new("purchase")
+                    .at(&timestamp)
+                    .payload(json!({ "order": { "amount": minute + 1 } }))
+                    .build(),
+            );
+        }
+        counter.add(
+            &EventBuilder::new("purchase")
+                .at("2025-06-16T11:30:00Z")
+                .payload(json!({ "order": { "amount": -5.5 } }))
+                .build(),
+        );
+        counter.add(
+            &EventBuilder::new("purchase")
+                .at("2025-06-16T11:45:00Z")
+                .payload(json!({ "order": {} }))
+                .build(),
+        );
+
+        let report = counter.finish().unwrap();
+        assert_eq!(report.buckets.len(), 2);
+        let first = &report.buckets[0].stats;
+        assert_eq!(
+            (first.count, first.sum, first.min, first.max),
+            (100, 5050.0, 1.0, 100.0)
+        );
+        assert_eq!(first.avg, 50.5);
+        assert!((first.p50 - 51.0).abs() <= 1.0, "{:?}", first);
+        assert!((first.p99 - 99.0).abs() <= 1.0, "{:?}", first);
+        assert_eq!(report.buckets[1].stats.p95, -5.5);
+
+        let total = report.total.unwrap();
+        assert_eq!((total.count, total.min), (101, -5.5));
+    }
+
+    #[test]
+    fn non_numeric_values_are_rejected() {
+        // ---
+        let query = query();
+        let mut counter = NumericStatsCounter::new(&query);
+        counter.add(
+            &EventBuilder::new("purchase")
+                .at("2025-06-16T10:00:00Z")
+                .payload(json!({ "order": { "amount": 10 } }))
+                .build(),
+        );
+        let bad = EventBuilder::new("purchase")
+            .at("2025-06-16T10:05:00Z")
+            .payload(json!({ "order": { "amount": "ten" } }))
+            .build();
+        counter.add(&bad);
+
+        let err = counter.finish().unwrap_err();
+        assert_eq!(err.event_id, bad.id);
+        assert!(err.to_string().contains("'order.amount' is not numeric"));
+    }
+}
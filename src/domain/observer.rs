@@ -0,0 +1,21 @@
+//! Hooks notified once an event has been stored.
+
+use std::sync::Arc;
+
+use super::Event;
+
+/// Reacts to events after the repository accepted them.
+///
+/// Observers run on the request path, so they should only update
+/// in-memory state or queue work for a background task.
+pub trait EventObserver: Send + Sync {
+    /// Whether `event` is of interest. Checked before storing, so an event
+    /// that no observer wants is never copied.
+    fn wants(&self, event: &Event) -> bool;
+
+    /// Called after `event` was stored successfully.
+    fn event_stored(&self, event: &Event);
+}
+
+/// Shared pointer to an observer.
+pub type EventObserverPtr = Arc<dyn EventObserver>;
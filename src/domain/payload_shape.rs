@@ -0,0 +1,147 @@
+//! Fields carried by event payloads and their JSON types.
+//!
+//! A payload's fields are named by the dotted paths `Event::payload_value`
+//! reads: nested objects are descended into, arrays are not. Keys that
+//! contain a dot are skipped, as no path can address them.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use utoipa::ToSchema;
+
+/// JSON type of a payload value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonType {
+    Null,
+    Boolean,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl JsonType {
+    // ---
+
+    pub fn of(value: &Value) -> Self {
+        // ---
+        match value {
+            Value::Null => Self::Null,
+            Value::Bool(_) => Self::Boolean,
+            Value::Number(_) => Self::Number,
+            Value::String(_) => Self::String,
+            Value::Array(_) => Self::Array,
+            Value::Object(_) => Self::Object,
+        }
+    }
+}
+
+/// Every field of `payload` with its type, parents before their children.
+pub fn payload_fields(payload: &Value) -> Vec<(String, JsonType)> {
+    // ---
+    fn walk(prefix: &str, value: &Value, fields: &mut Vec<(String, JsonType)>) {
+        let Value::Object(object) = value else {
+            return;
+        };
+        for (key, value) in object.iter().filter(|(key, _)| !key.contains('.')) {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            fields.push((path.clone(), JsonType::of(value)));
+            walk(&path, value, fields);
+        }
+    }
+
+    let mut fields = Vec::new();
+    walk("", payload, &mut fields);
+    fields
+}
+
+/// One field seen in a sample of payloads.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct PayloadField {
+    // ---
+    /// Dotted payload path.
+    pub name: String,
+
+    /// Types the field was seen with.
+    pub types: Vec<JsonType>,
+
+    /// Share of the sampled payloads carrying the field, from 0 to 1.
+    pub presence: f64,
+}
+
+/// Accumulates payloads into the fields they carry.
+#[derive(Debug, Clone, Default)]
+pub struct PayloadShape {
+    samples: u64,
+    fields: BTreeMap<String, (u64, BTreeSet<JsonType>)>,
+}
+
+impl PayloadShape {
+    // ---
+
+    pub fn add(&mut self, payload: &Value) {
+        // ---
+        self.samples += 1;
+        for (path, json_type) in payload_fields(payload) {
+            let (count, types) = self.fields.entry(path).or_default();
+            *count += 1;
+            types.insert(json_type);
+        }
+    }
+
+    /// Number of payloads added.
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// The fields seen, ordered by path.
+    pub fn fields(&self) -> Vec<PayloadField> {
+        // ---
+        self.fields
+            .iter()
+            .map(|(name, (count, types))| PayloadField {
+                name: name.clone(),
+                types: types.iter().copied().collect(),
+                presence: *count as f64 / self.samples as f64,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn fields_are_named_by_path_with_their_types_and_presence() {
+        // ---
+        let mut shape = PayloadShape::default();
+        shape.add(&json!({ "user_id": "u1", "order": { "amount": 10 }, "tags": [1] }));
+        shape.add(&json!({ "user_id": 7, "order": null, "a.b": true }));
+
+        let fields = shape.fields();
+        let fields: Vec<(&str, &[JsonType], f64)> = fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.types.as_slice(), f.presence))
+            .collect();
+        assert_eq!(shape.samples(), 2);
+        assert_eq!(
+            fields,
+            [
+                ("order", &[JsonType::Null, JsonType::Object][..], 1.0),
+                ("order.amount", &[JsonType::Number][..], 0.5),
+                ("tags", &[JsonType::Array][..], 0.5),
+                ("user_id", &[JsonType::Number, JsonType::String][..], 1.0),
+            ]
+        );
+        assert!(payload_fields(&json!([1, 2])).is_empty());
+    }
+}
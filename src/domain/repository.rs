@@ -8,8 +8,14 @@
 #![allow(dead_code)]
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
-use super::{Event, EventQuery};
+use super::{
+    CohortCounter, CohortQuery, CohortReport, ComponentHealth, Event, EventQuery, EventTypeCounter,
+    EventTypeSummary, FieldValueCounter, FieldValueQuery, FieldValueReport, FunnelCounter,
+    FunnelQuery, FunnelReport, NumericStatsCounter, NumericStatsQuery, NumericStatsReport,
+    RepositoryStats, SessionQuery, SessionReport, Sessionizer, SHAPE_SAMPLE_SIZE,
+};
 
 /// Trait representing a pluggable event storage backend.
 #[async_trait]
@@ -19,6 +25,102 @@ pub trait EventRepository: Send + Sync {
 
     /// Retrieves events matching the given query filters.
     async fn find_events(&self, query: EventQuery) -> anyhow::Result<Vec<Event>>;
+
+    /// Summarises the stored events: counts and time range per type, and their size.
+    async fn stats(&self) -> anyhow::Result<RepositoryStats>;
+
+    /// Calls `visit` with every stored event matching `query`.
+    ///
+    /// The analytics below are built on it. The default reads the events with
+    /// `find_events`; backends that can iterate their storage without copying
+    /// events should override it.
+    async fn scan(
+        &self,
+        query: &EventQuery,
+        visit: &mut (dyn for<'e> FnMut(&'e Event) + Send),
+    ) -> anyhow::Result<()> {
+        for event in self.find_events(query.clone()).await? {
+            visit(&event);
+        }
+        Ok(())
+    }
+
+    /// Lists the stored event types with their counts, time range and payload shape.
+    async fn event_types(&self) -> anyhow::Result<Vec<EventTypeSummary>> {
+        let mut counter = EventTypeCounter::new(SHAPE_SAMPLE_SIZE);
+        self.scan(&EventQuery::default(), &mut |event| counter.add(event))
+            .await?;
+        Ok(counter.finish())
+    }
+
+    /// Computes a conversion funnel over the stored events.
+    async fn funnel(&self, query: FunnelQuery) -> anyhow::Result<FunnelReport> {
+        let mut counter = FunnelCounter::new(&query);
+        for event_query in query.event_queries() {
+            self.scan(&event_query, &mut |event| counter.add(event))
+                .await?;
+        }
+        Ok(counter.finish())
+    }
+
+    /// Groups events into per-actor sessions separated by an inactivity gap.
+    async fn sessions(&self, query: SessionQuery) -> anyhow::Result<SessionReport> {
+        let mut sessionizer = Sessionizer::new(&query);
+        self.scan(&query.events, &mut |event| sessionizer.add(event))
+            .await?;
+        Ok(sessionizer.finish())
+    }
+
+    /// Computes cohort retention: actors grouped by birth period, counted per return period.
+    async fn cohorts(&self, query: CohortQuery) -> anyhow::Result<CohortReport> {
+        let mut counter = CohortCounter::new(&query);
+        for event_query in query.event_queries() {
+            self.scan(&event_query, &mut |event| counter.add(event))
+                .await?;
+        }
+        Ok(counter.finish())
+    }
+
+    /// Counts the distinct and most frequent values of a payload field.
+    ///
+    /// The default scans the events and counts exactly, unless the query asks
+    /// for estimates or there are too many values; backends that keep sketches
+    /// of their fields should override it.
+    async fn field_values(&self, query: FieldValueQuery) -> anyhow::Result<FieldValueReport> {
+        let mut counter = FieldValueCounter::new(&query);
+        self.scan(&query.event_query(), &mut |event| counter.add(event))
+            .await?;
+        Ok(counter.finish())
+    }
+
+    /// Computes count, sum, avg, min, max and percentiles of a numeric payload field per time bucket.
+    ///
+    /// Fails with a [`NonNumericField`](super::NonNumericField) error when a
+    /// matching event holds a value that is not a number.
+    async fn numeric_stats(&self, query: NumericStatsQuery) -> anyhow::Result<NumericStatsReport> {
+        let mut counter = NumericStatsCounter::new(&query);
+        self.scan(&query.events, &mut |event| counter.add(event))
+            .await?;
+        Ok(counter.finish()?)
+    }
+
+    /// Reports whether the backend is reachable and able to serve requests.
+    async fn health(&self) -> ComponentHealth;
+
+    /// Flushes buffered writes and releases resources before the process exits.
+    ///
+    /// Called once after the HTTP server has drained. The default does nothing,
+    /// which suits backends with no buffered state.
+    async fn shutdown(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Deletes events with a timestamp strictly before `cutoff`, returning how many were removed.
+    ///
+    /// The default does nothing, for backends that manage retention themselves.
+    async fn purge_before(&self, _cutoff: DateTime<Utc>) -> anyhow::Result<usize> {
+        Ok(0)
+    }
 }
 
 /// Shared, thread-safe pointer to a dynamic EventRepository implementation.
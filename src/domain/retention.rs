@@ -0,0 +1,23 @@
+//! Retention policy for raw events.
+
+use std::time::Duration;
+
+/// How long raw events are kept and how often expired ones are purged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    // ---
+    /// Events older than this are purged; `None` keeps events forever.
+    pub max_age: Option<Duration>,
+
+    /// Delay between purge sweeps.
+    pub sweep_interval: Duration,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_age: None,
+            sweep_interval: Duration::from_secs(60),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Continuously maintained rollups of stored events.
+//!
+//! A rollup counts the events of one `event_type` (optionally narrowed by
+//! payload fields) in fixed time buckets, split by the values of some
+//! payload fields, and keeps count/sum/min/max/avg of numeric payload
+//! fields. Buckets are aligned to the Unix epoch and keyed by the event
+//! `timestamp`, so late events land in the bucket they belong to.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use utoipa::ToSchema;
+
+use super::{Event, EventFilter};
+
+/// A configured rollup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RollupDefinition {
+    // ---
+    /// Unique name, used in `GET /v1/rollups/{name}`.
+    pub name: String,
+
+    /// Events that are rolled up.
+    pub events: EventFilter,
+
+    /// Length of a bucket.
+    pub interval: Duration,
+
+    /// Dotted payload paths whose values split each bucket into groups.
+    pub group_by: Vec<String>,
+
+    /// Dotted payload paths of the numeric fields aggregated in each bucket.
+    pub fields: Vec<String>,
+
+    /// Buckets older than this are dropped; unset keeps them all.
+    pub max_age: Option<Duration>,
+}
+
+impl RollupDefinition {
+    // ---
+
+    /// Whether `event` counts towards this rollup.
+    pub fn matches(&self, event: &Event) -> bool {
+        self.events.matches(event)
+    }
+}
+
+/// Aggregates of one numeric field within a bucket.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FieldStats {
+    // ---
+    /// Events carrying a number at the field.
+    pub count: u64,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+    pub avg: f64,
+}
+
+impl FieldStats {
+    // ---
+
+    /// Stats of a single value.
+    pub fn new(value: f64) -> Self {
+        Self {
+            count: 1,
+            sum: value,
+            min: value,
+            max: value,
+            avg: value,
+        }
+    }
+
+    pub fn record(&mut self, value: f64) {
+        // ---
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.avg = self.sum / self.count as f64;
+    }
+}
+
+/// One time bucket of one group.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RollupBucket {
+    // ---
+    /// Start of the bucket (inclusive); it ends one interval later.
+    pub start: DateTime<Utc>,
+
+    /// Value of each `group_by` field; fields an event lacks are left out.
+    pub group: BTreeMap<String, String>,
+
+    /// Matching events in the bucket.
+    pub events: u64,
+
+    /// Aggregates of each numeric field; fields no event carried a number at are left out.
+    pub fields: BTreeMap<String, FieldStats>,
+}
+
+/// Buckets of one rollup, as returned by `GET /v1/rollups/{name}`.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct RollupReport {
+    // ---
+    pub name: String,
+    pub event_type: String,
+    pub interval_secs: u64,
+    pub group_by: Vec<String>,
+    pub fields: Vec<String>,
+
+    /// Buckets ordered by start, then group.
+    pub buckets: Vec<RollupBucket>,
+}
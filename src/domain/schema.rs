@@ -0,0 +1,324 @@
+//! Payload schemas inferred from stored events, and drift from them.
+//!
+//! An `InferredSchema` learns one event type's payload fields as events
+//! are observed: the JSON types each field was seen with, whether it was
+//! ever null, and whether every event so far carried it (required).
+//! Observing an event reports how it departs from what was known before
+//! (a new field, a missing required field or a field with a new type)
+//! and then absorbs it, so each change is reported once.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{payload_fields, Event, JsonType};
+
+/// One field of an inferred schema.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct FieldSchema {
+    // ---
+    /// Dotted payload path.
+    pub name: String,
+
+    /// Types other than `null` the field was seen with.
+    pub types: Vec<JsonType>,
+
+    /// The field was seen holding `null`.
+    pub nullable: bool,
+
+    /// Every observed event carried the field.
+    pub required: bool,
+
+    /// Observed events carrying the field.
+    pub seen: u64,
+}
+
+/// Inferred payload schema of one event type.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct PayloadSchema {
+    // ---
+    pub event_type: String,
+
+    /// Events the schema was inferred from.
+    pub events: u64,
+
+    /// Fields ordered by path.
+    pub fields: Vec<FieldSchema>,
+}
+
+/// One way an event departs from its type's schema.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum SchemaChange {
+    /// A field no earlier event carried.
+    FieldAdded { field: String, found: JsonType },
+
+    /// A field every earlier event carried is absent.
+    RequiredFieldMissing { field: String },
+
+    /// A field holds a type it was never seen with.
+    TypeChanged {
+        field: String,
+        expected: Vec<JsonType>,
+        found: JsonType,
+    },
+}
+
+impl SchemaChange {
+    // ---
+
+    /// Label of the change, as in the serialized `change` tag.
+    pub fn as_str(&self) -> &'static str {
+        // ---
+        match self {
+            Self::FieldAdded { .. } => "field_added",
+            Self::RequiredFieldMissing { .. } => "required_field_missing",
+            Self::TypeChanged { .. } => "type_changed",
+        }
+    }
+}
+
+/// An event whose payload departs from its type's schema.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct SchemaDrift {
+    // ---
+    pub event_type: String,
+    pub event_id: Uuid,
+    pub timestamp: DateTime<Utc>,
+    pub changes: Vec<SchemaChange>,
+}
+
+/// What is known of one field.
+#[derive(Debug, Clone, Default)]
+struct FieldState {
+    types: BTreeSet<JsonType>,
+    nullable: bool,
+    seen: u64,
+}
+
+/// Schema of one event type, learnt from its events.
+#[derive(Debug, Clone)]
+pub struct InferredSchema {
+    event_type: String,
+    events: u64,
+    fields: BTreeMap<String, FieldState>,
+}
+
+impl InferredSchema {
+    // ---
+
+    pub fn new(event_type: &str) -> Self {
+        Self {
+            event_type: event_type.to_string(),
+            events: 0,
+            fields: BTreeMap::new(),
+        }
+    }
+
+    /// Events observed so far.
+    pub fn events(&self) -> u64 {
+        self.events
+    }
+
+    /// Learns the payload of `event`, returning how it departs from what was known.
+    ///
+    /// The first event only establishes the schema. Only the outermost of
+    /// several new or missing nested fields is reported.
+    pub fn observe(&mut self, event: &Event) -> Vec<SchemaChange> {
+        // ---
+        let present: BTreeMap<String, JsonType> =
+            payload_fields(&event.payload).into_iter().collect();
+        let changes = if self.events == 0 {
+            Vec::new()
+        } else {
+            self.changes(&present)
+        };
+
+        self.events += 1;
+        for (path, json_type) in present {
+            let field = self.fields.entry(path).or_default();
+            field.seen += 1;
+            if json_type == JsonType::Null {
+                field.nullable = true;
+            } else {
+                field.types.insert(json_type);
+            }
+        }
+        changes
+    }
+
+    fn changes(&self, present: &BTreeMap<String, JsonType>) -> Vec<SchemaChange> {
+        // ---
+        let mut changes = Vec::new();
+        for (path, found) in present {
+            match self.fields.get(path) {
+                None if parent(path).is_none_or(|p| self.fields.contains_key(p)) => {
+                    changes.push(SchemaChange::FieldAdded {
+                        field: path.clone(),
+                        found: *found,
+                    });
+                }
+                Some(field)
+                    if *found != JsonType::Null
+                        && !field.types.is_empty()
+                        && !field.types.contains(found) =>
+                {
+                    changes.push(SchemaChange::TypeChanged {
+                        field: path.clone(),
+                        expected: field.types.iter().copied().collect(),
+                        found: *found,
+                    });
+                }
+                _ => {}
+            }
+        }
+        for (path, field) in &self.fields {
+            let required = field.seen == self.events;
+            // A missing parent object is reported instead of its fields
+            let parent_present =
+                parent(path).is_none_or(|p| present.get(p) == Some(&JsonType::Object));
+            if required && parent_present && !present.contains_key(path) {
+                changes.push(SchemaChange::RequiredFieldMissing {
+                    field: path.clone(),
+                });
+            }
+        }
+        changes
+    }
+
+    pub fn schema(&self) -> PayloadSchema {
+        // ---
+        PayloadSchema {
+            event_type: self.event_type.clone(),
+            events: self.events,
+            fields: self
+                .fields
+                .iter()
+                .map(|(name, field)| FieldSchema {
+                    name: name.clone(),
+                    types: field.types.iter().copied().collect(),
+                    nullable: field.nullable,
+                    required: field.seen == self.events,
+                    seen: field.seen,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Path of the object holding the field at `path`, if it is nested.
+fn parent(path: &str) -> Option<&str> {
+    path.rsplit_once('.').map(|(parent, _)| parent)
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+    use crate::domain::EventBuilder;
+    use serde_json::json;
+
+    #[test]
+    fn schema_records_types_nullability_and_required_fields() {
+        // ---
+        let mut schema = InferredSchema::new("purchase");
+        for payload in [
+            json!({ "amount": 10, "coupon": null, "user": { "id": "u1" } }),
+            json!({ "amount": 12.5, "coupon": "SPRING", "user": { "id": "u2" } }),
+            json!({ "amount": 3, "user": { "id": "u3" } }),
+        ] {
+            schema.observe(&EventBuilder::new("purchase").payload(payload).build());
+        }
+
+        let schema = schema.schema();
+        assert_eq!(schema.events, 3);
+        let fields: Vec<(&str, &[JsonType], bool, bool)> = schema
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.types.as_slice(), f.nullable, f.required))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("amount", &[JsonType::Number][..], false, true),
+                ("coupon", &[JsonType::String][..], true, false),
+                ("user", &[JsonType::Object][..], false, true),
+                ("user.id", &[JsonType::String][..], false, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn departures_are_reported_once() {
+        // ---
+        let mut schema = InferredSchema::new("purchase");
+        assert!(schema
+            .observe(
+                &EventBuilder::new("purchase")
+                    .payload(json!({ "amount": 10, "user": { "id": "u1" } }))
+                    .build()
+            )
+            .is_empty());
+
+        let changes = schema.observe(
+            &EventBuilder::new("purchase")
+                .payload(json!({
+                    "amount": "10.00",
+                    "currency": "EUR",
+                    "user": { "tier": { "name": "gold" } },
+                }))
+                .build(),
+        );
+        assert_eq!(
+            changes,
+            [
+                SchemaChange::TypeChanged {
+                    field: "amount".to_string(),
+                    expected: vec![JsonType::Number],
+                    found: JsonType::String,
+                },
+                SchemaChange::FieldAdded {
+                    field: "currency".to_string(),
+                    found: JsonType::String,
+                },
+                SchemaChange::FieldAdded {
+                    field: "user.tier".to_string(),
+                    found: JsonType::Object,
+                },
+                SchemaChange::RequiredFieldMissing {
+                    field: "user.id".to_string(),
+                },
+            ]
+        );
+
+        // Absorbed: the same shape again is no longer a departure
+        let changes = schema.observe(
+            &EventBuilder::new("purchase")
+                .payload(json!({
+                    "amount": 7,
+                    "currency": "USD",
+                    "user": { "tier": { "name": "basic" } },
+                }))
+                .build(),
+        );
+        assert!(changes.is_empty(), "{:?}", changes);
+
+        // A missing object is reported, not each of its fields
+        let changes = schema.observe(
+            &EventBuilder::new("purchase")
+                .payload(json!({ "amount": 1, "currency": "EUR" }))
+                .build(),
+        );
+        let fields: Vec<&str> = changes
+            .iter()
+            .map(|change| match change {
+                SchemaChange::RequiredFieldMissing { field } => field.as_str(),
+                other => panic!("unexpected change {:?}", other),
+            })
+            .collect();
+        assert_eq!(fields, ["user"]);
+    }
+}
@@ -0,0 +1,265 @@
+//! Sessions: bursts of activity by one actor.
+//!
+//! Events are grouped into actors by a payload key (e.g. `user_id`). An
+//! actor's events, ordered by timestamp, form one session until the time
+//! between two consecutive events exceeds the inactivity gap, which starts
+//! a new session. Events may be fed in any order.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use super::{Event, EventQuery};
+
+/// Sessions to compute.
+#[derive(Debug, Clone)]
+pub struct SessionQuery {
+    // ---
+    /// Dotted payload path identifying the actor (e.g. `user_id`).
+    pub key: String,
+
+    /// Longest pause between two events of the same session.
+    pub gap: Duration,
+
+    /// Events considered: optional type and time range.
+    pub events: EventQuery,
+
+    /// Most sessions listed; the summary covers all of them.
+    pub limit: usize,
+}
+
+/// One session.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct Session {
+    // ---
+    /// Value of the key shared by the session's events.
+    pub actor: String,
+
+    /// Timestamp of the first event.
+    pub start: DateTime<Utc>,
+
+    /// Timestamp of the last event.
+    pub end: DateTime<Utc>,
+
+    /// `end - start` in seconds; 0 for a single event.
+    pub duration_secs: f64,
+
+    pub event_count: u64,
+
+    /// Event types in timestamp order.
+    pub event_types: Vec<String>,
+}
+
+/// Aggregates over every session found.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct SessionSummary {
+    // ---
+    pub sessions: u64,
+
+    /// Distinct actors with at least one session.
+    pub actors: u64,
+
+    /// Events across all sessions.
+    pub events: u64,
+
+    pub mean_duration_secs: f64,
+    pub median_duration_secs: f64,
+    pub max_duration_secs: f64,
+    pub mean_events_per_session: f64,
+}
+
+/// Result of a session computation.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct SessionReport {
+    // ---
+    /// Sessions ordered by start (then actor), at most `limit` of them.
+    pub sessions: Vec<Session>,
+
+    pub summary: SessionSummary,
+}
+
+/// Accumulates events, in any order, into sessions.
+pub struct Sessionizer<'q> {
+    query: &'q SessionQuery,
+    actors: HashMap<String, Vec<(DateTime<Utc>, String)>>,
+}
+
+impl<'q> Sessionizer<'q> {
+    // ---
+
+    pub fn new(query: &'q SessionQuery) -> Self {
+        Self {
+            query,
+            actors: HashMap::new(),
+        }
+    }
+
+    /// Adds `event` if it carries the key; type and range are the caller's to check.
+    pub fn add(&mut self, event: &Event) {
+        // ---
+        if let Some(actor) = event.payload_key(&self.query.key) {
+            self.actors
+                .entry(actor)
+                .or_default()
+                .push((event.timestamp, event.event_type.clone()));
+        }
+    }
+
+    pub fn finish(self) -> SessionReport {
+        // ---
+        let actors = self.actors.len() as u64;
+        let mut sessions = Vec::new();
+        for (actor, mut events) in self.actors {
+            events.sort_by_key(|(timestamp, _)| *timestamp);
+            let mut current: Option<Session> = None;
+            for (timestamp, event_type) in events {
+                match &mut current {
+                    Some(session) if timestamp - session.end <= self.query.gap => {
+                        session.end = timestamp;
+                        session.event_count += 1;
+                        session.event_types.push(event_type);
+                    }
+                    _ => {
+                        sessions.extend(current.take());
+                        current = Some(Session {
+                            actor: actor.clone(),
+                            start: timestamp,
+                            end: timestamp,
+                            duration_secs: 0.0,
+                            event_count: 1,
+                            event_types: vec![event_type],
+                        });
+                    }
+                }
+            }
+            sessions.extend(current);
+        }
+        for session in &mut sessions {
+            session.duration_secs =
+                (session.end - session.start).num_milliseconds() as f64 / 1000.0;
+        }
+
+        let summary = summarise(&sessions, actors);
+        sessions.sort_by(|a, b| (a.start, &a.actor).cmp(&(b.start, &b.actor)));
+        sessions.truncate(self.query.limit);
+        SessionReport { sessions, summary }
+    }
+}
+
+fn summarise(sessions: &[Session], actors: u64) -> SessionSummary {
+    // ---
+    if sessions.is_empty() {
+        return SessionSummary::default();
+    }
+    let count = sessions.len() as f64;
+    let events: u64 = sessions.iter().map(|s| s.event_count).sum();
+    let mut durations: Vec<f64> = sessions.iter().map(|s| s.duration_secs).collect();
+    durations.sort_by(f64::total_cmp);
+    let middle = durations.len() / 2;
+    let median = if durations.len() % 2 == 1 {
+        durations[middle]
+    } else {
+        (durations[middle - 1] + durations[middle]) / 2.0
+    };
+    SessionSummary {
+        sessions: sessions.len() as u64,
+        actors,
+        events,
+        mean_duration_secs: durations.iter().sum::<f64>() / count,
+        median_duration_secs: median,
+        max_duration_secs: durations.last().copied().unwrap_or(0.0),
+        mean_events_per_session: events as f64 / count,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+    use crate::domain::EventBuilder;
+    use serde_json::json;
+
+    fn query(limit: usize) -> SessionQuery {
+        SessionQuery {
+            key: "user.id".to_string(),
+            gap: Duration::minutes(30),
+            events: EventQuery::default(),
+            limit,
+        }
+    }
+
+    #[test]
+    fn out_of_order_events_are_split_by_the_gap() {
+        // ---
+        let query = query(10);
+        let mut sessionizer = Sessionizer::new(&query);
+        for (event_type, user, minute) in [
+            ("checkout", "a", 40),
+            ("login", "a", 0),
+            ("logout", "a", 100),
+            ("view", "a", 20),
+            ("login", "b", 5),
+            ("view", "a", 130),
+        ] {
+            let event = EventBuilder::new(event_type)
+                .timestamp(DateTime::<Utc>::UNIX_EPOCH + Duration::minutes(minute))
+                .payload(json!({ "user": { "id": user } }))
+                .build();
+            sessionizer.add(&event);
+        }
+
+        let report = sessionizer.finish();
+        let spans: Vec<(&str, i64, i64)> = report
+            .sessions
+            .iter()
+            .map(|s| {
+                (
+                    s.actor.as_str(),
+                    (s.start - DateTime::<Utc>::UNIX_EPOCH).num_minutes(),
+                    (s.end - DateTime::<Utc>::UNIX_EPOCH).num_minutes(),
+                )
+            })
+            .collect();
+        assert_eq!(spans, [("a", 0, 40), ("b", 5, 5), ("a", 100, 130)]);
+        assert_eq!(
+            report.sessions[0].event_types,
+            ["login", "view", "checkout"]
+        );
+        assert_eq!(report.sessions[0].duration_secs, 2400.0);
+
+        let summary = &report.summary;
+        assert_eq!(
+            (summary.sessions, summary.actors, summary.events),
+            (3, 2, 6)
+        );
+        assert_eq!(summary.median_duration_secs, 1800.0);
+        assert_eq!(summary.max_duration_secs, 2400.0);
+        assert_eq!(summary.mean_events_per_session, 2.0);
+    }
+
+    #[test]
+    fn limit_trims_the_list_but_not_the_summary() {
+        // ---
+        let query = query(1);
+        let mut sessionizer = Sessionizer::new(&query);
+        for (minute, payload) in [
+            (0, json!({ "user": { "id": "a" } })),
+            (60, json!({ "user": { "id": "a" } })),
+            // No key: not part of any session
+            (0, json!({})),
+        ] {
+            let event = EventBuilder::new("login")
+                .timestamp(DateTime::<Utc>::UNIX_EPOCH + Duration::minutes(minute))
+                .payload(payload)
+                .build();
+            sessionizer.add(&event);
+        }
+
+        let report = sessionizer.finish();
+        assert_eq!(report.sessions.len(), 1);
+        assert_eq!(report.summary.sessions, 2);
+        assert_eq!(report.summary.events, 2);
+    }
+}
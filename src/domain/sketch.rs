@@ -0,0 +1,363 @@
+//! Fixed-size, mergeable summaries of a stream of values.
+//!
+//! `HyperLogLog` estimates how many distinct values were seen (about 1.6%
+//! standard error) and `CountMinSketch` estimates how often each was seen,
+//! never under-counting. Both take the same memory however many values
+//! they summarise. `QuantileSketch` estimates quantiles of numbers within
+//! 1% relative error, in memory that grows with the logarithm of their
+//! range. Two sketches of the same kind can be merged into the sketch of
+//! both streams.
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// Bits of the hash selecting a HyperLogLog register.
+const HLL_PRECISION: u32 = 12;
+
+/// Counters per Count-Min row.
+const CMS_WIDTH: usize = 1024;
+
+/// Count-Min rows, each with its own hash function.
+const CMS_DEPTH: usize = 4;
+
+/// Most frequent values a `CountMinSketch` keeps track of.
+pub const SKETCH_TOP_VALUES: usize = 100;
+
+/// Relative error of `QuantileSketch` estimates.
+const QUANTILE_ACCURACY: f64 = 0.01;
+
+/// Magnitudes below this count as zero in a `QuantileSketch`.
+const QUANTILE_MIN_MAGNITUDE: f64 = 1e-9;
+
+fn hash(value: &str) -> u64 {
+    // ---
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Distinct-count estimator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self {
+            registers: vec![0; 1 << HLL_PRECISION],
+        }
+    }
+}
+
+impl HyperLogLog {
+    // ---
+
+    pub fn insert(&mut self, value: &str) {
+        // ---
+        let hash = hash(value);
+        let index = (hash >> (64 - HLL_PRECISION)) as usize;
+        // Leading zeros of the remaining bits, plus one; a sentinel bit caps the run
+        let rank = ((hash << HLL_PRECISION) | (1 << (HLL_PRECISION - 1))).leading_zeros() + 1;
+        self.registers[index] = self.registers[index].max(rank as u8);
+    }
+
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        // ---
+        for (register, other) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(*other);
+        }
+    }
+
+    /// Estimated number of distinct values inserted.
+    pub fn estimate(&self) -> u64 {
+        // ---
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|r| 2f64.powi(-i32::from(*r)))
+            .sum();
+        let raw = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|r| **r == 0).count();
+        // Linear counting is more accurate while many registers are still empty
+        let estimate = if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        };
+        estimate.round() as u64
+    }
+}
+
+/// Frequency estimator that also tracks the most frequent values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CountMinSketch {
+    counters: Vec<u64>,
+
+    /// Likely most frequent values with their estimates when last updated.
+    candidates: HashMap<String, u64>,
+}
+
+impl Default for CountMinSketch {
+    fn default() -> Self {
+        Self {
+            counters: vec![0; CMS_WIDTH * CMS_DEPTH],
+            candidates: HashMap::new(),
+        }
+    }
+}
+
+impl CountMinSketch {
+    // ---
+
+    /// Counter index of `value` in each row.
+    fn cells(value: &str) -> impl Iterator<Item = usize> {
+        // ---
+        let hash = hash(value);
+        let (h1, h2) = (hash & 0xffff_ffff, (hash >> 32) | 1);
+        (0..CMS_DEPTH).map(move |row| {
+            row * CMS_WIDTH
+                + (h1.wrapping_add(h2.wrapping_mul(row as u64)) % CMS_WIDTH as u64) as usize
+        })
+    }
+
+    /// Counts `value` `count` times.
+    pub fn add(&mut self, value: &str, count: u64) {
+        // ---
+        let mut estimate = u64::MAX;
+        for cell in Self::cells(value) {
+            self.counters[cell] += count;
+            estimate = estimate.min(self.counters[cell]);
+        }
+        self.track(value, estimate);
+    }
+
+    /// Upper bound of how often `value` was counted, usually exact for frequent values.
+    pub fn estimate(&self, value: &str) -> u64 {
+        Self::cells(value)
+            .map(|cell| self.counters[cell])
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Keeps `value` as a candidate if it is among the most frequent.
+    fn track(&mut self, value: &str, estimate: u64) {
+        // ---
+        if let Some(known) = self.candidates.get_mut(value) {
+            *known = estimate;
+            return;
+        }
+        if self.candidates.len() < SKETCH_TOP_VALUES {
+            self.candidates.insert(value.to_string(), estimate);
+            return;
+        }
+        let least = self
+            .candidates
+            .iter()
+            .min_by_key(|(_, count)| **count)
+            .map(|(value, count)| (value.clone(), *count));
+        if let Some((least, count)) = least {
+            if estimate > count {
+                self.candidates.remove(&least);
+                self.candidates.insert(value.to_string(), estimate);
+            }
+        }
+    }
+
+    pub fn merge(&mut self, other: &CountMinSketch) {
+        // ---
+        for (counter, other) in self.counters.iter_mut().zip(&other.counters) {
+            *counter += other;
+        }
+        let mut candidates: Vec<(String, u64)> = self
+            .candidates
+            .keys()
+            .chain(other.candidates.keys())
+            .map(|value| (value.clone(), self.estimate(value)))
+            .collect();
+        candidates.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        candidates.dedup_by(|a, b| a.0 == b.0);
+        candidates.truncate(SKETCH_TOP_VALUES);
+        self.candidates = candidates.into_iter().collect();
+    }
+
+    /// Up to `limit` most frequent values with their estimated counts, most frequent first.
+    pub fn top(&self, limit: usize) -> Vec<(String, u64)> {
+        // ---
+        let mut top: Vec<(String, u64)> = self
+            .candidates
+            .keys()
+            .map(|value| (value.clone(), self.estimate(value)))
+            .collect();
+        top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top.truncate(limit);
+        top
+    }
+}
+
+/// Quantile estimator with relative-error guarantees (a DDSketch).
+///
+/// Each number is counted in a bucket covering magnitudes from `γ^(k-1)`
+/// to `γ^k`, so any estimate is within `QUANTILE_ACCURACY` of a true value
+/// of the requested rank.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuantileSketch {
+    positive: BTreeMap<i32, u64>,
+    negative: BTreeMap<i32, u64>,
+    zeros: u64,
+    count: u64,
+}
+
+impl QuantileSketch {
+    // ---
+
+    fn gamma() -> f64 {
+        (1.0 + QUANTILE_ACCURACY) / (1.0 - QUANTILE_ACCURACY)
+    }
+
+    fn key(magnitude: f64) -> i32 {
+        (magnitude.ln() / Self::gamma().ln()).ceil() as i32
+    }
+
+    /// Midpoint, in relative terms, of the magnitudes counted under `key`.
+    fn magnitude(key: i32) -> f64 {
+        let gamma = Self::gamma();
+        2.0 * gamma.powi(key) / (gamma + 1.0)
+    }
+
+    /// Counts `value`; it must be finite.
+    pub fn insert(&mut self, value: f64) {
+        // ---
+        self.count += 1;
+        if value.abs() < QUANTILE_MIN_MAGNITUDE {
+            self.zeros += 1;
+        } else if value > 0.0 {
+            *self.positive.entry(Self::key(value)).or_default() += 1;
+        } else {
+            *self.negative.entry(Self::key(-value)).or_default() += 1;
+        }
+    }
+
+    pub fn merge(&mut self, other: &QuantileSketch) {
+        // ---
+        for (key, count) in &other.positive {
+            *self.positive.entry(*key).or_default() += count;
+        }
+        for (key, count) in &other.negative {
+            *self.negative.entry(*key).or_default() += count;
+        }
+        self.zeros += other.zeros;
+        self.count += other.count;
+    }
+
+    /// Estimated `q`-quantile (`0.0..=1.0`), or `None` if nothing was inserted.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        // ---
+        if self.count == 0 {
+            return None;
+        }
+        let rank = (q.clamp(0.0, 1.0) * (self.count - 1) as f64).round() as u64;
+        let mut seen = 0;
+        // Most negative first: the largest magnitudes of the negative store
+        for (key, count) in self.negative.iter().rev() {
+            seen += count;
+            if seen > rank {
+                return Some(-Self::magnitude(*key));
+            }
+        }
+        seen += self.zeros;
+        if seen > rank {
+            return Some(0.0);
+        }
+        for (key, count) in &self.positive {
+            seen += count;
+            if seen > rank {
+                return Some(Self::magnitude(*key));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+
+    #[test]
+    fn hyperloglog_estimates_within_a_few_percent() {
+        // ---
+        let mut first = HyperLogLog::default();
+        let mut second = HyperLogLog::default();
+        for i in 0..50_000 {
+            first.insert(&format!("user-{}", i));
+            // Overlaps the first half of `first`
+            second.insert(&format!("user-{}", i + 25_000));
+        }
+        let error = |estimate: u64, actual: f64| (estimate as f64 - actual).abs() / actual;
+        assert!(error(first.estimate(), 50_000.0) < 0.05);
+
+        first.merge(&second);
+        assert!(error(first.estimate(), 75_000.0) < 0.05);
+
+        let mut small = HyperLogLog::default();
+        for value in ["a", "b", "c", "a"] {
+            small.insert(value);
+        }
+        assert_eq!(small.estimate(), 3);
+    }
+
+    #[test]
+    fn count_min_sketch_finds_frequent_values() {
+        // ---
+        let mut first = CountMinSketch::default();
+        let mut second = CountMinSketch::default();
+        for i in 0..20_000 {
+            first.add(&format!("rare-{}", i), 1);
+        }
+        first.add("google", 500);
+        first.add("direct", 300);
+        second.add("direct", 400);
+        second.add("newsletter", 50);
+
+        first.merge(&second);
+        let top = first.top(2);
+        assert_eq!(top[0].0, "direct");
+        assert!(top[0].1 >= 700 && top[0].1 < 750, "{:?}", top);
+        assert_eq!(top[1].0, "google");
+        assert!(first.estimate("newsletter") >= 50);
+    }
+
+    #[test]
+    fn quantile_sketch_is_within_its_relative_error() {
+        // ---
+        let mut low = QuantileSketch::default();
+        let mut high = QuantileSketch::default();
+        for i in 1..=1000 {
+            low.insert(f64::from(i));
+            high.insert(f64::from(i + 1000));
+        }
+        // The sketch's error, plus rounding of the rank
+        let close = |estimate: Option<f64>, actual: f64| {
+            estimate.is_some_and(|e| (e - actual).abs() <= actual * 2.0 * QUANTILE_ACCURACY)
+        };
+        assert!(close(low.quantile(0.5), 500.0));
+        assert!(close(low.quantile(0.99), 990.0));
+
+        low.merge(&high);
+        assert!(close(low.quantile(0.5), 1000.0));
+        assert!(close(low.quantile(0.95), 1900.0));
+
+        let mut mixed = QuantileSketch::default();
+        for value in [-10.0, 0.0, 0.0, 5.0] {
+            mixed.insert(value);
+        }
+        assert!(close(mixed.quantile(0.0).map(f64::abs), 10.0));
+        assert!(mixed.quantile(0.0).unwrap() < 0.0);
+        assert_eq!(mixed.quantile(0.5), Some(0.0));
+        assert_eq!(QuantileSketch::default().quantile(0.5), None);
+    }
+}
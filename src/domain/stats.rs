@@ -0,0 +1,110 @@
+//! Summary statistics of what a repository currently holds.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+/// Stored events of one `event_type`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct EventTypeStats {
+    // ---
+    /// Number of stored events.
+    pub count: u64,
+
+    /// Earliest event timestamp.
+    pub oldest: Option<DateTime<Utc>>,
+
+    /// Latest event timestamp.
+    pub newest: Option<DateTime<Utc>>,
+
+    /// Approximate memory or storage footprint in bytes.
+    pub approx_bytes: u64,
+}
+
+/// Stored events across all types.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct RepositoryStats {
+    // ---
+    /// Number of stored events.
+    pub total_events: u64,
+
+    /// Number of distinct event types.
+    pub distinct_event_types: u64,
+
+    /// Earliest event timestamp.
+    pub oldest: Option<DateTime<Utc>>,
+
+    /// Latest event timestamp.
+    pub newest: Option<DateTime<Utc>>,
+
+    /// Approximate memory or storage footprint in bytes.
+    pub approx_bytes: u64,
+
+    /// Breakdown by event type.
+    pub event_types: BTreeMap<String, EventTypeStats>,
+}
+
+impl EventTypeStats {
+    // ---
+
+    /// Adds one event with the given timestamp and approximate size.
+    pub fn add(&mut self, timestamp: DateTime<Utc>, bytes: u64) {
+        // ---
+        self.count += 1;
+        self.approx_bytes += bytes;
+        self.oldest = Some(self.oldest.map_or(timestamp, |t| t.min(timestamp)));
+        self.newest = Some(self.newest.map_or(timestamp, |t| t.max(timestamp)));
+    }
+}
+
+impl RepositoryStats {
+    // ---
+
+    /// Totals derived from a per-type breakdown.
+    pub fn from_event_types(event_types: BTreeMap<String, EventTypeStats>) -> Self {
+        // ---
+        let stats = event_types.values();
+        Self {
+            total_events: stats.clone().map(|s| s.count).sum(),
+            distinct_event_types: event_types.len() as u64,
+            oldest: stats.clone().filter_map(|s| s.oldest).min(),
+            newest: stats.clone().filter_map(|s| s.newest).max(),
+            approx_bytes: stats.map(|s| s.approx_bytes).sum(),
+            event_types,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+
+    #[test]
+    fn totals_cover_every_type() {
+        // ---
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        let mut signup = EventTypeStats::default();
+        signup.add(at("2025-06-16T12:00:00Z"), 100);
+        signup.add(at("2025-06-16T10:00:00Z"), 50);
+        let mut login = EventTypeStats::default();
+        login.add(at("2025-06-16T13:00:00Z"), 10);
+
+        let stats = RepositoryStats::from_event_types(BTreeMap::from([
+            ("signup".to_string(), signup),
+            ("login".to_string(), login),
+        ]));
+
+        assert_eq!(stats.total_events, 3);
+        assert_eq!(stats.distinct_event_types, 2);
+        assert_eq!(stats.approx_bytes, 160);
+        assert_eq!(stats.oldest, Some(at("2025-06-16T10:00:00Z")));
+        assert_eq!(stats.newest, Some(at("2025-06-16T13:00:00Z")));
+        assert_eq!(
+            stats.event_types["signup"].newest,
+            Some(at("2025-06-16T12:00:00Z"))
+        );
+    }
+}
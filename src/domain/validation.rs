@@ -0,0 +1,373 @@
+//! Configurable validation rules applied to incoming events.
+//!
+//! `ValidationRules` describes what the service is willing to accept
+//! (event type shape, timestamp skew, payload limits) and reports every
+//! violation it finds, keyed by field and rule, so clients can fix all
+//! problems in a single round-trip.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use utoipa::ToSchema;
+
+use super::Event;
+
+/// Limits and allow-lists applied to every submitted event.
+#[derive(Debug, Clone)]
+pub struct ValidationRules {
+    // ---
+    /// Minimum length of `event_type`, in characters.
+    pub event_type_min_len: usize,
+
+    /// Maximum length of `event_type`, in characters.
+    pub event_type_max_len: usize,
+
+    /// Punctuation allowed in `event_type` in addition to ASCII alphanumerics.
+    pub event_type_extra_chars: String,
+
+    /// Optional allow-list of accepted event types (`None` accepts any type).
+    pub allowed_event_types: Option<HashSet<String>>,
+
+    /// How far into the future (relative to server time) a timestamp may be.
+    pub max_future_skew: Duration,
+
+    /// How far into the past a timestamp may be (`None` means unbounded).
+    pub max_past_skew: Option<Duration>,
+
+    /// Maximum serialized size of the payload, in bytes.
+    pub max_payload_bytes: usize,
+
+    /// Maximum nesting depth of the payload (a scalar has depth 0).
+    pub max_payload_depth: usize,
+
+    /// Maximum total number of object keys across the whole payload.
+    pub max_payload_keys: usize,
+
+    /// Maximum length of any string value inside the payload.
+    pub max_string_len: usize,
+}
+
+impl Default for ValidationRules {
+    fn default() -> Self {
+        Self {
+            event_type_min_len: 1,
+            event_type_max_len: 128,
+            event_type_extra_chars: "_-.:".to_string(),
+            allowed_event_types: None,
+            max_future_skew: Duration::minutes(5),
+            max_past_skew: None,
+            max_payload_bytes: 64 * 1024,
+            max_payload_depth: 16,
+            max_payload_keys: 512,
+            max_string_len: 8 * 1024,
+        }
+    }
+}
+
+/// A single rule violation, reported against the offending field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct FieldViolation {
+    // ---
+    /// Name of the offending field (e.g. `event_type`, `payload`).
+    pub field: String,
+
+    /// Stable identifier of the rule that failed (used as a metrics label).
+    #[schema(value_type = String)]
+    pub rule: &'static str,
+
+    /// Human-readable explanation of the failure.
+    pub message: String,
+}
+
+impl FieldViolation {
+    fn new(field: &str, rule: &'static str, message: String) -> Self {
+        Self {
+            field: field.to_string(),
+            rule,
+            message,
+        }
+    }
+}
+
+impl ValidationRules {
+    // ---
+
+    /// Validates an event against these rules, returning every violation found.
+    ///
+    /// `now` is the server's notion of the current time and is used for
+    /// timestamp skew checks. An empty result means the event is acceptable.
+    pub fn validate(&self, event: &Event, now: DateTime<Utc>) -> Vec<FieldViolation> {
+        // ---
+        let mut violations = Vec::new();
+        self.check_event_type(&event.event_type, &mut violations);
+        self.check_timestamp(event.timestamp, now, &mut violations);
+        self.check_payload(&event.payload, &mut violations);
+        violations
+    }
+
+    fn check_event_type(&self, event_type: &str, out: &mut Vec<FieldViolation>) {
+        // ---
+        let len = event_type.chars().count();
+        if len < self.event_type_min_len {
+            out.push(FieldViolation::new(
+                "event_type",
+                "event_type_min_length",
+                format!(
+                    "event_type must be at least {} characters",
+                    self.event_type_min_len
+                ),
+            ));
+        }
+        if len > self.event_type_max_len {
+            out.push(FieldViolation::new(
+                "event_type",
+                "event_type_max_length",
+                format!(
+                    "event_type must be at most {} characters, got {}",
+                    self.event_type_max_len, len
+                ),
+            ));
+        }
+
+        let invalid: Vec<char> = event_type
+            .chars()
+            .filter(|c| !c.is_ascii_alphanumeric() && !self.event_type_extra_chars.contains(*c))
+            .collect();
+        if !invalid.is_empty() {
+            out.push(FieldViolation::new(
+                "event_type",
+                "event_type_charset",
+                format!(
+                    "event_type may only contain ASCII letters, digits and '{}'; found {:?}",
+                    self.event_type_extra_chars, invalid
+                ),
+            ));
+        }
+
+        if let Some(allowed) = &self.allowed_event_types {
+            if !allowed.contains(event_type) {
+                out.push(FieldViolation::new(
+                    "event_type",
+                    "event_type_not_allowed",
+                    format!("event_type '{}' is not in the allow-list", event_type),
+                ));
+            }
+        }
+    }
+
+    fn check_timestamp(
+        &self,
+        ts: DateTime<Utc>,
+        now: DateTime<Utc>,
+        out: &mut Vec<FieldViolation>,
+    ) {
+        // ---
+        if ts > now + self.max_future_skew {
+            out.push(FieldViolation::new(
+                "timestamp",
+                "timestamp_future_skew",
+                format!(
+                    "timestamp is more than {}s ahead of server time",
+                    self.max_future_skew.num_seconds()
+                ),
+            ));
+        }
+        if let Some(max_past) = self.max_past_skew {
+            if ts < now - max_past {
+                out.push(FieldViolation::new(
+                    "timestamp",
+                    "timestamp_past_skew",
+                    format!(
+                        "timestamp is more than {}s behind server time",
+                        max_past.num_seconds()
+                    ),
+                ));
+            }
+        }
+    }
+
+    fn check_payload(&self, payload: &serde_json::Value, out: &mut Vec<FieldViolation>) {
+        // ---
+        let size = serde_json::to_vec(payload).map(|v| v.len()).unwrap_or(0);
+        if size > self.max_payload_bytes {
+            out.push(FieldViolation::new(
+                "payload",
+                "payload_max_bytes",
+                format!(
+                    "payload is {} bytes, maximum is {}",
+                    size, self.max_payload_bytes
+                ),
+            ));
+        }
+
+        let stats = PayloadStats::collect(payload);
+        if stats.depth > self.max_payload_depth {
+            out.push(FieldViolation::new(
+                "payload",
+                "payload_max_depth",
+                format!(
+                    "payload nesting depth is {}, maximum is {}",
+                    stats.depth, self.max_payload_depth
+                ),
+            ));
+        }
+        if stats.keys > self.max_payload_keys {
+            out.push(FieldViolation::new(
+                "payload",
+                "payload_max_keys",
+                format!(
+                    "payload has {} keys, maximum is {}",
+                    stats.keys, self.max_payload_keys
+                ),
+            ));
+        }
+        if stats.longest_string > self.max_string_len {
+            out.push(FieldViolation::new(
+                "payload",
+                "payload_max_string_length",
+                format!(
+                    "payload contains a string of {} characters, maximum is {}",
+                    stats.longest_string, self.max_string_len
+                ),
+            ));
+        }
+    }
+}
+
+/// Structural measurements of a JSON payload gathered in one traversal.
+#[derive(Debug, Default)]
+struct PayloadStats {
+    depth: usize,
+    keys: usize,
+    longest_string: usize,
+}
+
+impl PayloadStats {
+    fn collect(value: &serde_json::Value) -> Self {
+        // ---
+        let mut stats = PayloadStats::default();
+        stats.visit(value, 0);
+        stats
+    }
+
+    fn visit(&mut self, value: &serde_json::Value, depth: usize) {
+        // ---
+        self.depth = self.depth.max(depth);
+        match value {
+            serde_json::Value::Object(map) => {
+                self.keys += map.len();
+                for (key, child) in map {
+                    self.longest_string = self.longest_string.max(key.chars().count());
+                    self.visit(child, depth + 1);
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for child in items {
+                    self.visit(child, depth + 1);
+                }
+            }
+            serde_json::Value::String(s) => {
+                self.longest_string = self.longest_string.max(s.chars().count());
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn make_event(event_type: &str, timestamp: DateTime<Utc>, payload: serde_json::Value) -> Event {
+        // ---
+        Event {
+            id: Uuid::new_v4(),
+            event_type: event_type.to_string(),
+            timestamp,
+            payload,
+            trace: None,
+        }
+    }
+
+    fn rules_hit(violations: &[FieldViolation]) -> Vec<&'static str> {
+        violations.iter().map(|v| v.rule).collect()
+    }
+
+    #[test]
+    fn accepts_well_formed_event() {
+        // ---
+        let now = Utc::now();
+        let event = make_event("user_signup", now, json!({ "user_id": "42" }));
+        assert!(ValidationRules::default().validate(&event, now).is_empty());
+    }
+
+    #[test]
+    fn reports_every_violation() {
+        // ---
+        let now = Utc::now();
+        let event = make_event("", now + Duration::hours(1), json!({}));
+        let rules = rules_hit(&ValidationRules::default().validate(&event, now));
+        assert_eq!(
+            rules,
+            vec!["event_type_min_length", "timestamp_future_skew"]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_event_type_charset_and_allow_list() {
+        // ---
+        let now = Utc::now();
+        let rules = ValidationRules {
+            allowed_event_types: Some(["login".to_string()].into_iter().collect()),
+            ..ValidationRules::default()
+        };
+        let event = make_event("sign up!", now, json!(null));
+        let hit = rules_hit(&rules.validate(&event, now));
+        assert_eq!(hit, vec!["event_type_charset", "event_type_not_allowed"]);
+    }
+
+    #[test]
+    fn enforces_past_skew_only_when_configured() {
+        // ---
+        let now = Utc::now();
+        let event = make_event("old", now - Duration::days(30), json!(null));
+        assert!(ValidationRules::default().validate(&event, now).is_empty());
+
+        let rules = ValidationRules {
+            max_past_skew: Some(Duration::days(7)),
+            ..ValidationRules::default()
+        };
+        assert_eq!(
+            rules_hit(&rules.validate(&event, now)),
+            vec!["timestamp_past_skew"]
+        );
+    }
+
+    #[test]
+    fn enforces_payload_shape_limits() {
+        // ---
+        let now = Utc::now();
+        let rules = ValidationRules {
+            max_payload_bytes: 32,
+            max_payload_depth: 2,
+            max_payload_keys: 3,
+            max_string_len: 4,
+            ..ValidationRules::default()
+        };
+        let payload = json!({ "a": { "b": { "c": "too long" } }, "d": 1 });
+        let hit = rules_hit(&rules.validate(&make_event("deep", now, payload), now));
+        assert_eq!(
+            hit,
+            vec![
+                "payload_max_bytes",
+                "payload_max_depth",
+                "payload_max_keys",
+                "payload_max_string_length"
+            ]
+        );
+    }
+}
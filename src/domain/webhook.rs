@@ -0,0 +1,139 @@
+//! Webhook subscriptions and their deliveries.
+//!
+//! A subscription asks for every stored event of one `event_type` whose
+//! payload contains the given filter fields to be POSTed to a URL. Each
+//! match becomes a `Delivery` that is retried until it succeeds or runs
+//! out of attempts, at which point it becomes a `DeadLetter`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{Event, EventFilter};
+
+/// A registered webhook.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Subscription {
+    // ---
+    pub id: Uuid,
+
+    /// Events that are delivered.
+    #[serde(flatten)]
+    pub events: EventFilter,
+
+    /// Endpoint receiving the deliveries.
+    pub url: String,
+
+    /// Key of the HMAC-SHA256 signature sent with every delivery.
+    pub secret: String,
+
+    pub created_at: DateTime<Utc>,
+}
+
+impl Subscription {
+    // ---
+
+    /// Whether `event` should be delivered to this subscription.
+    pub fn matches(&self, event: &Event) -> bool {
+        self.events.matches(event)
+    }
+}
+
+/// One event queued for one subscription.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Delivery {
+    // ---
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+    pub event: Event,
+
+    /// Attempts made so far.
+    pub attempts: u32,
+
+    /// Earliest time of the next attempt.
+    pub next_attempt_at: DateTime<Utc>,
+
+    /// Why the previous attempt failed.
+    pub last_error: Option<String>,
+}
+
+impl Delivery {
+    // ---
+
+    /// A delivery of `event` to `subscription`, due now.
+    pub fn new(subscription: &Subscription, event: Event) -> Self {
+        // ---
+        Self {
+            id: Uuid::new_v4(),
+            subscription_id: subscription.id,
+            event,
+            attempts: 0,
+            next_attempt_at: Utc::now(),
+            last_error: None,
+        }
+    }
+}
+
+/// A delivery that was given up on.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DeadLetter {
+    // ---
+    /// Id of the failed delivery.
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+
+    /// Target URL at the time of the last attempt.
+    pub url: String,
+    pub event: Event,
+    pub attempts: u32,
+
+    /// Error of the last attempt.
+    pub last_error: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+    use crate::domain::EventBuilder;
+    use serde_json::json;
+    use serde_json::Value;
+
+    fn subscription(filter: Option<Value>) -> Subscription {
+        // ---
+        Subscription {
+            id: Uuid::new_v4(),
+            events: EventFilter {
+                event_type: "purchase".to_string(),
+                filter: filter.and_then(|f| f.as_object().cloned()),
+            },
+            url: "http://localhost/hook".to_string(),
+            secret: "secret".to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn matches_type_and_filter_fields() {
+        // ---
+        let any = subscription(None);
+        assert!(any.matches(&EventBuilder::new("purchase").payload(json!({})).build()));
+        assert!(!any.matches(&EventBuilder::new("signup").payload(json!({})).build()));
+
+        let eur = subscription(Some(json!({ "currency": "EUR" })));
+        assert!(eur.matches(
+            &EventBuilder::new("purchase")
+                .payload(json!({ "currency": "EUR", "amount": 5 }))
+                .build()
+        ));
+        assert!(!eur.matches(
+            &EventBuilder::new("purchase")
+                .payload(json!({ "currency": "USD" }))
+                .build()
+        ));
+        assert!(!eur.matches(&EventBuilder::new("purchase").payload(json!({})).build()));
+    }
+}
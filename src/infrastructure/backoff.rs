@@ -0,0 +1,28 @@
+//! Retry delays shared by the outbound HTTP clients.
+
+use std::time::Duration;
+
+/// Delay before retry number `attempt` (0-based): `initial` doubled per
+/// attempt, capped at `max`.
+pub fn exponential_backoff(initial: Duration, max: Duration, attempt: u32) -> Duration {
+    // ---
+    initial
+        .checked_mul(2u32.saturating_pow(attempt))
+        .map_or(max, |delay| delay.min(max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let initial = Duration::from_millis(100);
+        let max = Duration::from_millis(500);
+        let delays: Vec<_> = (0..5)
+            .map(|n| exponential_backoff(initial, max, n))
+            .collect();
+        assert_eq!(delays, [100, 200, 400, 500, 500].map(Duration::from_millis));
+        assert_eq!(exponential_backoff(initial, max, 40), max);
+    }
+}
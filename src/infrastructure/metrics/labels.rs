@@ -0,0 +1,116 @@
+//! Bounded label values.
+//!
+//! Client-controlled values such as `event_type` would otherwise create one
+//! time series per distinct value. `LabelLimiter` admits the first `limit`
+//! distinct values and folds the rest into a single overflow label.
+//! `GaugeLabels` applies the same bound to gauges set from snapshots.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::sync::Mutex;
+
+/// Label value recorded once the limit of distinct values is reached.
+pub const OVERFLOW_LABEL: &str = "__other__";
+
+/// Admits at most `limit` distinct values for one label.
+pub struct LabelLimiter {
+    limit: usize,
+    seen: Mutex<HashSet<String>>,
+}
+
+impl LabelLimiter {
+    // ---
+
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            seen: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Returns `value` if it is (or can become) one of the tracked values,
+    /// otherwise the overflow label.
+    pub fn label<'a>(&self, value: &'a str) -> &'a str {
+        // ---
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        if seen.contains(value) {
+            return value;
+        }
+        if seen.len() < self.limit {
+            seen.insert(value.to_string());
+            return value;
+        }
+        OVERFLOW_LABEL
+    }
+}
+
+/// Tracks which label values a snapshot gauge was last set for.
+///
+/// A gauge series keeps its last value until it is set again, so values
+/// that drop out of a snapshot (e.g. purged event types) are reported as
+/// zero once.
+#[derive(Default)]
+pub struct GaugeLabels {
+    published: Mutex<BTreeSet<String>>,
+}
+
+impl GaugeLabels {
+    // ---
+
+    /// Folds `values` into bounded labels (summing overflowed ones) and adds
+    /// a zero for every label published last time but absent now.
+    pub fn snapshot<'a>(
+        &self,
+        limiter: &LabelLimiter,
+        values: impl IntoIterator<Item = (&'a str, u64)>,
+    ) -> BTreeMap<String, u64> {
+        // ---
+        let mut current = BTreeMap::new();
+        for (value, amount) in values {
+            *current.entry(limiter.label(value).to_string()).or_insert(0) += amount;
+        }
+
+        let mut published = self.published.lock().unwrap_or_else(|e| e.into_inner());
+        let mut snapshot = current.clone();
+        for stale in published
+            .iter()
+            .filter(|label| !current.contains_key(*label))
+        {
+            snapshot.insert(stale.clone(), 0);
+        }
+        *published = current.into_keys().collect();
+        snapshot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_beyond_limit_share_overflow_label() {
+        let limiter = LabelLimiter::new(2);
+        assert_eq!(limiter.label("signup"), "signup");
+        assert_eq!(limiter.label("purchase"), "purchase");
+        assert_eq!(limiter.label("refund"), OVERFLOW_LABEL);
+        assert_eq!(limiter.label("signup"), "signup");
+    }
+
+    #[test]
+    fn gauge_snapshots_zero_vanished_labels() {
+        let limiter = LabelLimiter::new(1);
+        let gauges = GaugeLabels::default();
+
+        let first = gauges.snapshot(&limiter, [("signup", 3), ("refund", 1), ("login", 2)]);
+        assert_eq!(
+            first,
+            BTreeMap::from([("signup".to_string(), 3), (OVERFLOW_LABEL.to_string(), 3)])
+        );
+
+        let second = gauges.snapshot(&limiter, [("signup", 4)]);
+        assert_eq!(
+            second,
+            BTreeMap::from([("signup".to_string(), 4), (OVERFLOW_LABEL.to_string(), 0)])
+        );
+        assert_eq!(gauges.snapshot(&limiter, [("signup", 4)]).len(), 1);
+    }
+}
@@ -1,2 +1,8 @@
+mod labels;
 pub mod noop;
 pub mod prometheus;
+pub mod push;
+pub mod statsd;
+
+// Shared by the backends that label series by event type
+pub(crate) use labels::{GaugeLabels, LabelLimiter};
@@ -1,6 +1,7 @@
-use crate::domain::Metrics;
+use crate::domain::{AlertStatus, ComponentHealth, Metrics, RepositoryStats};
 use anyhow::Result;
-use std::time::Instant;
+use async_trait::async_trait;
+use std::time::{Duration, Instant};
 
 /// No-op metrics implementation for testing.
 pub struct NoopMetrics;
@@ -11,11 +12,25 @@ impl NoopMetrics {
     }
 }
 
+#[async_trait]
 impl Metrics for NoopMetrics {
     // ---
     fn render(&self) -> Result<String> {
         Ok(String::new())
     }
-    fn record_event_created(&self) {}
+    fn record_event_created(&self, _: &str) {}
     fn record_http_request(&self, _: Instant, _: &str, _: &str, _: u16) {}
+    fn record_http_in_flight(&self, _: &str, _: i64) {}
+    fn record_http_body_sizes(&self, _: &str, _: &str, _: Option<u64>, _: Option<u64>) {}
+    fn record_repository_operation(&self, _: &str, _: Duration, _: bool) {}
+    fn record_query_result_size(&self, _: usize) {}
+    fn record_validation_failure(&self, _: &str) {}
+    fn record_repository_stats(&self, _: &RepositoryStats) {}
+    fn record_webhook_delivery(&self, _: &str, _: Duration) {}
+    fn record_alert(&self, _: &AlertStatus) {}
+    fn record_schema_drift(&self, _: &str, _: &str) {}
+    async fn flush(&self) {}
+    fn health(&self) -> ComponentHealth {
+        ComponentHealth::healthy("metrics", "noop").with_detail("metrics disabled")
+    }
 }
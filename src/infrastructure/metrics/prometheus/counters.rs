@@ -1,13 +1,113 @@
-use metrics::{counter, histogram};
-use std::time::Instant;
+use metrics::{counter, gauge, histogram};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
 
-/// Increment a counter for created events.
-pub fn increment_event_created() {
-    counter!("events_created_total").increment(1);
+use crate::domain::{AlertState, AlertStatus, RepositoryStats};
+
+/// Increment a counter for created events of the given (already bounded) type label.
+pub fn increment_event_created(event_type: &str) {
+    counter!("events_created_total", "event_type" => event_type.to_string()).increment(1);
+}
+
+/// Increment the validation-failure counter for the given rule.
+pub fn increment_validation_failure(rule: &str) {
+    counter!("event_validation_failures_total", "rule" => rule.to_string()).increment(1);
+}
+
+/// Increment the schema-drift counter for the given (already bounded) type label and change.
+pub fn increment_schema_drift(event_type: &str, change: &str) {
+    counter!(
+        "schema_drift_total",
+        "event_type" => event_type.to_string(),
+        "change" => change.to_string()
+    )
+    .increment(1);
+}
+
+/// Track HTTP request count and latency, labeled by route template, method and status class.
+pub fn track_http_request(start: Instant, route: &str, method: &str, status: u16) {
+    let labels = [
+        ("route", route.to_string()),
+        ("method", method.to_string()),
+        ("status", status_class(status).to_string()),
+    ];
+    counter!("http_requests_total", &labels).increment(1);
+    histogram!("http_request_duration_seconds", &labels).record(start.elapsed());
+}
+
+/// Adjust the in-flight request gauge for a route template.
+pub fn adjust_http_in_flight(route: &str, delta: i64) {
+    gauge!("http_requests_in_flight", "route" => route.to_string()).increment(delta as f64);
+}
+
+/// Track request and response body sizes, when known.
+pub fn track_http_body_sizes(
+    route: &str,
+    method: &str,
+    request_bytes: Option<u64>,
+    response_bytes: Option<u64>,
+) {
+    let labels = [("route", route.to_string()), ("method", method.to_string())];
+    if let Some(bytes) = request_bytes {
+        histogram!("http_request_size_bytes", &labels).record(bytes as f64);
+    }
+    if let Some(bytes) = response_bytes {
+        histogram!("http_response_size_bytes", &labels).record(bytes as f64);
+    }
+}
+
+/// Track repository call latency, labeled by operation and outcome.
+pub fn track_repository_operation(operation: &str, elapsed: Duration, success: bool) {
+    let outcome = if success { "ok" } else { "error" };
+    histogram!(
+        "repository_operation_duration_seconds",
+        "operation" => operation.to_string(),
+        "outcome" => outcome
+    )
+    .record(elapsed);
+}
+
+/// Track the number of events returned by a repository query.
+pub fn track_query_result_size(count: usize) {
+    histogram!("repository_query_result_size").record(count as f64);
+}
+
+/// Track a webhook delivery attempt, labeled by outcome.
+pub fn track_webhook_delivery(outcome: &str, elapsed: Duration) {
+    let labels = [("outcome", outcome.to_string())];
+    counter!("webhook_deliveries_total", &labels).increment(1);
+    histogram!("webhook_delivery_duration_seconds", &labels).record(elapsed);
+}
+
+/// Set the repository gauges from a stats snapshot; `events_by_type` is already bounded.
+pub fn set_repository_stats(stats: &RepositoryStats, events_by_type: &BTreeMap<String, u64>) {
+    for (event_type, count) in events_by_type {
+        gauge!("repository_events", "event_type" => event_type.clone()).set(*count as f64);
+    }
+    gauge!("repository_event_types").set(stats.distinct_event_types as f64);
+    gauge!("repository_size_bytes").set(stats.approx_bytes as f64);
+    let seconds =
+        |t: Option<chrono::DateTime<chrono::Utc>>| t.map_or(0.0, |t| t.timestamp() as f64);
+    gauge!("repository_oldest_event_timestamp_seconds").set(seconds(stats.oldest));
+    gauge!("repository_newest_event_timestamp_seconds").set(seconds(stats.newest));
+}
+
+/// Set the gauges of one alert rule: 1 for its current state and 0 for the others, plus its value.
+pub fn set_alert_status(status: &AlertStatus) {
+    for state in AlertState::ALL {
+        let active = if state == status.state { 1.0 } else { 0.0 };
+        gauge!("alert_state", "rule" => status.rule.clone(), "state" => state.as_str()).set(active);
+    }
+    gauge!("alert_value", "rule" => status.rule.clone()).set(status.value);
 }
 
-/// Track HTTP request latency using a histogram.
-pub fn track_http_request(start: Instant) {
-    let elapsed = start.elapsed();
-    histogram!("http_request_duration_seconds").record(elapsed);
+/// Groups a status code into its class ("2xx", "4xx", ...) to bound label cardinality.
+fn status_class(status: u16) -> &'static str {
+    match status {
+        100..=199 => "1xx",
+        200..=299 => "2xx",
+        300..=399 => "3xx",
+        400..=499 => "4xx",
+        _ => "5xx",
+    }
 }
@@ -5,9 +5,17 @@ mod recorder;
 pub use prometheus_metrics::PrometheusMetrics;
 use std::sync::Arc;
 
+use crate::config::MetricsSettings;
+
 // Re-export utilities for internal use within this module
-pub(crate) use counters::{increment_event_created, track_http_request};
-pub(crate) use recorder::{init_metrics, render_metrics};
+pub(crate) use super::{GaugeLabels, LabelLimiter};
+pub(crate) use counters::{
+    adjust_http_in_flight, increment_event_created, increment_schema_drift,
+    increment_validation_failure, set_alert_status, set_repository_stats, track_http_body_sizes,
+    track_http_request, track_query_result_size, track_repository_operation,
+    track_webhook_delivery,
+};
+pub(crate) use recorder::{build_recorder, InstanceRecorder};
 
 /// Creates a new Prometheus metrics implementation.
 ///
@@ -15,13 +23,19 @@ pub(crate) use recorder::{init_metrics, render_metrics};
 /// expose them via HTTP endpoint for scraping.
 ///
 /// Returns a fully initialized metrics instance ready for use.
-pub fn create() -> anyhow::Result<crate::domain::MetricsPtr> {
+pub fn create(settings: &MetricsSettings) -> anyhow::Result<crate::domain::MetricsPtr> {
     // ---
     tracing::info!("Initializing Prometheus metrics");
-    // TODO: Start HTTP server for /metrics endpoint, initialize registry, etc.
-    init_metrics()?;
+    let recorder = build_recorder(settings)?;
+    if settings.install_global {
+        recorder.install_global()?;
+    }
 
-    Ok(Arc::new(PrometheusMetrics::new()))
+    Ok(Arc::new(PrometheusMetrics::new(
+        recorder,
+        settings.event_type_label_limit,
+        settings.install_global,
+    )))
 }
 
 #[cfg(test)]
@@ -30,7 +44,19 @@ mod tests {
 
     #[test]
     fn test_create_returns_valid_metrics() {
-        let result = create();
+        let result = create(&MetricsSettings::default());
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn instances_do_not_share_series() -> anyhow::Result<()> {
+        let first = create(&MetricsSettings::default())?;
+        let second = create(&MetricsSettings::default())?;
+
+        first.record_event_created("only_in_first");
+
+        assert!(first.render()?.contains("only_in_first"));
+        assert!(!second.render()?.contains("only_in_first"));
+        Ok(())
+    }
 }
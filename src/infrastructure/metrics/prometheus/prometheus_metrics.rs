@@ -3,52 +3,145 @@
 //! This module provides a concrete implementation of the `Metrics` trait using
 //! the Prometheus metrics format. It delegates to utility functions in sibling
 //! modules (`counters.rs`, `recorder.rs`) which handle the actual metrics
-//! collection via the global `metrics` crate registry.
+//! collection via the `metrics` crate macros.
 //!
-//! The implementation follows a global registry pattern where metrics are
-//! automatically registered when first used, and a single global handle
-//! manages rendering all collected metrics in Prometheus text format.
+//! Every instance owns its own recorder: the macros in `counters.rs` are run
+//! with that recorder installed locally, so each instance renders only the
+//! series it recorded.
 
-use crate::domain::Metrics;
+use super::{GaugeLabels, InstanceRecorder, LabelLimiter};
+use crate::domain::{AlertStatus, ComponentHealth, Metrics, RepositoryStats};
 use anyhow::Result;
-use std::time::Instant;
+use async_trait::async_trait;
+use std::time::{Duration, Instant};
 
 /// Prometheus-based metrics implementation.
 ///
-/// This struct is intentionally empty because we use the global metrics registry
-/// pattern via the `metrics` crate. All metrics are registered globally using
-/// macros like `counter!()` and `histogram!()`, and the global PrometheusHandle
-/// stored in `recorder.rs` manages the actual metrics collection and rendering.
+/// Holds the instance-scoped recorder and per-instance policy such as the
+/// `event_type` label limit.
 pub struct PrometheusMetrics {
-    // Empty - uses global metrics registry pattern
+    recorder: InstanceRecorder,
+    event_types: LabelLimiter,
+    stored_types: GaugeLabels,
+    global: bool,
 }
 
 impl PrometheusMetrics {
     // ---
-    pub fn new() -> Self {
-        tracing::info!("Creating Prometheus metrics");
-        PrometheusMetrics {}
+    pub fn new(recorder: InstanceRecorder, event_type_label_limit: usize, global: bool) -> Self {
+        tracing::info!(global, "Creating Prometheus metrics");
+        PrometheusMetrics {
+            recorder,
+            event_types: LabelLimiter::new(event_type_label_limit),
+            stored_types: GaugeLabels::default(),
+            global,
+        }
     }
 }
 
+#[async_trait]
 impl Metrics for PrometheusMetrics {
     // ---
 
     fn render(&self) -> Result<String> {
         // ---
-        // Use the recorder utility to get actual metrics
-        super::render_metrics()
+        Ok(self.recorder.render())
     }
 
-    fn record_event_created(&self) {
+    fn record_event_created(&self, event_type: &str) {
         // ---
-        tracing::debug!("Recording event created event");
-        super::increment_event_created();
+        tracing::debug!(event_type, "Recording event created event");
+        let label = self.event_types.label(event_type);
+        self.recorder
+            .record(|| super::increment_event_created(label));
     }
 
-    fn record_http_request(&self, start: Instant, _path: &str, _method: &str, _status: u16) {
+    fn record_http_request(&self, start: Instant, path: &str, method: &str, status: u16) {
         // ---
-        tracing::debug!("Recording HTTP request duration");
-        super::track_http_request(start);
+        tracing::debug!(path, method, status, "Recording HTTP request duration");
+        self.recorder
+            .record(|| super::track_http_request(start, path, method, status));
+    }
+
+    fn record_http_in_flight(&self, path: &str, delta: i64) {
+        // ---
+        self.recorder
+            .record(|| super::adjust_http_in_flight(path, delta));
+    }
+
+    fn record_http_body_sizes(
+        &self,
+        path: &str,
+        method: &str,
+        request_bytes: Option<u64>,
+        response_bytes: Option<u64>,
+    ) {
+        // ---
+        self.recorder
+            .record(|| super::track_http_body_sizes(path, method, request_bytes, response_bytes));
+    }
+
+    fn record_repository_operation(&self, operation: &str, elapsed: Duration, success: bool) {
+        // ---
+        self.recorder
+            .record(|| super::track_repository_operation(operation, elapsed, success));
+    }
+
+    fn record_query_result_size(&self, count: usize) {
+        // ---
+        self.recorder
+            .record(|| super::track_query_result_size(count));
+    }
+
+    fn record_validation_failure(&self, rule: &str) {
+        // ---
+        tracing::debug!(rule, "Recording validation failure");
+        self.recorder
+            .record(|| super::increment_validation_failure(rule));
+    }
+
+    fn record_repository_stats(&self, stats: &RepositoryStats) {
+        // ---
+        let by_type = self.stored_types.snapshot(
+            &self.event_types,
+            stats
+                .event_types
+                .iter()
+                .map(|(event_type, s)| (event_type.as_str(), s.count)),
+        );
+        self.recorder
+            .record(|| super::set_repository_stats(stats, &by_type));
+    }
+
+    fn record_webhook_delivery(&self, outcome: &str, elapsed: Duration) {
+        // ---
+        self.recorder
+            .record(|| super::track_webhook_delivery(outcome, elapsed));
+    }
+
+    fn record_alert(&self, status: &AlertStatus) {
+        // ---
+        self.recorder.record(|| super::set_alert_status(status));
+    }
+
+    fn record_schema_drift(&self, event_type: &str, change: &str) {
+        // ---
+        let label = self.event_types.label(event_type);
+        self.recorder
+            .record(|| super::increment_schema_drift(label, change));
+    }
+
+    async fn flush(&self) {
+        // Scraped via `render`; nothing to push
+    }
+
+    fn health(&self) -> ComponentHealth {
+        // ---
+        let scope = if self.global {
+            "instance registry, installed globally"
+        } else {
+            "instance registry"
+        };
+        ComponentHealth::healthy("metrics", "prometheus").with_detail(scope)
     }
 }
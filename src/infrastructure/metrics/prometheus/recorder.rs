@@ -1,37 +1,114 @@
+//! Per-instance Prometheus recorder.
+//!
+//! Each `PrometheusMetrics` owns its own recorder and handle, so several
+//! apps in one process (e.g. parallel tests) render only their own series.
+//! Metric macros are routed to the instance with `metrics::with_local_recorder`.
+//! Installing the recorder globally is opt-in, for picking up `metrics`
+//! macros emitted by other crates.
+
 use anyhow::Result;
-use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
-use std::sync::OnceLock;
+use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+use metrics_exporter_prometheus::{
+    Matcher, PrometheusBuilder, PrometheusHandle, PrometheusRecorder,
+};
+use std::sync::Arc;
+
+use crate::config::MetricsSettings;
+
+/// Histograms whose buckets come from `[metrics] latency_buckets`.
+const LATENCY_HISTOGRAMS: [&str; 3] = [
+    "http_request_duration_seconds",
+    "repository_operation_duration_seconds",
+    "webhook_delivery_duration_seconds",
+];
+
+/// Histograms whose buckets come from `[metrics] result_size_buckets`.
+const SIZE_HISTOGRAMS: [&str; 1] = ["repository_query_result_size"];
 
-static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+/// Histograms whose buckets come from `[metrics] body_size_buckets`.
+const BODY_SIZE_HISTOGRAMS: [&str; 2] = ["http_request_size_bytes", "http_response_size_bytes"];
 
-/// Initialize the Prometheus recorder globally and store the handle.
-/// This function is safe to call multiple times - it will only initialize once.
-/// Returns true if initialization was successful, false if already initialized.
-pub fn init_metrics() -> Result<bool> {
+/// A Prometheus recorder and the handle used to render it.
+pub struct InstanceRecorder {
+    recorder: Arc<PrometheusRecorder>,
+    handle: PrometheusHandle,
+}
+
+/// Builds a recorder with the configured histogram buckets.
+pub fn build_recorder(settings: &MetricsSettings) -> Result<InstanceRecorder> {
     // ---
-    // Check if already initialized before attempting initialization
-    if HANDLE.get().is_some() {
-        return Ok(false); // Already initialized
+    let mut builder = PrometheusBuilder::new();
+    for name in LATENCY_HISTOGRAMS {
+        builder = builder
+            .set_buckets_for_metric(Matcher::Full(name.to_string()), &settings.latency_buckets)?;
+    }
+    for name in SIZE_HISTOGRAMS {
+        builder = builder.set_buckets_for_metric(
+            Matcher::Full(name.to_string()),
+            &settings.result_size_buckets,
+        )?;
     }
+    for name in BODY_SIZE_HISTOGRAMS {
+        builder = builder
+            .set_buckets_for_metric(Matcher::Full(name.to_string()), &settings.body_size_buckets)?;
+    }
+
+    let recorder = builder.build_recorder();
+    let handle = recorder.handle();
+    Ok(InstanceRecorder {
+        recorder: Arc::new(recorder),
+        handle,
+    })
+}
 
-    // Try to initialize the recorder
-    let handle = PrometheusBuilder::new()
-        .install_recorder()
-        .map_err(|e| anyhow::anyhow!("Failed to install Prometheus recorder: {}", e))?;
+impl InstanceRecorder {
+    // ---
 
-    // Store the handle - this will only succeed if we're the first to initialize
-    match HANDLE.set(handle) {
-        Ok(()) => Ok(true),  // Successfully initialized
-        Err(_) => Ok(false), // Another thread beat us to it, but that's fine
+    /// Runs `f` with this recorder receiving all `metrics` macro calls.
+    pub fn record<T>(&self, f: impl FnOnce() -> T) -> T {
+        metrics::with_local_recorder(self.recorder.as_ref(), f)
+    }
+
+    /// Render this instance's metrics in Prometheus text format.
+    pub fn render(&self) -> String {
+        self.handle.render()
+    }
+
+    /// Installs this recorder as the process-wide `metrics` recorder.
+    ///
+    /// Fails if another global recorder is already installed.
+    pub fn install_global(&self) -> Result<()> {
+        // ---
+        metrics::set_global_recorder(SharedRecorder(self.recorder.clone()))
+            .map_err(|e| anyhow::anyhow!("Failed to install Prometheus recorder: {}", e))
     }
 }
 
-/// Render the current metrics in Prometheus text format.
-pub fn render_metrics() -> Result<String> {
-    // ---
-    let handle = HANDLE.get().ok_or_else(|| {
-        anyhow::anyhow!("Metrics recorder not initialized. Call init_metrics() first.")
-    })?;
+/// Lets the instance and the global registry share one recorder.
+struct SharedRecorder(Arc<PrometheusRecorder>);
+
+impl Recorder for SharedRecorder {
+    fn describe_counter(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.0.describe_counter(key, unit, description)
+    }
 
-    Ok(handle.render())
+    fn describe_gauge(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.0.describe_gauge(key, unit, description)
+    }
+
+    fn describe_histogram(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.0.describe_histogram(key, unit, description)
+    }
+
+    fn register_counter(&self, key: &Key, metadata: &Metadata<'_>) -> Counter {
+        self.0.register_counter(key, metadata)
+    }
+
+    fn register_gauge(&self, key: &Key, metadata: &Metadata<'_>) -> Gauge {
+        self.0.register_gauge(key, metadata)
+    }
+
+    fn register_histogram(&self, key: &Key, metadata: &Metadata<'_>) -> Histogram {
+        self.0.register_histogram(key, metadata)
+    }
 }
@@ -0,0 +1,24 @@
+mod push_metrics;
+mod pusher;
+
+pub use push_metrics::PushMetrics;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::MetricsSettings;
+use pusher::Pusher;
+
+/// Creates Prometheus metrics that are pushed to `settings.push.url`.
+///
+/// Metrics are recorded exactly as by the `prom` backend and rendered in
+/// the text exposition format, which push gateways accept. Must be called
+/// within a Tokio runtime, which runs the periodic push.
+///
+/// Returns a fully initialized metrics instance ready for use.
+pub fn create(settings: &MetricsSettings) -> anyhow::Result<crate::domain::MetricsPtr> {
+    // ---
+    let inner = super::prometheus::create(settings)?;
+    let pusher = Pusher::new(&settings.push)?;
+    let interval = Duration::from_millis(settings.push.interval_ms);
+    Ok(Arc::new(PushMetrics::start(inner, pusher, interval)?))
+}
@@ -0,0 +1,153 @@
+//! Prometheus metrics that are pushed instead of (only) scraped.
+//!
+//! Recording is delegated to a Prometheus backend; a background task
+//! renders it every interval and hands the text to the `Pusher`. The
+//! series remain available on `/metrics` as well.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+
+use super::pusher::Pusher;
+use crate::domain::{AlertStatus, ComponentHealth, Metrics, MetricsPtr, RepositoryStats};
+
+/// Push-mode metrics implementation.
+pub struct PushMetrics {
+    inner: MetricsPtr,
+    pusher: Arc<Pusher>,
+    task: JoinHandle<()>,
+}
+
+impl PushMetrics {
+    // ---
+
+    /// Starts pushing `inner` every `interval` on the current Tokio runtime.
+    pub fn start(inner: MetricsPtr, pusher: Pusher, interval: Duration) -> Result<Self> {
+        // ---
+        let runtime = Handle::try_current()
+            .map_err(|_| anyhow::anyhow!("Push metrics must be created within a Tokio runtime"))?;
+        tracing::info!(url = %pusher.url(), ?interval, "Creating push metrics");
+        let pusher = Arc::new(pusher);
+        let task = runtime.spawn(push_loop(inner.clone(), pusher.clone(), interval));
+        Ok(Self {
+            inner,
+            pusher,
+            task,
+        })
+    }
+}
+
+impl Drop for PushMetrics {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn push_loop(metrics: MetricsPtr, pusher: Arc<Pusher>, interval: Duration) {
+    // ---
+    let mut ticks = tokio::time::interval(interval);
+    ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    // The first tick completes immediately; nothing has been recorded yet
+    ticks.tick().await;
+    loop {
+        ticks.tick().await;
+        push_once(metrics.as_ref(), &pusher).await;
+    }
+}
+
+async fn push_once(metrics: &dyn Metrics, pusher: &Pusher) {
+    // ---
+    let body = match metrics.render() {
+        Ok(body) => body,
+        Err(err) => {
+            tracing::error!("Failed to render metrics for push: {:#}", err);
+            return;
+        }
+    };
+    if let Err(err) = pusher.push(body).await {
+        tracing::warn!("Metrics push failed: {:#}", err);
+    }
+}
+
+#[async_trait]
+impl Metrics for PushMetrics {
+    // ---
+
+    fn render(&self) -> Result<String> {
+        self.inner.render()
+    }
+
+    fn record_event_created(&self, event_type: &str) {
+        self.inner.record_event_created(event_type);
+    }
+
+    fn record_http_request(&self, start: Instant, path: &str, method: &str, status: u16) {
+        self.inner.record_http_request(start, path, method, status);
+    }
+
+    fn record_http_in_flight(&self, path: &str, delta: i64) {
+        self.inner.record_http_in_flight(path, delta);
+    }
+
+    fn record_http_body_sizes(
+        &self,
+        path: &str,
+        method: &str,
+        request_bytes: Option<u64>,
+        response_bytes: Option<u64>,
+    ) {
+        self.inner
+            .record_http_body_sizes(path, method, request_bytes, response_bytes);
+    }
+
+    fn record_repository_operation(&self, operation: &str, elapsed: Duration, success: bool) {
+        self.inner
+            .record_repository_operation(operation, elapsed, success);
+    }
+
+    fn record_query_result_size(&self, count: usize) {
+        self.inner.record_query_result_size(count);
+    }
+
+    fn record_validation_failure(&self, rule: &str) {
+        self.inner.record_validation_failure(rule);
+    }
+
+    fn record_repository_stats(&self, stats: &RepositoryStats) {
+        self.inner.record_repository_stats(stats);
+    }
+
+    fn record_webhook_delivery(&self, outcome: &str, elapsed: Duration) {
+        self.inner.record_webhook_delivery(outcome, elapsed);
+    }
+
+    fn record_alert(&self, status: &AlertStatus) {
+        self.inner.record_alert(status);
+    }
+
+    fn record_schema_drift(&self, event_type: &str, change: &str) {
+        self.inner.record_schema_drift(event_type, change);
+    }
+
+    /// Pushes the current metrics once more.
+    async fn flush(&self) {
+        // ---
+        push_once(self.inner.as_ref(), &self.pusher).await;
+    }
+
+    fn health(&self) -> ComponentHealth {
+        // ---
+        let detail = match self.pusher.last_error() {
+            None => format!("pushing to {}", self.pusher.url()),
+            Some(err) => format!(
+                "pushing to {}; last push failed: {}",
+                self.pusher.url(),
+                err
+            ),
+        };
+        ComponentHealth::healthy("metrics", "push").with_detail(detail)
+    }
+}
@@ -0,0 +1,118 @@
+//! HTTP delivery of rendered metrics with retry and exponential backoff.
+//!
+//! Connection errors, timeouts, `429` and `5xx` responses are retried up
+//! to `max_retries` times, doubling the delay each time up to the
+//! configured maximum. Other `4xx` responses mean the request itself is
+//! wrong and fail immediately.
+
+use anyhow::{anyhow, Result};
+use reqwest::{header, Client, Method, StatusCode};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::config::PushSettings;
+use crate::infrastructure::exponential_backoff;
+
+/// Exposition format of the pushed body.
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Sends metrics to one endpoint.
+pub struct Pusher {
+    client: Client,
+    url: String,
+    method: Method,
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    last_error: Mutex<Option<String>>,
+}
+
+/// Failure of a single attempt.
+struct AttemptError {
+    error: anyhow::Error,
+    retryable: bool,
+}
+
+impl Pusher {
+    // ---
+    pub fn new(settings: &PushSettings) -> Result<Self> {
+        // ---
+        let client = Client::builder()
+            .timeout(Duration::from_millis(settings.timeout_ms))
+            .build()?;
+        Ok(Self {
+            client,
+            url: settings.url.clone(),
+            method: Method::from_bytes(settings.method.as_bytes())?,
+            max_retries: settings.max_retries,
+            initial_backoff: Duration::from_millis(settings.initial_backoff_ms),
+            max_backoff: Duration::from_millis(settings.max_backoff_ms),
+            last_error: Mutex::new(None),
+        })
+    }
+
+    /// Target URL.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Error of the most recent push, if it failed.
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Pushes `body`, retrying transient failures.
+    pub async fn push(&self, body: String) -> Result<()> {
+        // ---
+        let mut attempt = 0;
+        let result = loop {
+            match self.attempt(body.clone()).await {
+                Ok(()) => break Ok(()),
+                Err(failure) if failure.retryable && attempt < self.max_retries => {
+                    let delay =
+                        exponential_backoff(self.initial_backoff, self.max_backoff, attempt);
+                    tracing::debug!(
+                        attempt,
+                        ?delay,
+                        error = %failure.error,
+                        "Metrics push failed, retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(failure) => break Err(failure.error),
+            }
+        };
+
+        *self.last_error.lock().unwrap_or_else(|e| e.into_inner()) =
+            result.as_ref().err().map(|err| format!("{:#}", err));
+        result
+    }
+
+    async fn attempt(&self, body: String) -> Result<(), AttemptError> {
+        // ---
+        let response = self
+            .client
+            .request(self.method.clone(), &self.url)
+            .header(header::CONTENT_TYPE, CONTENT_TYPE)
+            .body(body)
+            .send()
+            .await
+            .map_err(|err| AttemptError {
+                error: err.into(),
+                retryable: true,
+            })?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        Err(AttemptError {
+            error: anyhow!("{} responded {}", self.url, status),
+            retryable: status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS,
+        })
+    }
+}
@@ -0,0 +1,157 @@
+mod sink;
+mod statsd_metrics;
+
+pub use statsd_metrics::StatsdMetrics;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::MetricsSettings;
+use sink::{spawn_flusher, UdpSink};
+
+/// Creates a StatsD metrics implementation pushing to `settings.statsd`.
+///
+/// Metrics are sent over UDP, batched and flushed on an interval; nothing
+/// is exposed for scraping.
+///
+/// Returns a fully initialized metrics instance ready for use.
+pub fn create(settings: &MetricsSettings) -> anyhow::Result<crate::domain::MetricsPtr> {
+    // ---
+    Ok(Arc::new(create_statsd(settings)?))
+}
+
+fn create_statsd(settings: &MetricsSettings) -> anyhow::Result<StatsdMetrics> {
+    // ---
+    let statsd = &settings.statsd;
+    let sink = Arc::new(UdpSink::connect(&statsd.address, statsd.max_packet_bytes)?);
+    spawn_flusher(&sink, Duration::from_millis(statsd.flush_interval_ms));
+    Ok(StatsdMetrics::new(
+        sink,
+        statsd,
+        settings.event_type_label_limit,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StatsdSettings;
+    use crate::domain::Metrics;
+    use std::net::UdpSocket;
+    use std::time::Instant;
+
+    /// A local agent, and metrics configured to push to it.
+    fn agent_and_metrics(statsd: StatsdSettings) -> anyhow::Result<(UdpSocket, StatsdMetrics)> {
+        let agent = UdpSocket::bind("127.0.0.1:0")?;
+        agent.set_read_timeout(Some(Duration::from_secs(2)))?;
+        let settings = MetricsSettings {
+            kind: "statsd".to_string(),
+            event_type_label_limit: 1,
+            statsd: StatsdSettings {
+                address: agent.local_addr()?.to_string(),
+                // Flushed explicitly by the tests
+                flush_interval_ms: 60_000,
+                ..statsd
+            },
+            ..MetricsSettings::default()
+        };
+        Ok((agent, create_statsd(&settings)?))
+    }
+
+    fn receive(agent: &UdpSocket) -> anyhow::Result<String> {
+        let mut buf = [0u8; 65_536];
+        let len = agent.recv(&mut buf)?;
+        Ok(String::from_utf8(buf[..len].to_vec())?)
+    }
+
+    #[tokio::test]
+    async fn emits_dogstatsd_lines_with_prefix_and_tags() -> anyhow::Result<()> {
+        let (agent, metrics) = agent_and_metrics(StatsdSettings {
+            tags: [("env".to_string(), "test".to_string())].into(),
+            ..StatsdSettings::default()
+        })?;
+
+        metrics.record_event_created("signup");
+        metrics.record_event_created("purchase");
+        metrics.record_validation_failure("event_type_charset");
+        metrics.record_http_in_flight("/v1/events", -1);
+        metrics.record_http_request(Instant::now(), "/v1/events", "POST", 201);
+        metrics.flush().await;
+
+        let packet = receive(&agent)?;
+        let lines: Vec<&str> = packet.lines().collect();
+        assert_eq!(
+            lines[0],
+            "argus_events.events.created:1|c|#env:test,event_type:signup"
+        );
+        assert_eq!(
+            lines[1],
+            "argus_events.events.created:1|c|#env:test,event_type:__other__"
+        );
+        assert_eq!(
+            lines[2],
+            "argus_events.events.validation_failures:1|c|#env:test,rule:event_type_charset"
+        );
+        assert_eq!(
+            lines[3],
+            "argus_events.http.requests.in_flight:-1|g|#env:test,route:/v1/events"
+        );
+        assert_eq!(
+            lines[4],
+            "argus_events.http.requests:1|c|#env:test,route:/v1/events,method:POST,status:201"
+        );
+        assert!(
+            lines[5].starts_with("argus_events.http.request.duration:")
+                && lines[5].ends_with("|ms|#env:test,route:/v1/events,method:POST,status:201"),
+            "{}",
+            lines[5]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn plain_statsd_dialect_drops_tags() -> anyhow::Result<()> {
+        let (agent, metrics) = agent_and_metrics(StatsdSettings {
+            prefix: String::new(),
+            dialect: "statsd".to_string(),
+            tags: [("env".to_string(), "test".to_string())].into(),
+            ..StatsdSettings::default()
+        })?;
+
+        metrics.record_query_result_size(3);
+        metrics.flush().await;
+
+        assert_eq!(receive(&agent)?, "repository.query.result_size:3|h");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn packets_never_exceed_the_size_limit() -> anyhow::Result<()> {
+        let (agent, metrics) = agent_and_metrics(StatsdSettings {
+            max_packet_bytes: 100,
+            ..StatsdSettings::default()
+        })?;
+
+        for _ in 0..10 {
+            metrics.record_validation_failure("payload_too_large");
+        }
+        metrics.flush().await;
+
+        let mut lines = 0;
+        while lines < 10 {
+            let packet = receive(&agent)?;
+            assert!(packet.len() <= 100, "Packet of {} bytes", packet.len());
+            lines += packet.lines().count();
+        }
+        assert_eq!(lines, 10);
+        Ok(())
+    }
+
+    #[test]
+    fn render_explains_push_mode() -> anyhow::Result<()> {
+        let (_agent, metrics) = agent_and_metrics(StatsdSettings::default())?;
+        let text = metrics.render()?;
+        assert!(text.contains("pushed to StatsD"), "{}", text);
+        assert!(metrics.health().healthy);
+        Ok(())
+    }
+}
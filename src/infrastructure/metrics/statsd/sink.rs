@@ -0,0 +1,120 @@
+//! Batched UDP transport for StatsD lines.
+//!
+//! Lines are appended to a buffer and sent as newline-separated packets no
+//! larger than the configured size: when the next line would not fit, and
+//! otherwise on every flush. A background thread flushes on an interval and
+//! exits once the sink is dropped, so no async runtime is required.
+
+use anyhow::{Context, Result};
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Buffered, connected UDP socket.
+pub struct UdpSink {
+    socket: UdpSocket,
+    max_packet_bytes: usize,
+    buffer: Mutex<String>,
+    packets_sent: AtomicU64,
+    send_errors: AtomicU64,
+}
+
+impl UdpSink {
+    // ---
+
+    /// Connects to `address`, resolved once; fails if it does not resolve.
+    pub fn connect(address: &str, max_packet_bytes: usize) -> Result<Self> {
+        // ---
+        let target = address
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut addrs| addrs.next())
+            .with_context(|| format!("Failed to resolve StatsD address '{}'", address))?;
+        let local = if target.is_ipv6() {
+            "[::]:0"
+        } else {
+            "0.0.0.0:0"
+        };
+        let socket = UdpSocket::bind(local).context("Failed to bind StatsD socket")?;
+        socket
+            .connect(target)
+            .with_context(|| format!("Failed to connect StatsD socket to {}", target))?;
+        Ok(Self {
+            socket,
+            max_packet_bytes,
+            buffer: Mutex::new(String::with_capacity(max_packet_bytes)),
+            packets_sent: AtomicU64::new(0),
+            send_errors: AtomicU64::new(0),
+        })
+    }
+
+    /// Queues one line, sending the buffer first if the line would not fit.
+    pub fn push(&self, line: &str) {
+        // ---
+        let mut buffer = self.buffer.lock().unwrap_or_else(|e| e.into_inner());
+        if !buffer.is_empty() && buffer.len() + 1 + line.len() > self.max_packet_bytes {
+            self.send(&buffer);
+            buffer.clear();
+        }
+        if !buffer.is_empty() {
+            buffer.push('\n');
+        }
+        buffer.push_str(line);
+    }
+
+    /// Sends everything buffered.
+    pub fn flush(&self) {
+        // ---
+        let mut buffer = self.buffer.lock().unwrap_or_else(|e| e.into_inner());
+        if !buffer.is_empty() {
+            self.send(&buffer);
+            buffer.clear();
+        }
+    }
+
+    /// Number of packets sent and failed sends so far.
+    pub fn counts(&self) -> (u64, u64) {
+        // ---
+        (
+            self.packets_sent.load(Ordering::Relaxed),
+            self.send_errors.load(Ordering::Relaxed),
+        )
+    }
+
+    fn send(&self, payload: &str) {
+        // ---
+        match self.socket.send(payload.as_bytes()) {
+            Ok(_) => {
+                self.packets_sent.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(err) => {
+                // Logged at debug only: a missing agent must not flood the logs
+                self.send_errors.fetch_add(1, Ordering::Relaxed);
+                tracing::debug!(%err, "Failed to send StatsD packet");
+            }
+        }
+    }
+}
+
+impl Drop for UdpSink {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+/// Flushes `sink` every `interval` until it is dropped.
+pub fn spawn_flusher(sink: &Arc<UdpSink>, interval: Duration) {
+    // ---
+    let sink = Arc::downgrade(sink);
+    std::thread::Builder::new()
+        .name("statsd-flush".to_string())
+        .spawn(move || loop {
+            std::thread::sleep(interval);
+            match sink.upgrade() {
+                Some(sink) => sink.flush(),
+                None => break,
+            }
+        })
+        .expect("failed to spawn StatsD flush thread");
+}
@@ -0,0 +1,245 @@
+//! StatsD metrics implementation.
+//!
+//! Formats each recording as a StatsD line — counters (`c`), timings in
+//! milliseconds (`ms`), histograms (`h`) and gauges (`g`) — and hands
+//! it to the UDP sink. Labels become DogStatsD tags; with the plain
+//! `statsd` dialect they are dropped. Nothing is aggregated locally, so
+//! `render()` has nothing to show.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::sink::UdpSink;
+use crate::config::StatsdSettings;
+use crate::domain::{AlertState, AlertStatus, ComponentHealth, Metrics, RepositoryStats};
+use crate::infrastructure::metrics::{GaugeLabels, LabelLimiter};
+
+/// StatsD/DogStatsD push-based metrics implementation.
+pub struct StatsdMetrics {
+    sink: Arc<UdpSink>,
+    address: String,
+    prefix: String,
+    /// Constant tags, preformatted as `key:value,key:value`.
+    constant_tags: String,
+    tagged: bool,
+    event_types: LabelLimiter,
+    stored_types: GaugeLabels,
+}
+
+impl StatsdMetrics {
+    // ---
+    pub fn new(
+        sink: Arc<UdpSink>,
+        settings: &StatsdSettings,
+        event_type_label_limit: usize,
+    ) -> Self {
+        tracing::info!(address = %settings.address, "Creating StatsD metrics");
+        let constant_tags = settings
+            .tags
+            .iter()
+            .map(|(key, value)| format!("{}:{}", sanitize(key), sanitize(value)))
+            .collect::<Vec<_>>()
+            .join(",");
+        StatsdMetrics {
+            sink,
+            address: settings.address.clone(),
+            prefix: if settings.prefix.is_empty() {
+                String::new()
+            } else {
+                format!("{}.", settings.prefix)
+            },
+            constant_tags,
+            tagged: settings.dialect == "dogstatsd",
+            event_types: LabelLimiter::new(event_type_label_limit),
+            stored_types: GaugeLabels::default(),
+        }
+    }
+
+    fn emit(&self, name: &str, value: &str, kind: &str, tags: &[(&str, &str)]) {
+        // ---
+        let mut line = format!("{}{}:{}|{}", self.prefix, name, value, kind);
+        if self.tagged && (!tags.is_empty() || !self.constant_tags.is_empty()) {
+            line.push_str("|#");
+            line.push_str(&self.constant_tags);
+            for (i, (key, value)) in tags.iter().enumerate() {
+                if i > 0 || !self.constant_tags.is_empty() {
+                    line.push(',');
+                }
+                line.push_str(key);
+                line.push(':');
+                line.push_str(&sanitize(value));
+            }
+        }
+        self.sink.push(&line);
+    }
+}
+
+/// Replaces characters that would break the line or tag syntax.
+fn sanitize(value: &str) -> String {
+    // ---
+    value
+        .chars()
+        .map(|c| match c {
+            '|' | ',' | '#' | '\n' | '\r' => '_',
+            c => c,
+        })
+        .collect()
+}
+
+fn millis(elapsed: Duration) -> String {
+    // ---
+    format!("{:.3}", elapsed.as_secs_f64() * 1_000.0)
+}
+
+#[async_trait]
+impl Metrics for StatsdMetrics {
+    // ---
+
+    fn render(&self) -> Result<String> {
+        // ---
+        Ok(format!(
+            "# Metrics are pushed to StatsD at {}; there is nothing to scrape here.\n\
+             # Set metrics.kind = \"prom\" to serve Prometheus metrics on this endpoint.\n",
+            self.address
+        ))
+    }
+
+    fn record_event_created(&self, event_type: &str) {
+        // ---
+        let label = self.event_types.label(event_type);
+        self.emit("events.created", "1", "c", &[("event_type", label)]);
+    }
+
+    fn record_http_request(&self, start: Instant, path: &str, method: &str, status: u16) {
+        // ---
+        let status = status.to_string();
+        let tags = [("route", path), ("method", method), ("status", &status)];
+        self.emit("http.requests", "1", "c", &tags);
+        self.emit(
+            "http.request.duration",
+            &millis(start.elapsed()),
+            "ms",
+            &tags,
+        );
+    }
+
+    fn record_http_in_flight(&self, path: &str, delta: i64) {
+        // ---
+        self.emit(
+            "http.requests.in_flight",
+            &format!("{:+}", delta),
+            "g",
+            &[("route", path)],
+        );
+    }
+
+    fn record_http_body_sizes(
+        &self,
+        path: &str,
+        method: &str,
+        request_bytes: Option<u64>,
+        response_bytes: Option<u64>,
+    ) {
+        // ---
+        let tags = [("route", path), ("method", method)];
+        if let Some(bytes) = request_bytes {
+            self.emit("http.request.size", &bytes.to_string(), "h", &tags);
+        }
+        if let Some(bytes) = response_bytes {
+            self.emit("http.response.size", &bytes.to_string(), "h", &tags);
+        }
+    }
+
+    fn record_repository_operation(&self, operation: &str, elapsed: Duration, success: bool) {
+        // ---
+        let outcome = if success { "success" } else { "error" };
+        self.emit(
+            "repository.operation.duration",
+            &millis(elapsed),
+            "ms",
+            &[("operation", operation), ("outcome", outcome)],
+        );
+    }
+
+    fn record_query_result_size(&self, count: usize) {
+        // ---
+        self.emit("repository.query.result_size", &count.to_string(), "h", &[]);
+    }
+
+    fn record_validation_failure(&self, rule: &str) {
+        // ---
+        self.emit("events.validation_failures", "1", "c", &[("rule", rule)]);
+    }
+
+    fn record_repository_stats(&self, stats: &RepositoryStats) {
+        // ---
+        let by_type = self.stored_types.snapshot(
+            &self.event_types,
+            stats
+                .event_types
+                .iter()
+                .map(|(event_type, s)| (event_type.as_str(), s.count)),
+        );
+        for (event_type, count) in &by_type {
+            self.emit(
+                "repository.events",
+                &count.to_string(),
+                "g",
+                &[("event_type", event_type)],
+            );
+        }
+        let seconds = |t: Option<chrono::DateTime<chrono::Utc>>| t.map_or(0, |t| t.timestamp());
+        let gauges = [
+            ("repository.event_types", stats.distinct_event_types as i64),
+            ("repository.size_bytes", stats.approx_bytes as i64),
+            ("repository.oldest_event_timestamp", seconds(stats.oldest)),
+            ("repository.newest_event_timestamp", seconds(stats.newest)),
+        ];
+        for (name, value) in gauges {
+            self.emit(name, &value.to_string(), "g", &[]);
+        }
+    }
+
+    fn record_webhook_delivery(&self, outcome: &str, elapsed: Duration) {
+        // ---
+        let tags = [("outcome", outcome)];
+        self.emit("webhooks.deliveries", "1", "c", &tags);
+        self.emit("webhooks.delivery.duration", &millis(elapsed), "ms", &tags);
+    }
+
+    fn record_alert(&self, status: &AlertStatus) {
+        // ---
+        let tags = [("rule", status.rule.as_str())];
+        let firing = u8::from(status.state == AlertState::Firing);
+        self.emit("alerts.firing", &firing.to_string(), "g", &tags);
+        self.emit("alerts.value", &status.value.to_string(), "g", &tags);
+    }
+
+    fn record_schema_drift(&self, event_type: &str, change: &str) {
+        // ---
+        let label = self.event_types.label(event_type);
+        self.emit(
+            "schemas.drift",
+            "1",
+            "c",
+            &[("event_type", label), ("change", change)],
+        );
+    }
+
+    async fn flush(&self) {
+        // ---
+        self.sink.flush();
+    }
+
+    fn health(&self) -> ComponentHealth {
+        // ---
+        let (sent, errors) = self.sink.counts();
+        ComponentHealth::healthy("metrics", "statsd").with_detail(format!(
+            "pushing to {} ({} packets sent, {} send errors)",
+            self.address, sent, errors
+        ))
+    }
+}
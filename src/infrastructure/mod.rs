@@ -1,25 +1,47 @@
+mod backoff;
 mod metrics;
+mod telemetry;
+mod tls;
 
 // Re-export the factory functions for easy access
 use anyhow::{anyhow, Result};
-use metrics::{noop::create as create_noop_metrics, prometheus::create as create_prom_metrics};
+use metrics::{
+    noop::create as create_noop_metrics, prometheus::create as create_prom_metrics,
+    push::create as create_push_metrics, statsd::create as create_statsd_metrics,
+};
 use std::env;
 
+use crate::config::MetricsSettings;
 use crate::domain::MetricsPtr;
 
+pub(crate) use backoff::exponential_backoff;
+pub use telemetry::{create_telemetry, Telemetry};
+pub use tls::TlsServer;
+
 pub fn create_metrics() -> Result<MetricsPtr> {
     // --
     // Determine metrics implementation from environment
     let metrics_type = env::var("ARGUS_METRICS_TYPE").unwrap_or_else(|_| "noop".to_string());
+    create_metrics_for(&metrics_type)
+}
+
+/// Factory function to create metrics instances based on type string
+pub fn create_metrics_for(kind: &str) -> Result<MetricsPtr> {
+    // ---
+    create_metrics_with(&MetricsSettings {
+        kind: kind.to_string(),
+        ..MetricsSettings::default()
+    })
+}
 
-    if metrics_type == "prom" {
-        create_prom_metrics()
-    } else if metrics_type == "noop" {
-        create_noop_metrics()
-    } else {
-        Err(anyhow!(
-            "Invalid value for ARGUS_METRICS_TYPE: {}",
-            metrics_type
-        ))
+/// Factory function to create metrics instances from `[metrics]` settings
+pub fn create_metrics_with(settings: &MetricsSettings) -> Result<MetricsPtr> {
+    // ---
+    match settings.kind.as_str() {
+        "prom" => create_prom_metrics(settings),
+        "statsd" => create_statsd_metrics(settings),
+        "push" => create_push_metrics(settings),
+        "noop" => create_noop_metrics(),
+        other => Err(anyhow!("Unknown metrics type: '{}'", other)),
     }
 }
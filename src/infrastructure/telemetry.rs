@@ -0,0 +1,101 @@
+//! OpenTelemetry trace export over OTLP.
+//!
+//! Spans recorded with `tracing` by this crate are turned into OpenTelemetry
+//! spans by a `tracing-opentelemetry` layer and sent to a collector in
+//! batches from a Tokio task, so request handling never waits on the
+//! collector. Spans from dependencies (hyper, the exporter's own HTTP
+//! client) are filtered out.
+
+use anyhow::{anyhow, Result};
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::{Protocol, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::runtime;
+use opentelemetry_sdk::trace::span_processor_with_async_runtime::BatchSpanProcessor;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing::{Level, Subscriber};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::config::TelemetrySettings;
+
+/// Target prefix of the spans that are exported.
+const EXPORTED_TARGET: &str = "argus_events";
+
+/// A running OTLP trace pipeline.
+#[derive(Clone)]
+pub struct Telemetry {
+    provider: SdkTracerProvider,
+}
+
+impl Telemetry {
+    // ---
+
+    /// Layer exporting this crate's `info` and higher spans through the pipeline.
+    pub fn layer<S>(&self) -> impl Layer<S> + Send + Sync
+    where
+        S: Subscriber + for<'span> LookupSpan<'span> + Send + Sync,
+    {
+        // ---
+        tracing_opentelemetry::layer()
+            .with_tracer(self.provider.tracer(EXPORTED_TARGET))
+            .with_filter(Targets::new().with_target(EXPORTED_TARGET, Level::INFO))
+    }
+
+    /// Exports every span finished so far.
+    pub async fn force_flush(&self) -> Result<()> {
+        // ---
+        let provider = self.provider.clone();
+        tokio::task::spawn_blocking(move || provider.force_flush()).await??;
+        Ok(())
+    }
+
+    /// Exports remaining spans and stops the pipeline.
+    pub async fn shutdown(self) -> Result<()> {
+        // ---
+        let provider = self.provider;
+        tokio::task::spawn_blocking(move || provider.shutdown()).await??;
+        Ok(())
+    }
+}
+
+/// Builds the OTLP pipeline described by `[telemetry]`, or `None` when no
+/// collector endpoint is configured. Must be called within a Tokio runtime.
+pub fn create_telemetry(settings: &TelemetrySettings) -> Result<Option<Telemetry>> {
+    // ---
+    let Some(endpoint) = &settings.otlp_endpoint else {
+        return Ok(None);
+    };
+    let base = endpoint.trim_end_matches('/');
+    let traces_url = format!("{}/v1/traces", base);
+
+    let exporter = match settings.otlp_protocol.as_str() {
+        "grpc" => SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(base)
+            .build()?,
+        "http/protobuf" => SpanExporter::builder()
+            .with_http()
+            .with_protocol(Protocol::HttpBinary)
+            .with_endpoint(traces_url)
+            .build()?,
+        "http/json" => SpanExporter::builder()
+            .with_http()
+            .with_protocol(Protocol::HttpJson)
+            .with_endpoint(traces_url)
+            .build()?,
+        other => return Err(anyhow!("Unknown OTLP protocol: '{}'", other)),
+    };
+
+    let provider = SdkTracerProvider::builder()
+        .with_span_processor(BatchSpanProcessor::builder(exporter, runtime::Tokio).build())
+        .with_resource(
+            Resource::builder()
+                .with_service_name(settings.service_name.clone())
+                .build(),
+        )
+        .build();
+
+    Ok(Some(Telemetry { provider }))
+}
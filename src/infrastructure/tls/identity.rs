@@ -0,0 +1,30 @@
+//! Maps verified client certificates to `ClientIdentity` values.
+
+use rustls::pki_types::CertificateDer;
+use std::collections::{BTreeSet, HashMap};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+use crate::domain::{ClientIdentity, Role};
+
+/// Subject common name → roles, as configured in `[tls.identities]`.
+pub(super) type IdentityMap = HashMap<String, BTreeSet<Role>>;
+
+/// Derives the identity for a verified end-entity certificate.
+///
+/// Returns `None` when the certificate has no readable common name.
+pub(super) fn identify(
+    cert: &CertificateDer<'_>,
+    identities: &IdentityMap,
+) -> Option<ClientIdentity> {
+    // ---
+    let (_, parsed) = X509Certificate::from_der(cert.as_ref()).ok()?;
+    let subject = parsed
+        .subject()
+        .iter_common_name()
+        .next()
+        .and_then(|cn| cn.as_str().ok())?
+        .to_string();
+    let roles = identities.get(&subject).cloned().unwrap_or_default();
+
+    Some(ClientIdentity { subject, roles })
+}
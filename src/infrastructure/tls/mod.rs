@@ -0,0 +1,13 @@
+//! Native TLS and mutual-TLS termination.
+//!
+//! `TlsServer` serves an axum router over rustls, reloads certificates when
+//! their files change, and attaches a `ClientIdentity` derived from a
+//! verified client certificate to every request on that connection, or an
+//! `AnonymousClient` marker when identities are configured but the client
+//! presented no usable certificate.
+
+mod identity;
+mod loader;
+mod server;
+
+pub use server::TlsServer;
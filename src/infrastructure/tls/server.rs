@@ -0,0 +1,169 @@
+//! HTTPS accept loop with certificate hot reload.
+
+use anyhow::Result;
+use arc_swap::ArcSwap;
+use axum::extract::Request;
+use axum::Router;
+use hyper::body::Incoming;
+use hyper_util::rt::TokioIo;
+use hyper_util::server::graceful::GracefulShutdown;
+use rustls::ServerConfig;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tokio_rustls::TlsAcceptor;
+use tower::ServiceExt;
+
+use super::identity::{identify, IdentityMap};
+use super::loader::load_server_config;
+use crate::config::TlsSettings;
+use crate::domain::AnonymousClient;
+
+/// How often certificate files are checked for changes.
+const RELOAD_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// TLS terminator for the API router.
+#[derive(Clone)]
+pub struct TlsServer {
+    settings: TlsSettings,
+    config: Arc<ArcSwap<ServerConfig>>,
+    identities: Arc<IdentityMap>,
+}
+
+impl TlsServer {
+    // ---
+
+    /// Loads certificates from the paths in `settings`, failing on any invalid file.
+    pub fn new(settings: &TlsSettings) -> Result<Self> {
+        // ---
+        let config = load_server_config(settings)?;
+        Ok(Self {
+            settings: settings.clone(),
+            config: Arc::new(ArcSwap::new(config)),
+            identities: Arc::new(settings.identities.clone()),
+        })
+    }
+
+    /// Re-reads certificate files; new handshakes use them, existing connections are untouched.
+    ///
+    /// On error the previous certificates stay in use.
+    pub fn reload(&self) -> Result<()> {
+        // ---
+        let config = load_server_config(&self.settings)?;
+        self.config.store(config);
+        Ok(())
+    }
+
+    /// Spawns a task that reloads certificates when any of their files change.
+    pub fn spawn_reloader(&self) -> JoinHandle<()> {
+        // ---
+        let server = self.clone();
+        tokio::spawn(async move {
+            let mut last_modified = server.files_modified();
+            let mut poll = tokio::time::interval(RELOAD_POLL_INTERVAL);
+            loop {
+                poll.tick().await;
+                let now = server.files_modified();
+                if now == last_modified {
+                    continue;
+                }
+                last_modified = now;
+                match server.reload() {
+                    Ok(()) => tracing::info!("🔐 TLS certificates reloaded"),
+                    Err(err) => {
+                        tracing::error!(
+                            "TLS reload failed, keeping current certificates: {:#}",
+                            err
+                        )
+                    }
+                }
+            }
+        })
+    }
+
+    /// Serves `app` over TLS until `shutdown` resolves, then waits for open connections to finish.
+    pub async fn serve(
+        self,
+        listener: TcpListener,
+        app: Router,
+        shutdown: impl Future<Output = ()> + Send + 'static,
+    ) -> Result<()> {
+        // ---
+        let graceful = GracefulShutdown::new();
+        tokio::pin!(shutdown);
+
+        loop {
+            let (stream, peer) = tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok(accepted) => accepted,
+                    Err(err) => {
+                        tracing::warn!(?err, "Failed to accept connection");
+                        continue;
+                    }
+                },
+                _ = &mut shutdown => break,
+            };
+
+            let acceptor = TlsAcceptor::from(self.config.load_full());
+            let identities = self.identities.clone();
+            let app = app.clone();
+            let watcher = graceful.watcher();
+
+            tokio::spawn(async move {
+                let stream = match acceptor.accept(stream).await {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        tracing::debug!(%peer, %err, "TLS handshake failed");
+                        return;
+                    }
+                };
+
+                let identity = stream
+                    .get_ref()
+                    .1
+                    .peer_certificates()
+                    .and_then(|chain| chain.first())
+                    .and_then(|cert| identify(cert, &identities));
+
+                // With identities configured, requests lacking one must not pass role checks
+                let anonymous = identity.is_none() && !identities.is_empty();
+
+                let service = hyper::service::service_fn(move |mut request: Request<Incoming>| {
+                    if let Some(identity) = &identity {
+                        request.extensions_mut().insert(identity.clone());
+                    }
+                    if anonymous {
+                        request.extensions_mut().insert(AnonymousClient);
+                    }
+                    app.clone().oneshot(request)
+                });
+
+                let connection = hyper::server::conn::http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service);
+                if let Err(err) = watcher.watch(connection).await {
+                    tracing::debug!(%peer, %err, "Connection closed with error");
+                }
+            });
+        }
+
+        drop(listener);
+        graceful.shutdown().await;
+        Ok(())
+    }
+
+    fn files_modified(&self) -> Vec<Option<SystemTime>> {
+        // ---
+        [
+            &self.settings.cert_path,
+            &self.settings.key_path,
+            &self.settings.client_ca_path,
+        ]
+        .into_iter()
+        .flatten()
+        .map(|path: &PathBuf| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+        .collect()
+    }
+}
@@ -5,29 +5,107 @@
 //! is hidden behind this gateway.
 
 // Bring submodules into scope
+mod alerts;
 mod api;
 mod cli;
+mod config;
 mod domain;
 mod infrastructure;
 mod repository;
+mod rollups;
+mod schemas;
+mod webhooks;
 
 // Public exports (visible outside this crate)
-pub use api::event_routes;
+pub use alerts::{create_alerts, Alerts};
+pub use api::{
+    event_routes, event_routes_with_config, ApiDoc, AppConfig, DeprecationPolicy, Lifecycle,
+    LiveConfig, LogFilter, API_ROUTES,
+};
 pub use cli::Args;
+pub use config::{
+    apply_reload, spawn_config_watcher, AlertRuleSettings, AlertSettings, MetricsSettings,
+    PushSettings, ReloadReport, ReloadTargets, RollupDefinitionSettings, RollupSettings,
+    SchemaSettings, Settings, StatsdSettings, TelemetrySettings, TlsSettings, WebhookSettings,
+};
 pub use domain::{
     // ------------
     create_repository,
+    AlertCondition,
+    AlertRule,
+    AlertState,
+    AlertStatus,
+    ClientIdentity,
+    Cohort,
+    CohortPeriod,
+    CohortQuery,
+    CohortReport,
+    ComponentHealth,
+    DeadLetter,
     Event,
+    EventFilter,
+    EventObserver,
+    EventObserverPtr,
     EventQuery,
     EventRepository,
     EventRepositoryPtr,
+    EventTypeStats,
+    EventTypeSummary,
+    FieldSchema,
+    FieldStats,
+    FieldValueQuery,
+    FieldValueReport,
+    FieldViolation,
+    FunnelQuery,
+    FunnelReport,
+    FunnelStep,
+    JsonType,
     Metrics,
     MetricsPtr,
+    NonNumericField,
+    NumericBucket,
+    NumericStats,
+    NumericStatsQuery,
+    NumericStatsReport,
+    PayloadField,
+    PayloadSchema,
+    RepositoryStats,
+    RetentionPolicy,
+    Role,
+    RollupBucket,
+    RollupDefinition,
+    RollupReport,
+    SchemaChange,
+    SchemaDrift,
+    Session,
+    SessionQuery,
+    SessionReport,
+    SessionSummary,
+    Subscription,
+    TraceContext,
+    ValidationRules,
+    ValueCount,
+};
+pub use infrastructure::{
+    create_metrics, create_metrics_for, create_metrics_with, create_telemetry, Telemetry, TlsServer,
 };
-pub use infrastructure::create_metrics;
+pub use repository::{spawn_retention_task, spawn_stats_task, RetentionPolicyHandle};
+pub use rollups::{create_rollups, Rollups};
+pub use schemas::{create_schemas, Schemas};
+pub use webhooks::{create_webhooks, sign_webhook, Webhooks};
 
 // Helper function for creating the complete app (useful for testing)
 pub fn create_app(repo: EventRepositoryPtr, metrics: MetricsPtr) -> anyhow::Result<axum::Router> {
     // ---
     Ok(event_routes(repo, metrics))
 }
+
+/// Like [`create_app`], but with an explicit API configuration.
+pub fn create_app_with_config(
+    repo: EventRepositoryPtr,
+    metrics: MetricsPtr,
+    config: AppConfig,
+) -> anyhow::Result<axum::Router> {
+    // ---
+    Ok(event_routes_with_config(repo, metrics, config))
+}
@@ -1,47 +1,221 @@
 //! Application entry point for the Argus Events server.
-use argus_events::{create_metrics, create_repository};
-use argus_events::{event_routes, Args};
+use arc_swap::ArcSwap;
+use argus_events::{
+    create_alerts, create_metrics_with, create_repository, create_rollups, create_schemas,
+    create_telemetry, create_webhooks, spawn_config_watcher, spawn_retention_task,
+    spawn_stats_task,
+};
+use argus_events::{event_routes_with_config, AppConfig, Args, DeprecationPolicy, Lifecycle};
+use argus_events::{LiveConfig, LogFilter, ReloadTargets, TlsServer};
 use clap::Parser;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::signal;
-use tracing_subscriber::EnvFilter;
+use tokio::sync::oneshot;
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Layer};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Parse CLI args
+    // Parse CLI args and resolve settings (defaults < file < env < flags)
     let args = Args::parse();
+    let settings = args.resolve_settings()?;
 
-    // Init logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
+    // Init logging behind a reload handle so the level can change at runtime.
+    // The log filter applies to log output only; exported spans have their own.
+    let (filter, log_handle) = reload::Layer::new(EnvFilter::try_new(&settings.logging.level)?);
+    let log_output = match settings.logging.format.as_str() {
+        "json" => tracing_subscriber::fmt::layer().json().boxed(),
+        _ => tracing_subscriber::fmt::layer().boxed(),
+    };
+    let telemetry = create_telemetry(&settings.telemetry)?;
+    tracing_subscriber::registry()
+        .with(log_output.with_filter(filter))
+        .with(telemetry.as_ref().map(|telemetry| telemetry.layer()))
         .init();
+    if let Some(endpoint) = &settings.telemetry.otlp_endpoint {
+        tracing::info!(
+            endpoint,
+            protocol = %settings.telemetry.otlp_protocol,
+            "📡 Exporting traces over OTLP"
+        );
+    }
 
     // Shared repository
-    let repo = create_repository(&args.repository)
+    let repo = create_repository(&settings.repository.kind)
         .map_err(|e| anyhow::anyhow!("Failed to create repository: {}", e))?;
 
+    // Retention sweeps read the policy on every pass
+    let retention = Arc::new(ArcSwap::from_pointee(settings.retention_policy()));
+    spawn_retention_task(repo.clone(), retention.clone());
+
+    // Log filter changes from config reloads and the admin endpoint
+    let log_filter = LogFilter::new(&settings.logging.level, move |directive| {
+        Ok(log_handle.reload(EnvFilter::try_new(directive)?)?)
+    });
+
     // Route setup
-    let metrics = create_metrics()?;
-    let app = event_routes(repo, metrics);
+    let metrics = create_metrics_with(&settings.metrics)?;
+    if settings.metrics.stats_refresh_secs > 0 {
+        let interval = Duration::from_secs(settings.metrics.stats_refresh_secs);
+        spawn_stats_task(repo.clone(), metrics.clone(), interval);
+    }
+    // Webhook deliveries queued before a restart resume right away
+    let webhooks = create_webhooks(&settings.webhooks, metrics.clone())?;
+    let alerts = create_alerts(settings.alert_rules(), &settings.alerts, metrics.clone())?;
+    // Rollup buckets are restored from disk and outlive raw-event retention
+    let rollups = create_rollups(settings.rollup_definitions(), &settings.rollups)?;
+    let schemas = if settings.schemas.enabled {
+        Some(create_schemas(&settings.schemas, metrics.clone())?)
+    } else {
+        None
+    };
+    let lifecycle = Lifecycle::default();
+    let live = LiveConfig::new(settings.validation_rules(), settings.api_keys());
+    let config = AppConfig {
+        live: live.clone(),
+        openapi_ui: settings.server.api_docs_ui,
+        deprecation: DeprecationPolicy {
+            sunset: settings.server.legacy_sunset,
+            ..DeprecationPolicy::default()
+        },
+        lifecycle: lifecycle.clone(),
+        log_filter: settings.server.admin_api.then(|| log_filter.clone()),
+        webhooks: Some(webhooks.clone()),
+        alerts: Some(alerts),
+        rollups: Some(rollups.clone()),
+        schemas,
+    };
+    let app = event_routes_with_config(repo.clone(), metrics.clone(), config);
+
+    // Hot reload of the safe subset on SIGHUP or config file change
+    if let Some(path) = args.config.clone() {
+        let targets = ReloadTargets {
+            live,
+            retention,
+            log_filter,
+        };
+        let loader_args = args.clone();
+        spawn_config_watcher(
+            path,
+            move || loader_args.resolve_settings(),
+            settings.clone(),
+            targets,
+        );
+    }
+
+    // Native TLS when certificates are configured; they reload when changed on disk
+    let tls = if settings.tls.enabled() {
+        let tls = TlsServer::new(&settings.tls)?;
+        tls.spawn_reloader();
+        Some(tls)
+    } else {
+        None
+    };
 
     // Launch server
-    let listener = tokio::net::TcpListener::bind(&args.endpoint).await?;
-    tracing::info!("🚀 Server running on http://{}", args.endpoint);
+    let endpoint = &settings.server.endpoint;
+    let listener = tokio::net::TcpListener::bind(endpoint).await?;
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    tracing::info!("🚀 Server running on {}://{}", scheme, endpoint);
 
-    // Graceful shutdown using tokio::signal::ctrl_c().
-    // This was listed as complete in docs/ASSIGNMENT.md, but was not implemented until this commit.
+    // Graceful shutdown on SIGINT (Ctrl+C) or SIGTERM (container orchestrators).
     // Cannot be unit tested directly (due to signal handling), but verified manually:
-    // - Server starts
-    // - Ctrl+C triggers log and clean shutdown
-    axum::serve(listener, app)
-        .with_graceful_shutdown(async {
-            signal::ctrl_c()
-                .await
-                .expect("failed to install Ctrl+C handler");
-            tracing::info!("🛑 Received Ctrl+C, shutting down gracefully...");
-        })
-        .await?;
-
-    Ok(())
+    // - Ctrl+C / `kill -TERM` flips /readyz to 503 and stops accepting connections
+    // - In-flight requests complete, bounded by --shutdown-timeout-secs
+    // - The repository shutdown hook runs and a drain summary is logged
+    let (stop_tx, stop_rx) = oneshot::channel::<()>();
+    let stopped = async {
+        let _ = stop_rx.await;
+    };
+    let server = tokio::spawn(async move {
+        match tls {
+            Some(tls) => tls.serve(listener, app, stopped).await,
+            None => Ok(axum::serve(listener, app)
+                .with_graceful_shutdown(stopped)
+                .await?),
+        }
+    });
+
+    let signal_name = shutdown_signal().await;
+    lifecycle.start_draining();
+    let in_flight_at_signal = lifecycle.in_flight();
+    tracing::info!(
+        signal = signal_name,
+        in_flight = in_flight_at_signal,
+        "🛑 Received shutdown signal, draining in-flight requests..."
+    );
+    // Load balancers see the failing readiness probe before the listener closes
+    tokio::time::sleep(Duration::from_secs(settings.server.drain_delay_secs)).await;
+    let _ = stop_tx.send(());
+
+    let deadline = Duration::from_secs(settings.server.shutdown_timeout_secs);
+    // A failed server is reported once every shutdown hook has run
+    let (timed_out, served) = match tokio::time::timeout(deadline, server).await {
+        Ok(Ok(served)) => (false, served),
+        Ok(Err(err)) => (false, Err(err.into())),
+        Err(_) => (true, Ok(())),
+    };
+    if let Err(err) = &served {
+        tracing::error!("Server failed: {:#}", err);
+    }
+
+    if let Err(err) = repo.shutdown().await {
+        tracing::error!(?err, "Repository shutdown failed");
+    }
+    // Webhook queue changes since the last periodic flush
+    match tokio::task::spawn_blocking(move || webhooks.flush()).await {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => tracing::error!("Final webhook flush failed: {:#}", err),
+        Err(err) => tracing::error!(?err, "Final webhook flush task failed"),
+    }
+    // Rollup buckets changed since the last periodic flush
+    match tokio::task::spawn_blocking(move || rollups.flush()).await {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => tracing::error!("Final rollup flush failed: {:#}", err),
+        Err(err) => tracing::error!(?err, "Final rollup flush task failed"),
+    }
+    // Push-based metrics backends send what they still hold
+    metrics.flush().await;
+    if let Some(telemetry) = telemetry {
+        if let Err(err) = telemetry.shutdown().await {
+            tracing::error!(?err, "Trace export shutdown failed");
+        }
+    }
+
+    tracing::info!(
+        signal = signal_name,
+        in_flight_at_signal,
+        drained = lifecycle.completed_while_draining(),
+        abandoned = lifecycle.in_flight(),
+        timed_out,
+        "✅ Shutdown complete"
+    );
+
+    served
+}
+
+/// Waits for SIGINT or (on Unix) SIGTERM and returns the signal's name.
+async fn shutdown_signal() -> &'static str {
+    // ---
+    let ctrl_c = async {
+        signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => "SIGINT",
+        _ = terminate => "SIGTERM",
+    }
 }
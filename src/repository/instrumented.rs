@@ -0,0 +1,219 @@
+//! Repository decorator that records call latency, result sizes and spans.
+//!
+//! Wraps any `EventRepository` so storage backends stay free of metrics
+//! and tracing code; `store_event`, `find_events`, `scan` and the analytics
+//! queries are timed and run in their own span, everything else is forwarded
+//! unchanged. Every method is forwarded, so a backend's own analytics are used.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::Instrument;
+
+use crate::domain::{
+    CohortQuery, CohortReport, ComponentHealth, Event, EventQuery, EventRepository,
+    EventRepositoryPtr, EventTypeSummary, FieldValueQuery, FieldValueReport, FunnelQuery,
+    FunnelReport, MetricsPtr, NumericStatsQuery, NumericStatsReport, RepositoryStats, SessionQuery,
+    SessionReport,
+};
+
+struct InstrumentedRepository {
+    inner: EventRepositoryPtr,
+    metrics: MetricsPtr,
+}
+
+#[async_trait]
+impl EventRepository for InstrumentedRepository {
+    // ---
+    async fn store_event(&self, event: Event) -> Result<()> {
+        // ---
+        let start = Instant::now();
+        let span = tracing::info_span!("repository.store_event", event_type = %event.event_type);
+        let result = self.inner.store_event(event).instrument(span).await;
+        self.metrics
+            .record_repository_operation("store_event", start.elapsed(), result.is_ok());
+        result
+    }
+
+    async fn find_events(&self, query: EventQuery) -> Result<Vec<Event>> {
+        // ---
+        let start = Instant::now();
+        let span = tracing::info_span!("repository.find_events");
+        let result = self.inner.find_events(query).instrument(span).await;
+        self.metrics
+            .record_repository_operation("find_events", start.elapsed(), result.is_ok());
+        if let Ok(events) = &result {
+            self.metrics.record_query_result_size(events.len());
+        }
+        result
+    }
+
+    async fn scan(
+        &self,
+        query: &EventQuery,
+        visit: &mut (dyn for<'e> FnMut(&'e Event) + Send),
+    ) -> Result<()> {
+        // ---
+        let start = Instant::now();
+        let span = tracing::info_span!("repository.scan");
+        let result = self.inner.scan(query, visit).instrument(span).await;
+        self.metrics
+            .record_repository_operation("scan", start.elapsed(), result.is_ok());
+        result
+    }
+
+    async fn event_types(&self) -> Result<Vec<EventTypeSummary>> {
+        // ---
+        let start = Instant::now();
+        let span = tracing::info_span!("repository.event_types");
+        let result = self.inner.event_types().instrument(span).await;
+        self.metrics
+            .record_repository_operation("event_types", start.elapsed(), result.is_ok());
+        result
+    }
+
+    async fn funnel(&self, query: FunnelQuery) -> Result<FunnelReport> {
+        // ---
+        let start = Instant::now();
+        let span = tracing::info_span!("repository.funnel", steps = query.steps.len());
+        let result = self.inner.funnel(query).instrument(span).await;
+        self.metrics
+            .record_repository_operation("funnel", start.elapsed(), result.is_ok());
+        result
+    }
+
+    async fn sessions(&self, query: SessionQuery) -> Result<SessionReport> {
+        // ---
+        let start = Instant::now();
+        let span = tracing::info_span!("repository.sessions");
+        let result = self.inner.sessions(query).instrument(span).await;
+        self.metrics
+            .record_repository_operation("sessions", start.elapsed(), result.is_ok());
+        result
+    }
+
+    async fn cohorts(&self, query: CohortQuery) -> Result<CohortReport> {
+        // ---
+        let start = Instant::now();
+        let span = tracing::info_span!("repository.cohorts");
+        let result = self.inner.cohorts(query).instrument(span).await;
+        self.metrics
+            .record_repository_operation("cohorts", start.elapsed(), result.is_ok());
+        result
+    }
+
+    async fn field_values(&self, query: FieldValueQuery) -> Result<FieldValueReport> {
+        // ---
+        let start = Instant::now();
+        let span = tracing::info_span!("repository.field_values");
+        let result = self.inner.field_values(query).instrument(span).await;
+        self.metrics
+            .record_repository_operation("field_values", start.elapsed(), result.is_ok());
+        result
+    }
+
+    async fn numeric_stats(&self, query: NumericStatsQuery) -> Result<NumericStatsReport> {
+        // ---
+        let start = Instant::now();
+        let span = tracing::info_span!("repository.numeric_stats");
+        let result = self.inner.numeric_stats(query).instrument(span).await;
+        self.metrics
+            .record_repository_operation("numeric_stats", start.elapsed(), result.is_ok());
+        result
+    }
+
+    async fn stats(&self) -> Result<RepositoryStats> {
+        self.inner.stats().await
+    }
+
+    async fn health(&self) -> ComponentHealth {
+        self.inner.health().await
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        self.inner.shutdown().await
+    }
+
+    async fn purge_before(&self, cutoff: DateTime<Utc>) -> Result<usize> {
+        self.inner.purge_before(cutoff).await
+    }
+}
+
+/// Wraps `repo` so its calls are reported to `metrics`.
+pub fn instrument_repository(repo: EventRepositoryPtr, metrics: MetricsPtr) -> EventRepositoryPtr {
+    // ---
+    Arc::new(InstrumentedRepository {
+        inner: repo,
+        metrics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+    use crate::domain::ComponentHealth;
+    use crate::infrastructure::create_metrics_for;
+    use crate::repository::observe_events;
+
+    /// Backend with its own funnel, which the decorators must not bypass.
+    struct NativeFunnel;
+
+    #[async_trait]
+    impl EventRepository for NativeFunnel {
+        // ---
+        async fn store_event(&self, _event: Event) -> Result<()> {
+            Ok(())
+        }
+
+        async fn find_events(&self, _query: EventQuery) -> Result<Vec<Event>> {
+            anyhow::bail!("the native funnel does not read events")
+        }
+
+        async fn funnel(&self, _query: FunnelQuery) -> Result<FunnelReport> {
+            Ok(FunnelReport {
+                steps: Vec::new(),
+                entered: 7,
+                completed: 3,
+            })
+        }
+
+        async fn stats(&self) -> Result<RepositoryStats> {
+            Ok(RepositoryStats::default())
+        }
+
+        async fn health(&self) -> ComponentHealth {
+            ComponentHealth::healthy("repository", "native")
+        }
+    }
+
+    #[tokio::test]
+    async fn decorators_reach_a_native_analytics_override() -> Result<()> {
+        // ---
+        let metrics = create_metrics_for("prom")?;
+        let repo = observe_events(Arc::new(NativeFunnel), Vec::new());
+        let repo = instrument_repository(repo, metrics.clone());
+
+        let report = repo
+            .funnel(FunnelQuery {
+                steps: vec!["signup".into(), "purchase".into()],
+                key: "user_id".into(),
+                window: chrono::Duration::hours(1),
+                start: None,
+                end: None,
+            })
+            .await?;
+
+        anyhow::ensure!((report.entered, report.completed) == (7, 3), "{:?}", report);
+        let rendered = metrics.render()?;
+        anyhow::ensure!(
+            rendered.contains(r#"operation="funnel""#),
+            "no funnel latency recorded:\n{rendered}"
+        );
+
+        Ok(())
+    }
+}
@@ -3,13 +3,26 @@
 //! Uses DashMap for concurrent, type-indexed event storage. Events are grouped
 //! by event_type for efficient query filtering. This backend is suitable for testing
 //! and non-persistent deployments.
+//!
+//! The first approximate distinct or top-N query on a field builds per-day
+//! sketches of its values, which stored events keep up to date from then on.
+//! Later queries read whole days from the sketches and only scan the events
+//! of partially covered days. At most `MAX_SKETCHED_FIELDS` fields are
+//! sketched; approximate queries on any other field scan the events.
 
 use crate::domain::EventRepositoryPtr;
 use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use dashmap::mapref::one::Ref;
 use dashmap::DashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
-use crate::domain::{Event, EventQuery, EventRepository};
+use crate::domain::{
+    ComponentHealth, Event, EventQuery, EventRepository, EventTypeStats, FieldSketch,
+    FieldValueCounter, FieldValueQuery, FieldValueReport, RepositoryStats,
+};
 
 /// Creates an Arc-wrapped in-memory repository.
 pub fn create() -> Result<EventRepositoryPtr> {
@@ -17,11 +30,62 @@ pub fn create() -> Result<EventRepositoryPtr> {
     Ok(Arc::new(InMemoryEventRepository::new()))
 }
 
+/// Most (event type, field) pairs the repository keeps sketches of.
+pub const MAX_SKETCHED_FIELDS: usize = 64;
+
+/// Sketches of one field's values, per UTC day.
+type DailySketches = BTreeMap<NaiveDate, FieldSketch>;
+
+/// Maps payload field → sketches of its values, for one event type.
+type FieldSketches = HashMap<String, DailySketches>;
+
+/// Events of one type, with their stats kept up to date as they are stored and purged.
+#[derive(Debug, Default)]
+struct StoredEvents {
+    events: Vec<Event>,
+    stats: EventTypeStats,
+}
+
+impl StoredEvents {
+    // ---
+
+    fn push(&mut self, event: Event) {
+        // ---
+        self.stats.add(event.timestamp, approx_size(&event));
+        self.events.push(event);
+    }
+
+    /// Removes the events before `cutoff`, returning how many were removed.
+    fn purge_before(&mut self, cutoff: DateTime<Utc>) -> usize {
+        // ---
+        let mut kept = EventTypeStats::default();
+        let mut purged_bytes = 0;
+        self.events.retain(|event| {
+            if event.timestamp >= cutoff {
+                kept.add(event.timestamp, 0);
+                return true;
+            }
+            purged_bytes += approx_size(event);
+            false
+        });
+        let removed = self.stats.count - kept.count;
+        kept.approx_bytes = self.stats.approx_bytes - purged_bytes;
+        self.stats = kept;
+        removed as usize
+    }
+}
+
 /// A thread-safe, in-memory event repository using DashMap.
 #[derive(Debug, Default)]
 pub struct InMemoryEventRepository {
-    /// Maps event_type → list of events
-    store: DashMap<String, Vec<Event>>,
+    /// Maps event_type → list of events and their stats
+    store: DashMap<String, StoredEvents>,
+
+    /// Maps event_type → sketched fields; locked before `store` when both are
+    sketches: DashMap<String, FieldSketches>,
+
+    /// Fields sketched across all event types, at most `MAX_SKETCHED_FIELDS`
+    sketched_fields: AtomicUsize,
 }
 
 impl InMemoryEventRepository {
@@ -29,8 +93,83 @@ impl InMemoryEventRepository {
     pub fn new() -> Self {
         Self {
             store: DashMap::new(),
+            sketches: DashMap::new(),
+            sketched_fields: AtomicUsize::new(0),
         }
     }
+
+    /// Returns the sketched fields of `event_type`, sketching `field` from the stored events first if needed.
+    ///
+    /// Returns `None` when `field` is not sketched yet and `MAX_SKETCHED_FIELDS` already are.
+    fn sketched(&self, event_type: &str, field: &str) -> Option<Ref<'_, String, FieldSketches>> {
+        // ---
+        if let Some(fields) = self.sketches.get(event_type) {
+            if fields.contains_key(field) {
+                return Some(fields);
+            }
+        }
+
+        // Holding the entry keeps events of this type from being stored while it is sketched
+        let mut fields = self.sketches.entry(event_type.to_string()).or_default();
+        if !fields.contains_key(field) {
+            let reserved =
+                self.sketched_fields
+                    .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                        (n < MAX_SKETCHED_FIELDS).then_some(n + 1)
+                    });
+            if reserved.is_err() {
+                return None;
+            }
+            let mut days = DailySketches::new();
+            if let Some(stored) = self.store.get(event_type) {
+                stored
+                    .events
+                    .iter()
+                    .for_each(|event| sketch(&mut days, field, event));
+            }
+            fields.insert(field.to_string(), days);
+        }
+        Some(fields.downgrade())
+    }
+
+    /// Calls `visit` with every stored event matching `query`, without copying them.
+    fn for_each_matching(&self, query: &EventQuery, mut visit: impl FnMut(&Event)) {
+        // ---
+        let mut scan = |stored: &StoredEvents| {
+            stored
+                .events
+                .iter()
+                .filter(|event| query.contains(event.timestamp))
+                .for_each(&mut visit)
+        };
+        match &query.event_type {
+            Some(t) => {
+                if let Some(entry) = self.store.get(t) {
+                    scan(entry.value());
+                }
+            }
+            None => self.store.iter().for_each(|entry| scan(entry.value())),
+        }
+    }
+}
+
+/// Adds the value of `field` in `event`, if it carries one, to the sketch of its day.
+fn sketch(days: &mut DailySketches, field: &str, event: &Event) {
+    // ---
+    if let Some(value) = event.payload_key(field) {
+        days.entry(event.timestamp.date_naive())
+            .or_default()
+            .add(&value, 1);
+    }
+}
+
+/// Rough heap footprint of a stored event: the struct plus its strings and payload.
+///
+/// Computed once when an event is stored and once when it is purged.
+fn approx_size(event: &Event) -> u64 {
+    // ---
+    let payload = serde_json::to_vec(&event.payload).map_or(0, |bytes| bytes.len());
+    (std::mem::size_of::<Event>() + event.event_type.len() + payload) as u64
 }
 
 #[async_trait::async_trait]
@@ -40,6 +179,10 @@ impl EventRepository for InMemoryEventRepository {
     async fn store_event(&self, event: Event) -> anyhow::Result<()> {
         // ---
 
+        let mut fields = self.sketches.entry(event.event_type.clone()).or_default();
+        for (field, days) in fields.iter_mut() {
+            sketch(days, field, &event);
+        }
         self.store
             .entry(event.event_type.clone())
             .or_default()
@@ -50,34 +193,114 @@ impl EventRepository for InMemoryEventRepository {
     async fn find_events(&self, query: EventQuery) -> anyhow::Result<Vec<Event>> {
         // ---
 
-        let iter: Vec<Event> = match &query.event_type {
-            Some(t) => self
-                .store
-                .get(t)
-                .into_iter()
-                .flat_map(|entry| entry.value().clone())
-                .collect(),
-            None => self
-                .store
-                .iter()
-                .flat_map(|entry| entry.value().clone())
-                .collect(),
+        let mut events = Vec::new();
+        self.for_each_matching(&query, |event| events.push(event.clone()));
+        Ok(events)
+    }
+
+    async fn scan(
+        &self,
+        query: &EventQuery,
+        visit: &mut (dyn for<'e> FnMut(&'e Event) + Send),
+    ) -> anyhow::Result<()> {
+        // ---
+        self.for_each_matching(query, visit);
+        Ok(())
+    }
+
+    async fn field_values(&self, query: FieldValueQuery) -> anyhow::Result<FieldValueReport> {
+        // ---
+        let mut counter = FieldValueCounter::new(&query);
+        let event_query = query.event_query();
+        let sketched = match query.approximate {
+            true => self.sketched(&query.event_type, &query.field),
+            false => None,
+        };
+        // Exact counts, or too many fields sketched already
+        let Some(fields) = sketched else {
+            self.for_each_matching(&event_query, |event| counter.add(event));
+            return Ok(counter.finish());
         };
 
-        let filtered = iter
-            .into_iter()
-            .filter(|event| {
-                let ts = event.timestamp;
-                match (query.start, query.end) {
-                    (Some(start), Some(end)) => ts >= start && ts <= end,
-                    (Some(start), None) => ts >= start,
-                    (None, Some(end)) => ts <= end,
-                    (None, None) => true,
-                }
-            })
+        for (day, sketch) in fields[&query.field].iter() {
+            let day_start = day.and_time(NaiveTime::MIN).and_utc();
+            let day_end = day_start + Duration::days(1) - Duration::nanoseconds(1);
+            if event_query.contains(day_start) && event_query.contains(day_end) {
+                counter.merge(sketch);
+                continue;
+            }
+            // Only part of the day is in range: count its events one by one
+            let partial = EventQuery {
+                event_type: Some(query.event_type.clone()),
+                start: Some(query.start.map_or(day_start, |start| start.max(day_start))),
+                end: Some(query.end.map_or(day_end, |end| end.min(day_end))),
+            };
+            if partial.start <= partial.end {
+                self.for_each_matching(&partial, |event| counter.add(event));
+            }
+        }
+        Ok(counter.finish())
+    }
+
+    async fn stats(&self) -> anyhow::Result<RepositoryStats> {
+        // ---
+        let event_types: BTreeMap<String, EventTypeStats> = self
+            .store
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().stats.clone()))
             .collect();
+        Ok(RepositoryStats::from_event_types(event_types))
+    }
 
-        Ok(filtered)
+    async fn health(&self) -> ComponentHealth {
+        // ---
+        ComponentHealth::healthy("repository", "memory")
+            .with_detail(format!("{} event types stored", self.store.len()))
+    }
+
+    async fn purge_before(&self, cutoff: DateTime<Utc>) -> anyhow::Result<usize> {
+        // ---
+        let mut removed = 0;
+        for mut entry in self.store.iter_mut() {
+            removed += entry.value_mut().purge_before(cutoff);
+        }
+        self.store.retain(|_, stored| !stored.events.is_empty());
+        if removed == 0 {
+            return Ok(0);
+        }
+
+        // Drop the sketches of purged days and rebuild the cutoff day from what is left of it
+        let cutoff_day = cutoff.date_naive();
+        for mut entry in self.sketches.iter_mut() {
+            let (event_type, fields) = entry.pair_mut();
+            let events = self.store.get(event_type);
+            let remaining = events.iter().flat_map(|stored| stored.events.iter());
+            let cutoff_events: Vec<&Event> = remaining
+                .filter(|event| event.timestamp.date_naive() == cutoff_day)
+                .collect();
+            for (field, days) in fields.iter_mut() {
+                days.retain(|day, _| *day > cutoff_day);
+                for event in &cutoff_events {
+                    sketch(days, field, event);
+                }
+            }
+        }
+        Ok(removed)
+    }
+
+    async fn shutdown(&self) -> anyhow::Result<()> {
+        // ---
+        let events: usize = self
+            .store
+            .iter()
+            .map(|entry| entry.value().events.len())
+            .sum();
+        tracing::warn!(
+            events,
+            event_types = self.store.len(),
+            "In-memory repository shutting down; stored events are not persisted"
+        );
+        Ok(())
     }
 }
 
@@ -87,9 +310,8 @@ mod tests {
     // ---
 
     use super::*;
+    use crate::domain::{FunnelCounter, FunnelQuery};
     use anyhow::Result;
-    use chrono::DateTime;
-    use chrono::Utc;
     use uuid::Uuid;
 
     // ---
@@ -104,6 +326,7 @@ mod tests {
             event_type: event_type.to_string(),
             timestamp,
             payload: serde_json::json!({ "key": "value" }),
+            trace: None,
         })
     }
 
@@ -197,4 +420,188 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn stats_summarise_each_event_type() -> Result<()> {
+        // ---
+
+        let repo = InMemoryEventRepository::new();
+        repo.store_event(make_event("signup", "2025-06-16T10:00:00Z")?)
+            .await?;
+        repo.store_event(make_event("signup", "2025-06-16T12:00:00Z")?)
+            .await?;
+        repo.store_event(make_event("login", "2025-06-16T11:00:00Z")?)
+            .await?;
+
+        let stats = repo.stats().await?;
+
+        anyhow::ensure!(stats.total_events == 3 && stats.distinct_event_types == 2);
+        let signup = &stats.event_types["signup"];
+        anyhow::ensure!(signup.count == 2);
+        anyhow::ensure!(
+            signup.oldest == Some(DateTime::parse_from_rfc3339("2025-06-16T10:00:00Z")?.into())
+        );
+        anyhow::ensure!(
+            stats.newest == Some(DateTime::parse_from_rfc3339("2025-06-16T12:00:00Z")?.into())
+        );
+        anyhow::ensure!(stats.approx_bytes > 3 * std::mem::size_of::<Event>() as u64);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn funnel_over_scan_matches_find_events() -> Result<()> {
+        // ---
+
+        let repo = InMemoryEventRepository::new();
+        for (event_type, user, timestamp) in [
+            ("signup", "a", "2025-06-16T10:00:00Z"),
+            ("purchase", "a", "2025-06-16T10:30:00Z"),
+            ("signup", "b", "2025-06-16T10:00:00Z"),
+            ("purchase", "b", "2025-06-17T10:00:00Z"),
+            ("login", "a", "2025-06-16T10:10:00Z"),
+        ] {
+            let mut event = make_event(event_type, timestamp)?;
+            event.payload = serde_json::json!({ "user_id": user });
+            repo.store_event(event).await?;
+        }
+        let query = FunnelQuery {
+            steps: vec!["signup".into(), "purchase".into()],
+            key: "user_id".into(),
+            window: chrono::Duration::hours(1),
+            start: None,
+            end: None,
+        };
+
+        let scanned = repo.funnel(query.clone()).await?;
+        let mut counter = FunnelCounter::new(&query);
+        for event in repo.find_events(EventQuery::default()).await? {
+            counter.add(&event);
+        }
+
+        anyhow::ensure!(scanned == counter.finish());
+        anyhow::ensure!(scanned.entered == 2 && scanned.completed == 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn approximate_field_values_combine_sketches_and_scans() -> Result<()> {
+        // ---
+
+        let repo = InMemoryEventRepository::new();
+        for (user, timestamp) in [
+            ("a", "2025-06-15T23:00:00Z"),
+            ("b", "2025-06-16T10:00:00Z"),
+            ("a", "2025-06-16T12:00:00Z"),
+            ("c", "2025-06-17T09:00:00Z"),
+            ("c", "2025-06-17T18:00:00Z"),
+        ] {
+            let mut event = make_event("login", timestamp)?;
+            event.payload = serde_json::json!({ "user_id": user });
+            repo.store_event(event).await?;
+        }
+        // The 16th is sketched; only the morning of the 17th is scanned
+        let mut query = FieldValueQuery {
+            event_type: "login".into(),
+            field: "user_id".into(),
+            start: Some(DateTime::parse_from_rfc3339("2025-06-16T00:00:00Z")?.into()),
+            end: Some(DateTime::parse_from_rfc3339("2025-06-17T12:00:00Z")?.into()),
+            limit: 10,
+            approximate: true,
+        };
+
+        let report = repo.field_values(query.clone()).await?;
+        anyhow::ensure!(report.approximate);
+        anyhow::ensure!((report.events, report.distinct) == (3, 3), "{:?}", report);
+
+        query.approximate = false;
+        let exact = repo.field_values(query).await?;
+        anyhow::ensure!(!exact.approximate && exact.distinct == 3);
+
+        // Purging part of the 16th rebuilds its sketch from the remaining events
+        repo.purge_before(DateTime::parse_from_rfc3339("2025-06-16T11:00:00Z")?.into())
+            .await?;
+        let report = repo
+            .field_values(FieldValueQuery {
+                event_type: "login".into(),
+                field: "user_id".into(),
+                start: None,
+                end: None,
+                limit: 1,
+                approximate: true,
+            })
+            .await?;
+        anyhow::ensure!((report.events, report.distinct) == (3, 2), "{:?}", report);
+        anyhow::ensure!(report.top[0].value == "c" && report.top[0].count == 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn only_queried_fields_are_sketched_up_to_the_cap() -> Result<()> {
+        // ---
+
+        let repo = InMemoryEventRepository::new();
+        let fields: Vec<String> = (0..=MAX_SKETCHED_FIELDS)
+            .map(|i| format!("field_{i}"))
+            .collect();
+        let payload: serde_json::Map<_, _> = fields
+            .iter()
+            .map(|field| (field.clone(), serde_json::json!("value")))
+            .collect();
+        let mut event = make_event("signup", "2025-06-16T10:00:00Z")?;
+        event.payload = payload.into();
+        repo.store_event(event).await?;
+        anyhow::ensure!(repo.sketched_fields.load(Ordering::Relaxed) == 0);
+
+        // Past the cap, approximate queries still get answers from scanning
+        for field in &fields {
+            let report = repo
+                .field_values(FieldValueQuery {
+                    event_type: "signup".into(),
+                    field: field.clone(),
+                    start: None,
+                    end: None,
+                    limit: 1,
+                    approximate: true,
+                })
+                .await?;
+            anyhow::ensure!(report.approximate && report.events == 1, "{:?}", report);
+        }
+        anyhow::ensure!(repo.sketched_fields.load(Ordering::Relaxed) == MAX_SKETCHED_FIELDS);
+        anyhow::ensure!(repo.sketches.get("signup").map(|f| f.len()) == Some(MAX_SKETCHED_FIELDS));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn purge_before_removes_only_older_events() -> Result<()> {
+        // ---
+
+        let repo = InMemoryEventRepository::new();
+        repo.store_event(make_event("old", "2025-06-16T10:00:00Z")?)
+            .await?;
+        repo.store_event(make_event("test", "2025-06-16T11:00:00Z")?)
+            .await?;
+        repo.store_event(make_event("test", "2025-06-16T12:00:00Z")?)
+            .await?;
+
+        let cutoff = DateTime::parse_from_rfc3339("2025-06-16T11:00:00Z")?.with_timezone(&Utc);
+        let removed = repo.purge_before(cutoff).await?;
+
+        anyhow::ensure!(removed == 1, "Expected 1 purged event, got {}", removed);
+        let remaining = repo.find_events(EventQuery::default()).await?;
+        anyhow::ensure!(remaining.len() == 2);
+        anyhow::ensure!(remaining.iter().all(|e| e.event_type == "test"));
+
+        // Purging keeps the stats as if only the remaining events had been stored
+        let fresh = InMemoryEventRepository::new();
+        for event in remaining {
+            fresh.store_event(event).await?;
+        }
+        anyhow::ensure!(repo.stats().await? == fresh.stats().await?);
+
+        Ok(())
+    }
 }
@@ -3,14 +3,22 @@
 //! This module provides concrete implementations of the EventRepository trait
 //! and a factory function to create repository instances based on configuration.
 
+mod instrumented;
 mod memory;
 mod noop_repository;
+mod observed;
+mod retention;
+mod stats;
 
 // Public exports
 pub use crate::domain::EventRepositoryPtr;
 use anyhow::Result;
+pub use instrumented::instrument_repository;
 use memory::create as create_memory_repository;
 use noop_repository::create as create_noop_repository;
+pub use observed::observe_events;
+pub use retention::{spawn_retention_task, RetentionPolicyHandle};
+pub use stats::spawn_stats_task;
 
 /// Factory function to create repository instances based on type string
 pub fn create_repository(kind: &str) -> Result<EventRepositoryPtr> {
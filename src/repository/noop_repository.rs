@@ -1,4 +1,6 @@
-use crate::domain::{Event, EventQuery, EventRepository, EventRepositoryPtr};
+use crate::domain::{
+    ComponentHealth, Event, EventQuery, EventRepository, EventRepositoryPtr, RepositoryStats,
+};
 use anyhow::Result;
 use async_trait::async_trait;
 use std::sync::Arc;
@@ -17,6 +19,14 @@ impl EventRepository for NoopRepository {
         tracing::info!("NoopRepository: find_events called");
         Ok(vec![])
     }
+
+    async fn stats(&self) -> Result<RepositoryStats> {
+        Ok(RepositoryStats::default())
+    }
+
+    async fn health(&self) -> ComponentHealth {
+        ComponentHealth::healthy("repository", "noop").with_detail("events are discarded")
+    }
 }
 
 pub fn create() -> Result<EventRepositoryPtr> {
@@ -0,0 +1,109 @@
+//! Repository decorator that notifies observers of stored events.
+//!
+//! Observers (webhooks, alert rules) hear about an event only once
+//! `store_event` has succeeded, so they never act on an event that was
+//! not stored. Everything else is forwarded unchanged.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+use crate::domain::{
+    CohortQuery, CohortReport, ComponentHealth, Event, EventObserverPtr, EventQuery,
+    EventRepository, EventRepositoryPtr, EventTypeSummary, FieldValueQuery, FieldValueReport,
+    FunnelQuery, FunnelReport, NumericStatsQuery, NumericStatsReport, RepositoryStats,
+    SessionQuery, SessionReport,
+};
+
+struct ObservedRepository {
+    inner: EventRepositoryPtr,
+    observers: Vec<EventObserverPtr>,
+}
+
+#[async_trait]
+impl EventRepository for ObservedRepository {
+    // ---
+    async fn store_event(&self, event: Event) -> Result<()> {
+        // ---
+        let interested: Vec<&EventObserverPtr> = self
+            .observers
+            .iter()
+            .filter(|observer| observer.wants(&event))
+            .collect();
+        if interested.is_empty() {
+            return self.inner.store_event(event).await;
+        }
+
+        let stored = event.clone();
+        self.inner.store_event(event).await?;
+        for observer in interested {
+            observer.event_stored(&stored);
+        }
+        Ok(())
+    }
+
+    async fn find_events(&self, query: EventQuery) -> Result<Vec<Event>> {
+        self.inner.find_events(query).await
+    }
+
+    async fn scan(
+        &self,
+        query: &EventQuery,
+        visit: &mut (dyn for<'e> FnMut(&'e Event) + Send),
+    ) -> Result<()> {
+        self.inner.scan(query, visit).await
+    }
+
+    async fn event_types(&self) -> Result<Vec<EventTypeSummary>> {
+        self.inner.event_types().await
+    }
+
+    async fn funnel(&self, query: FunnelQuery) -> Result<FunnelReport> {
+        self.inner.funnel(query).await
+    }
+
+    async fn sessions(&self, query: SessionQuery) -> Result<SessionReport> {
+        self.inner.sessions(query).await
+    }
+
+    async fn cohorts(&self, query: CohortQuery) -> Result<CohortReport> {
+        self.inner.cohorts(query).await
+    }
+
+    async fn field_values(&self, query: FieldValueQuery) -> Result<FieldValueReport> {
+        self.inner.field_values(query).await
+    }
+
+    async fn numeric_stats(&self, query: NumericStatsQuery) -> Result<NumericStatsReport> {
+        self.inner.numeric_stats(query).await
+    }
+
+    async fn stats(&self) -> Result<RepositoryStats> {
+        self.inner.stats().await
+    }
+
+    async fn health(&self) -> ComponentHealth {
+        self.inner.health().await
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        self.inner.shutdown().await
+    }
+
+    async fn purge_before(&self, cutoff: DateTime<Utc>) -> Result<usize> {
+        self.inner.purge_before(cutoff).await
+    }
+}
+
+/// Wraps `repo` so stored events are passed to `observers`.
+pub fn observe_events(
+    repo: EventRepositoryPtr,
+    observers: Vec<EventObserverPtr>,
+) -> EventRepositoryPtr {
+    // ---
+    Arc::new(ObservedRepository {
+        inner: repo,
+        observers,
+    })
+}
@@ -0,0 +1,42 @@
+//! Background task enforcing the event retention policy.
+//!
+//! The policy is read from a shared, swappable handle on every sweep so
+//! configuration reloads take effect without restarting the task.
+
+use arc_swap::ArcSwap;
+use chrono::Utc;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+use crate::domain::{EventRepositoryPtr, RetentionPolicy};
+
+/// Shared handle to the current retention policy.
+pub type RetentionPolicyHandle = Arc<ArcSwap<RetentionPolicy>>;
+
+/// Spawns a task that periodically purges events older than the policy allows.
+pub fn spawn_retention_task(
+    repo: EventRepositoryPtr,
+    policy: RetentionPolicyHandle,
+) -> JoinHandle<()> {
+    // ---
+    tokio::spawn(async move {
+        loop {
+            let current = policy.load_full();
+            tokio::time::sleep(current.sweep_interval).await;
+
+            let Some(max_age) = policy.load().max_age else {
+                continue;
+            };
+            let Ok(max_age) = chrono::Duration::from_std(max_age) else {
+                continue;
+            };
+
+            let cutoff = Utc::now() - max_age;
+            match repo.purge_before(cutoff).await {
+                Ok(0) => tracing::debug!(%cutoff, "Retention sweep found nothing to purge"),
+                Ok(removed) => tracing::info!(removed, %cutoff, "Retention sweep purged events"),
+                Err(err) => tracing::error!(?err, "Retention sweep failed"),
+            }
+        }
+    })
+}
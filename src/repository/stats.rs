@@ -0,0 +1,26 @@
+//! Background task publishing repository stats as metrics gauges.
+
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::domain::{EventRepositoryPtr, MetricsPtr};
+
+/// Spawns a task that reads `repo.stats()` every `interval` and records it.
+pub fn spawn_stats_task(
+    repo: EventRepositoryPtr,
+    metrics: MetricsPtr,
+    interval: Duration,
+) -> JoinHandle<()> {
+    // ---
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(interval);
+        ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticks.tick().await;
+            match repo.stats().await {
+                Ok(stats) => metrics.record_repository_stats(&stats),
+                Err(err) => tracing::error!(?err, "Repository stats refresh failed"),
+            }
+        }
+    })
+}
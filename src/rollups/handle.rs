@@ -0,0 +1,110 @@
+//! Cloneable handle to the rollup subsystem.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use super::store::RollupStore;
+use crate::config::RollupSettings;
+use crate::domain::{Event, EventObserver, RollupDefinition, RollupReport};
+
+/// Rollup buckets and the periodic flush task.
+#[derive(Clone)]
+pub struct Rollups {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    store: Arc<RollupStore>,
+    settings: RollupSettings,
+    flusher: OnceLock<JoinHandle<()>>,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        if let Some(task) = self.flusher.get() {
+            task.abort();
+        }
+    }
+}
+
+impl Rollups {
+    // ---
+
+    /// Sets up `definitions`, restoring their buckets from `settings.state_path`.
+    /// Periodic flushing starts with [`start`](Self::start).
+    pub fn new(definitions: Vec<RollupDefinition>, settings: &RollupSettings) -> Result<Self> {
+        // ---
+        let store = RollupStore::open(definitions, settings.state_path.clone())?;
+        Ok(Self {
+            inner: Arc::new(Inner {
+                store: Arc::new(store),
+                settings: settings.clone(),
+                flusher: OnceLock::new(),
+            }),
+        })
+    }
+
+    /// Starts the periodic flush task if it is not running. Must be called within a Tokio runtime.
+    pub fn start(&self) {
+        // ---
+        let store = self.inner.store.clone();
+        let interval = Duration::from_millis(self.inner.settings.flush_interval_ms);
+        self.inner
+            .flusher
+            .get_or_init(|| tokio::spawn(run_flusher(store, interval)));
+    }
+
+    /// Buckets of the rollup `name` overlapping `start..=end`; `None` if there is no such rollup.
+    pub fn report(
+        &self,
+        name: &str,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Option<RollupReport> {
+        self.inner.store.report(name, start, end)
+    }
+
+    /// Drops expired buckets and writes the rest to `state_path`, if set.
+    pub fn flush(&self) -> Result<()> {
+        self.inner.store.flush(Utc::now())
+    }
+}
+
+/// Flushes `store` each `interval` until the task is aborted.
+async fn run_flusher(store: Arc<RollupStore>, interval: Duration) {
+    // ---
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        let store = store.clone();
+        match tokio::task::spawn_blocking(move || store.flush(Utc::now())).await {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => tracing::error!("Failed to flush rollups: {:#}", err),
+            Err(err) => tracing::error!(?err, "Rollup flush task failed"),
+        }
+    }
+}
+
+impl EventObserver for Rollups {
+    fn wants(&self, event: &Event) -> bool {
+        self.inner.store.wants(event)
+    }
+
+    fn event_stored(&self, event: &Event) {
+        self.inner.store.record(event);
+    }
+}
+
+impl fmt::Debug for Rollups {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rollups")
+            .field("state_path", &self.inner.settings.state_path)
+            .field("flush_interval_ms", &self.inner.settings.flush_interval_ms)
+            .finish_non_exhaustive()
+    }
+}
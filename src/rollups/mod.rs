@@ -0,0 +1,30 @@
+//! Continuously maintained rollups of stored events.
+//!
+//! Rollups from `[rollups]` are updated as each event is stored, so
+//! dashboards read pre-aggregated buckets at `GET /v1/rollups/{name}` instead
+//! of scanning raw events. Buckets are kept apart from the repository, so
+//! raw-event retention does not remove them; a rollup's own `max_age_secs`
+//! bounds them instead.
+
+mod handle;
+mod store;
+mod table;
+
+// Public exports
+pub use handle::Rollups;
+
+use crate::config::RollupSettings;
+use crate::domain::RollupDefinition;
+
+/// Sets up `definitions`, restoring stored buckets, and starts the periodic flush.
+///
+/// Must be called within a Tokio runtime.
+pub fn create_rollups(
+    definitions: Vec<RollupDefinition>,
+    settings: &RollupSettings,
+) -> anyhow::Result<Rollups> {
+    // ---
+    let rollups = Rollups::new(definitions, settings)?;
+    rollups.start();
+    Ok(rollups)
+}
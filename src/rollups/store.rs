@@ -0,0 +1,229 @@
+//! Rollup buckets, optionally backed by a file.
+//!
+//! Buckets live in memory behind one lock and are updated as events are
+//! stored. With a `state_path` they are written to a JSON file by
+//! [`flush`](RollupStore::flush) (to a temporary file first, then renamed
+//! over the old one) and read back at startup. Stored buckets are only
+//! restored for a rollup whose definition did not change.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+use super::table::RollupTable;
+use crate::domain::{Event, EventFilter, RollupBucket, RollupDefinition, RollupReport};
+
+/// Everything that is persisted.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct State {
+    rollups: BTreeMap<String, StoredRollup>,
+}
+
+/// One rollup on disk, with the parts of its definition that shape the buckets.
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredRollup {
+    #[serde(flatten)]
+    events: EventFilter,
+    interval_secs: u64,
+    group_by: Vec<String>,
+    fields: Vec<String>,
+    buckets: Vec<RollupBucket>,
+}
+
+impl StoredRollup {
+    // ---
+
+    /// Whether the buckets were computed by `definition`.
+    fn fits(&self, definition: &RollupDefinition) -> bool {
+        // ---
+        self.events == definition.events
+            && self.interval_secs == definition.interval.as_secs()
+            && self.group_by == definition.group_by
+            && self.fields == definition.fields
+    }
+}
+
+/// Every rollup's buckets.
+pub struct RollupStore {
+    path: Option<PathBuf>,
+    tables: Mutex<Vec<RollupTable>>,
+
+    /// Buckets changed since the last flush.
+    dirty: AtomicBool,
+}
+
+impl RollupStore {
+    // ---
+
+    /// Opens the store for `definitions`, restoring their buckets from `path` if it exists.
+    pub fn open(definitions: Vec<RollupDefinition>, path: Option<PathBuf>) -> Result<Self> {
+        // ---
+        let mut state = match &path {
+            Some(path) if path.exists() => load(path)?,
+            _ => State::default(),
+        };
+        let tables = definitions
+            .into_iter()
+            .map(|definition| {
+                let buckets = match state.rollups.remove(&definition.name) {
+                    Some(stored) if stored.fits(&definition) => stored.buckets,
+                    Some(_) => {
+                        tracing::warn!(
+                            rollup = %definition.name,
+                            "Rollup definition changed, discarding its stored buckets"
+                        );
+                        Vec::new()
+                    }
+                    None => Vec::new(),
+                };
+                RollupTable::new(definition, buckets)
+            })
+            .collect();
+        Ok(Self {
+            path,
+            tables: Mutex::new(tables),
+            dirty: AtomicBool::new(false),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<RollupTable>> {
+        self.tables.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Whether any rollup counts `event`.
+    pub fn wants(&self, event: &Event) -> bool {
+        self.lock().iter().any(|t| t.definition.matches(event))
+    }
+
+    /// Adds `event` to every rollup it matches.
+    pub fn record(&self, event: &Event) {
+        // ---
+        let mut tables = self.lock();
+        for table in tables.iter_mut().filter(|t| t.definition.matches(event)) {
+            table.record(event);
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Buckets of the rollup `name` overlapping `start..=end`; `None` if there is no such rollup.
+    pub fn report(
+        &self,
+        name: &str,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Option<RollupReport> {
+        // ---
+        self.lock()
+            .iter()
+            .find(|t| t.definition.name == name)
+            .map(|t| t.report(start, end))
+    }
+
+    /// Drops expired buckets and, if anything changed, writes the buckets to the file.
+    pub fn flush(&self, now: DateTime<Utc>) -> Result<()> {
+        // ---
+        let state = {
+            let mut tables = self.lock();
+            let mut pruned = false;
+            for table in tables.iter_mut() {
+                pruned |= table.prune(now);
+            }
+            if !self.dirty.swap(false, Ordering::Relaxed) && !pruned {
+                return Ok(());
+            }
+            if self.path.is_none() {
+                return Ok(());
+            }
+            State {
+                rollups: tables
+                    .iter()
+                    .map(|table| {
+                        let definition = &table.definition;
+                        let stored = StoredRollup {
+                            events: definition.events.clone(),
+                            interval_secs: definition.interval.as_secs(),
+                            group_by: definition.group_by.clone(),
+                            fields: definition.fields.clone(),
+                            buckets: table.buckets(),
+                        };
+                        (definition.name.clone(), stored)
+                    })
+                    .collect(),
+            }
+        };
+        self.save(&state)
+    }
+
+    /// Writes `state` to the file, if there is one.
+    fn save(&self, state: &State) -> Result<()> {
+        // ---
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(state)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        std::fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))
+    }
+}
+
+fn load(path: &Path) -> Result<State> {
+    // ---
+    let bytes =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_slice(&bytes)
+        .with_context(|| format!("Invalid rollup state in {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+    use crate::domain::EventBuilder;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    fn definition(interval_secs: u64) -> RollupDefinition {
+        // ---
+        RollupDefinition {
+            name: "signups".to_string(),
+            events: EventFilter {
+                event_type: "signup".to_string(),
+                filter: None,
+            },
+            interval: Duration::from_secs(interval_secs),
+            group_by: Vec::new(),
+            fields: Vec::new(),
+            max_age: None,
+        }
+    }
+
+    #[test]
+    fn buckets_survive_reopening_unless_the_definition_changed() -> Result<()> {
+        // ---
+        let path = std::env::temp_dir().join(format!("argus-rollups-{}.json", Uuid::new_v4()));
+        let store = RollupStore::open(vec![definition(60)], Some(path.clone()))?;
+        store.record(&EventBuilder::new("signup").build());
+        store.record(&EventBuilder::new("signup").build());
+        store.flush(Utc::now())?;
+
+        let reopened = RollupStore::open(vec![definition(60)], Some(path.clone()))?;
+        let changed = RollupStore::open(vec![definition(3600)], Some(path.clone()))?;
+        std::fs::remove_file(&path)?;
+
+        let events: u64 = reopened
+            .report("signups", None, None)
+            .map(|report| report.buckets.iter().map(|b| b.events).sum())
+            .unwrap_or_default();
+        assert_eq!(events, 2);
+        let report = changed.report("signups", None, None);
+        assert_eq!(report.map(|r| r.buckets.len()), Some(0));
+        assert!(reopened.report("logins", None, None).is_none());
+        Ok(())
+    }
+}
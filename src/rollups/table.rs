@@ -0,0 +1,210 @@
+//! Buckets of one rollup.
+
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+use crate::domain::{Event, FieldStats, RollupBucket, RollupDefinition, RollupReport};
+
+/// Bucket start (Unix seconds) and group values.
+type BucketKey = (i64, BTreeMap<String, String>);
+
+/// One rollup's definition and buckets.
+pub struct RollupTable {
+    pub definition: RollupDefinition,
+    buckets: BTreeMap<BucketKey, RollupBucket>,
+}
+
+impl RollupTable {
+    // ---
+
+    /// A table holding `buckets`, e.g. as restored from disk.
+    pub fn new(definition: RollupDefinition, buckets: Vec<RollupBucket>) -> Self {
+        // ---
+        let buckets = buckets
+            .into_iter()
+            .map(|bucket| ((bucket.start.timestamp(), bucket.group.clone()), bucket))
+            .collect();
+        Self {
+            definition,
+            buckets,
+        }
+    }
+
+    fn interval_secs(&self) -> i64 {
+        self.definition.interval.as_secs().max(1) as i64
+    }
+
+    /// Adds a matching event to its bucket.
+    pub fn record(&mut self, event: &Event) {
+        // ---
+        let interval = self.interval_secs();
+        let start = event.timestamp.timestamp().div_euclid(interval) * interval;
+        let group: BTreeMap<String, String> = self
+            .definition
+            .group_by
+            .iter()
+            .filter_map(|path| Some((path.clone(), event.payload_key(path)?)))
+            .collect();
+
+        let bucket = self
+            .buckets
+            .entry((start, group.clone()))
+            .or_insert_with(|| RollupBucket {
+                start: DateTime::from_timestamp(start, 0).unwrap_or_default(),
+                group,
+                events: 0,
+                fields: BTreeMap::new(),
+            });
+        bucket.events += 1;
+        for path in &self.definition.fields {
+            let Some(value) = event.payload_value(path).and_then(|v| v.as_f64()) else {
+                continue;
+            };
+            match bucket.fields.get_mut(path) {
+                Some(stats) => stats.record(value),
+                None => {
+                    bucket.fields.insert(path.clone(), FieldStats::new(value));
+                }
+            }
+        }
+    }
+
+    /// Drops buckets that ended more than `max_age` before `now`; returns whether any were.
+    pub fn prune(&mut self, now: DateTime<Utc>) -> bool {
+        // ---
+        let Some(max_age) = self.definition.max_age else {
+            return false;
+        };
+        let oldest_end = now.timestamp() - max_age.as_secs() as i64;
+        let interval = self.interval_secs();
+        let before = self.buckets.len();
+        self.buckets
+            .retain(|(start, _), _| start + interval > oldest_end);
+        self.buckets.len() != before
+    }
+
+    /// Every bucket, ordered by start, then group.
+    pub fn buckets(&self) -> Vec<RollupBucket> {
+        self.buckets.values().cloned().collect()
+    }
+
+    /// Buckets overlapping `start..=end`.
+    pub fn report(&self, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>) -> RollupReport {
+        // ---
+        let interval = self.interval_secs();
+        let from = start.map_or(i64::MIN, |start| start.timestamp() - interval + 1);
+        let to = end.map_or(i64::MAX, |end| end.timestamp());
+        let buckets = self
+            .buckets
+            .iter()
+            .filter(|((bucket_start, _), _)| (from..=to).contains(bucket_start))
+            .map(|(_, bucket)| bucket.clone())
+            .collect();
+
+        let definition = &self.definition;
+        RollupReport {
+            name: definition.name.clone(),
+            event_type: definition.events.event_type.clone(),
+            interval_secs: definition.interval.as_secs(),
+            group_by: definition.group_by.clone(),
+            fields: definition.fields.clone(),
+            buckets,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+    use crate::domain::{EventBuilder, EventFilter};
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn definition(max_age: Option<Duration>) -> RollupDefinition {
+        RollupDefinition {
+            name: "revenue".to_string(),
+            events: EventFilter {
+                event_type: "purchase".to_string(),
+                filter: None,
+            },
+            interval: Duration::from_secs(3600),
+            group_by: vec!["country".to_string()],
+            fields: vec!["order.amount".to_string()],
+            max_age,
+        }
+    }
+
+    #[test]
+    fn events_are_bucketed_grouped_and_aggregated() {
+        // ---
+        let mut table = RollupTable::new(definition(None), Vec::new());
+        for event in [
+            EventBuilder::new("purchase")
+                .at("2025-06-16T10:05:00Z")
+                .payload(json!({ "country": "US", "order": { "amount": 10.0 } }))
+                .build(),
+            EventBuilder::new("purchase")
+                .at("2025-06-16T10:55:00Z")
+                .payload(json!({ "country": "US", "order": { "amount": 30 } }))
+                .build(),
+            EventBuilder::new("purchase")
+                .at("2025-06-16T10:30:00Z")
+                .payload(json!({ "country": "DE", "order": { "amount": "n/a" } }))
+                .build(),
+            EventBuilder::new("purchase")
+                .at("2025-06-16T11:00:00Z")
+                .payload(json!({ "order": { "amount": 5 } }))
+                .build(),
+        ] {
+            table.record(&event);
+        }
+
+        let buckets = table.buckets();
+        assert_eq!(buckets.len(), 3);
+        assert_eq!(buckets[0].start.to_rfc3339(), "2025-06-16T10:00:00+00:00");
+        assert_eq!(buckets[0].group["country"], "DE");
+        assert_eq!(buckets[0].events, 1);
+        assert!(buckets[0].fields.is_empty());
+
+        let us = &buckets[1];
+        assert_eq!(us.events, 2);
+        let amount = &us.fields["order.amount"];
+        assert_eq!(
+            (amount.count, amount.sum, amount.min, amount.max, amount.avg),
+            (2, 40.0, 10.0, 30.0, 20.0)
+        );
+        assert!(buckets[2].group.is_empty());
+
+        let later = table.report(Some("2025-06-16T10:59:59Z".parse().unwrap()), None);
+        assert_eq!(later.buckets.len(), 3);
+        let later = table.report(Some("2025-06-16T11:00:00Z".parse().unwrap()), None);
+        assert_eq!(later.buckets.len(), 1);
+        let earlier = table.report(None, Some("2025-06-16T10:59:59Z".parse().unwrap()));
+        assert_eq!(earlier.buckets.len(), 2);
+    }
+
+    #[test]
+    fn old_buckets_are_pruned() {
+        // ---
+        let mut table = RollupTable::new(definition(Some(Duration::from_secs(7200))), Vec::new());
+        table.record(
+            &EventBuilder::new("purchase")
+                .at("2025-06-16T08:30:00Z")
+                .payload(json!({}))
+                .build(),
+        );
+        table.record(
+            &EventBuilder::new("purchase")
+                .at("2025-06-16T09:30:00Z")
+                .payload(json!({}))
+                .build(),
+        );
+
+        // The 08:00 bucket ended at 09:00, more than two hours before 11:30
+        assert!(table.prune("2025-06-16T11:30:00Z".parse().unwrap()));
+        assert_eq!(table.buckets().len(), 1);
+        assert!(!table.prune("2025-06-16T11:30:00Z".parse().unwrap()));
+    }
+}
@@ -0,0 +1,92 @@
+//! Cloneable handle to the schema subsystem.
+
+use anyhow::Result;
+use std::fmt;
+use std::sync::Arc;
+
+use super::notifier::Notifier;
+use super::registry::SchemaRegistry;
+use crate::config::SchemaSettings;
+use crate::domain::{Event, EventObserver, MetricsPtr, PayloadSchema, SchemaDrift};
+
+/// Inferred schemas and the reporting of their drift.
+#[derive(Clone)]
+pub struct Schemas {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    registry: SchemaRegistry,
+    metrics: MetricsPtr,
+    notifier: Notifier,
+    min_events: u64,
+}
+
+impl Schemas {
+    // ---
+
+    pub fn new(settings: &SchemaSettings, metrics: MetricsPtr) -> Result<Self> {
+        // ---
+        Ok(Self {
+            inner: Arc::new(Inner {
+                registry: SchemaRegistry::new(settings.min_events),
+                metrics,
+                notifier: Notifier::new(settings)?,
+                min_events: settings.min_events,
+            }),
+        })
+    }
+
+    /// Inferred schema of `event_type`; `None` if no such event was stored.
+    pub fn schema(&self, event_type: &str) -> Option<PayloadSchema> {
+        self.inner.registry.schema(event_type)
+    }
+
+    /// Every inferred schema, ordered by event type.
+    pub fn schemas(&self) -> Vec<PayloadSchema> {
+        self.inner.registry.schemas()
+    }
+
+    /// Logs, counts and forwards `drift`.
+    fn report(&self, drift: SchemaDrift) {
+        // ---
+        let changes: Vec<String> = drift
+            .changes
+            .iter()
+            .map(|change| serde_json::to_string(change).unwrap_or_default())
+            .collect();
+        tracing::warn!(
+            event_type = %drift.event_type,
+            event_id = %drift.event_id,
+            changes = %changes.join(", "),
+            "Payload schema drift"
+        );
+        for change in &drift.changes {
+            self.inner
+                .metrics
+                .record_schema_drift(&drift.event_type, change.as_str());
+        }
+        self.inner.notifier.notify(drift);
+    }
+}
+
+impl EventObserver for Schemas {
+    fn wants(&self, _event: &Event) -> bool {
+        true
+    }
+
+    fn event_stored(&self, event: &Event) {
+        if let Some(drift) = self.inner.registry.observe(event) {
+            self.report(drift);
+        }
+    }
+}
+
+impl fmt::Debug for Schemas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Schemas")
+            .field("event_types", &self.schemas().len())
+            .field("min_events", &self.inner.min_events)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,24 @@
+//! Payload schema inference and drift detection.
+//!
+//! With `[schemas]` enabled, the payload schema of every event type is
+//! inferred from its stored events and served at `GET /v1/schemas`. Once a
+//! type's schema has learnt from `min_events` events, a stored event that
+//! adds a field, lacks a required one or changes a field's type is logged,
+//! counted in `schema_drift_total` and, given a `webhook_url`, posted to it.
+//! Schemas are kept in memory and learnt afresh after a restart.
+
+mod handle;
+mod notifier;
+mod registry;
+
+// Public exports
+pub use handle::Schemas;
+
+use crate::config::SchemaSettings;
+use crate::domain::MetricsPtr;
+
+/// Sets up schema inference as configured by `settings`.
+pub fn create_schemas(settings: &SchemaSettings, metrics: MetricsPtr) -> anyhow::Result<Schemas> {
+    // ---
+    Schemas::new(settings, metrics)
+}
@@ -0,0 +1,99 @@
+//! Forwarding of schema drift to the configured webhook.
+//!
+//! Like alert notifications, each report is sent from its own task and
+//! retried with backoff, so a slow endpoint never holds up storing events.
+
+use anyhow::{anyhow, Result};
+use reqwest::{header, Client};
+use std::time::Duration;
+
+use crate::config::SchemaSettings;
+use crate::domain::SchemaDrift;
+use crate::infrastructure::exponential_backoff;
+use crate::webhooks::sign_webhook;
+
+/// Delay before the first retry of a notification.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Upper bound of the retry delay.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Sends drift reports, if a webhook is configured.
+pub struct Notifier {
+    client: Client,
+    url: Option<String>,
+    secret: Option<String>,
+    max_attempts: u32,
+}
+
+impl Notifier {
+    // ---
+
+    pub fn new(settings: &SchemaSettings) -> Result<Self> {
+        // ---
+        Ok(Self {
+            client: Client::builder()
+                .timeout(Duration::from_millis(settings.notify_timeout_ms))
+                .build()?,
+            url: settings.webhook_url.clone(),
+            secret: settings.webhook_secret.clone(),
+            max_attempts: settings.notify_max_attempts,
+        })
+    }
+
+    /// Posts `drift` to the webhook, if there is one, in the background.
+    ///
+    /// Must be called within a Tokio runtime.
+    pub fn notify(&self, drift: SchemaDrift) {
+        // ---
+        let Some(url) = self.url.clone() else {
+            return;
+        };
+        let secret = self.secret.clone();
+        let client = self.client.clone();
+        let max_attempts = self.max_attempts;
+        tokio::spawn(async move {
+            for attempt in 0..max_attempts {
+                match send(&client, &url, secret.as_deref(), &drift).await {
+                    Ok(()) => return,
+                    Err(err) => tracing::warn!(
+                        event_type = %drift.event_type,
+                        attempt = attempt + 1,
+                        "Schema drift notification failed: {:#}",
+                        err
+                    ),
+                }
+                if attempt + 1 < max_attempts {
+                    tokio::time::sleep(exponential_backoff(INITIAL_BACKOFF, MAX_BACKOFF, attempt))
+                        .await;
+                }
+            }
+            tracing::error!(
+                event_type = %drift.event_type,
+                %url,
+                "Giving up on schema drift notification"
+            );
+        });
+    }
+}
+
+/// Posts one report; any non-`2xx` response is an error.
+async fn send(client: &Client, url: &str, secret: Option<&str>, drift: &SchemaDrift) -> Result<()> {
+    // ---
+    let body = serde_json::to_vec(drift)?;
+    let mut request = client
+        .post(url)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("x-argus-schema-drift", &drift.event_type);
+    if let Some(secret) = secret {
+        request = request.header("x-argus-signature", sign_webhook(secret, &body));
+    }
+    let response = request.body(body).send().await?;
+
+    let code = response.status();
+    if code.is_success() {
+        Ok(())
+    } else {
+        Err(anyhow!("{} responded {}", url, code))
+    }
+}
@@ -0,0 +1,104 @@
+//! Inferred schemas of every event type.
+
+use dashmap::DashMap;
+
+use crate::domain::{Event, InferredSchema, PayloadSchema, SchemaDrift};
+
+/// One `InferredSchema` per event type.
+pub struct SchemaRegistry {
+    schemas: DashMap<String, InferredSchema>,
+
+    /// Events a schema learns from before its departures count as drift.
+    min_events: u64,
+}
+
+impl SchemaRegistry {
+    // ---
+
+    pub fn new(min_events: u64) -> Self {
+        Self {
+            schemas: DashMap::new(),
+            min_events,
+        }
+    }
+
+    /// Learns `event`, returning its drift if its type's schema was established.
+    pub fn observe(&self, event: &Event) -> Option<SchemaDrift> {
+        // ---
+        let mut schema = self
+            .schemas
+            .entry(event.event_type.clone())
+            .or_insert_with(|| InferredSchema::new(&event.event_type));
+        let established = schema.events() >= self.min_events;
+        let changes = schema.observe(event);
+        (established && !changes.is_empty()).then(|| SchemaDrift {
+            event_type: event.event_type.clone(),
+            event_id: event.id,
+            timestamp: event.timestamp,
+            changes,
+        })
+    }
+
+    pub fn schema(&self, event_type: &str) -> Option<PayloadSchema> {
+        self.schemas.get(event_type).map(|schema| schema.schema())
+    }
+
+    /// Every schema, ordered by event type.
+    pub fn schemas(&self) -> Vec<PayloadSchema> {
+        // ---
+        let mut schemas: Vec<PayloadSchema> = self
+            .schemas
+            .iter()
+            .map(|entry| entry.value().schema())
+            .collect();
+        schemas.sort_by(|a, b| a.event_type.cmp(&b.event_type));
+        schemas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+    use crate::domain::EventBuilder;
+    use serde_json::json;
+
+    #[test]
+    fn drift_is_reported_once_a_schema_is_established() {
+        // ---
+        let registry = SchemaRegistry::new(2);
+        assert!(registry
+            .observe(
+                &EventBuilder::new("signup")
+                    .payload(json!({ "plan": "free" }))
+                    .build()
+            )
+            .is_none());
+        // Still learning: a second event may add fields silently
+        assert!(registry
+            .observe(
+                &EventBuilder::new("signup")
+                    .payload(json!({ "plan": "pro", "referrer": "ads" }))
+                    .build()
+            )
+            .is_none());
+
+        let drifting = EventBuilder::new("signup")
+            .payload(json!({ "plan": 3 }))
+            .build();
+        let drift = registry.observe(&drifting).expect("drift");
+        assert_eq!(drift.event_id, drifting.id);
+        let changes: Vec<&str> = drift.changes.iter().map(|c| c.as_str()).collect();
+        assert_eq!(changes, ["type_changed"]);
+
+        registry.observe(&EventBuilder::new("login").payload(json!({})).build());
+        let types: Vec<String> = registry
+            .schemas()
+            .into_iter()
+            .map(|s| s.event_type)
+            .collect();
+        assert_eq!(types, ["login", "signup"]);
+        assert_eq!(registry.schema("signup").map(|s| s.events), Some(3));
+    }
+}
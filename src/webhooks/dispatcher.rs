@@ -0,0 +1,169 @@
+//! Background delivery of queued webhooks.
+//!
+//! One task drains the queue: it sends due deliveries concurrently, at most
+//! `max_concurrent_deliveries` at a time, then sleeps until the next one is
+//! due or a new one is queued. A
+//! delivery succeeds on any `2xx` response; anything else is retried with
+//! exponential backoff until `max_attempts` is reached, after which the
+//! delivery becomes a dead letter.
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use reqwest::{header, Client};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tokio::task::JoinSet;
+
+use super::signing::sign;
+use super::store::WebhookStore;
+use crate::config::WebhookSettings;
+use crate::domain::{DeadLetter, Delivery, MetricsPtr, Subscription};
+use crate::infrastructure::exponential_backoff;
+
+/// Longest sleep between queue checks when nothing is scheduled.
+const IDLE_WAIT: Duration = Duration::from_secs(5);
+
+/// Everything the delivery task needs.
+pub struct Dispatcher {
+    pub store: Arc<WebhookStore>,
+    pub wake: Arc<Notify>,
+    pub client: Client,
+    pub settings: WebhookSettings,
+    pub metrics: MetricsPtr,
+}
+
+impl Dispatcher {
+    // ---
+
+    /// Delivers queued webhooks until the task is aborted.
+    pub async fn run(self) {
+        // ---
+        loop {
+            let mut batch = JoinSet::new();
+            let due = self
+                .store
+                .due(Utc::now(), self.settings.max_concurrent_deliveries);
+            for delivery in due {
+                match self.store.subscription(delivery.subscription_id) {
+                    Some(subscription) => {
+                        let client = self.client.clone();
+                        batch.spawn(async move {
+                            let start = Instant::now();
+                            let result = send(&client, &subscription, &delivery).await;
+                            (subscription, delivery, result, start.elapsed())
+                        });
+                    }
+                    // Unsubscribed while queued
+                    None => self.store.complete(delivery.id),
+                }
+            }
+            while let Some(joined) = batch.join_next().await {
+                match joined {
+                    Ok((subscription, delivery, result, elapsed)) => {
+                        self.settle(&subscription, delivery, result, elapsed)
+                    }
+                    Err(err) => tracing::error!(?err, "Webhook delivery task failed"),
+                }
+            }
+
+            let wait = self
+                .store
+                .next_due()
+                .map(|at| (at - Utc::now()).to_std().unwrap_or(Duration::ZERO))
+                .map_or(IDLE_WAIT, |wait| wait.min(IDLE_WAIT));
+            tokio::select! {
+                _ = self.wake.notified() => {}
+                _ = tokio::time::sleep(wait) => {}
+            }
+        }
+    }
+
+    /// Records the outcome of one attempt.
+    fn settle(
+        &self,
+        subscription: &Subscription,
+        mut delivery: Delivery,
+        result: Result<()>,
+        elapsed: Duration,
+    ) {
+        // ---
+        delivery.attempts += 1;
+        let err = match result {
+            Ok(()) => {
+                tracing::debug!(delivery_id = %delivery.id, url = %subscription.url, "Webhook delivered");
+                self.metrics.record_webhook_delivery("delivered", elapsed);
+                self.store.complete(delivery.id);
+                return;
+            }
+            Err(err) => format!("{:#}", err),
+        };
+
+        if delivery.attempts >= self.settings.max_attempts {
+            tracing::warn!(
+                delivery_id = %delivery.id,
+                url = %subscription.url,
+                attempts = delivery.attempts,
+                error = %err,
+                "Webhook delivery failed for good, moving it to the dead letters"
+            );
+            self.metrics.record_webhook_delivery("dead_letter", elapsed);
+            self.store.bury(DeadLetter {
+                id: delivery.id,
+                subscription_id: subscription.id,
+                url: subscription.url.clone(),
+                event: delivery.event,
+                attempts: delivery.attempts,
+                last_error: err,
+                failed_at: Utc::now(),
+            });
+            return;
+        }
+
+        let delay = exponential_backoff(
+            Duration::from_millis(self.settings.initial_backoff_ms),
+            Duration::from_millis(self.settings.max_backoff_ms),
+            delivery.attempts - 1,
+        );
+        tracing::debug!(
+            delivery_id = %delivery.id,
+            attempts = delivery.attempts,
+            ?delay,
+            error = %err,
+            "Webhook delivery failed, retrying"
+        );
+        self.metrics.record_webhook_delivery("retry", elapsed);
+        delivery.next_attempt_at =
+            Utc::now() + chrono::Duration::from_std(delay).unwrap_or(chrono::Duration::zero());
+        delivery.last_error = Some(err);
+        self.store.reschedule(delivery);
+    }
+}
+
+/// Sends one delivery attempt.
+async fn send(client: &Client, subscription: &Subscription, delivery: &Delivery) -> Result<()> {
+    // ---
+    let body = serde_json::to_vec(&json!({
+        "delivery_id": delivery.id,
+        "subscription_id": subscription.id,
+        "attempt": delivery.attempts + 1,
+        "event": delivery.event,
+    }))?;
+    let response = client
+        .post(&subscription.url)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("x-argus-signature", sign(&subscription.secret, &body))
+        .header("x-argus-delivery", delivery.id.to_string())
+        .header("x-argus-event-type", &delivery.event.event_type)
+        .body(body)
+        .send()
+        .await?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(anyhow!("{} responded {}", subscription.url, status))
+    }
+}
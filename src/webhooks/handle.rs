@@ -0,0 +1,238 @@
+//! Cloneable handle to the webhook subsystem.
+
+use anyhow::Result;
+use chrono::Utc;
+use serde_json::{Map, Value};
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use super::dispatcher::Dispatcher;
+use super::signing::generate_secret;
+use super::store::WebhookStore;
+use super::targets::{check_target, PublicResolver};
+use crate::config::WebhookSettings;
+use crate::domain::{DeadLetter, Event, EventFilter, EventObserver, MetricsPtr, Subscription};
+
+/// Subscriptions, the delivery queue and its dispatcher.
+#[derive(Clone)]
+pub struct Webhooks {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    store: Arc<WebhookStore>,
+    wake: Arc<Notify>,
+    settings: WebhookSettings,
+    metrics: MetricsPtr,
+    dispatcher: OnceLock<JoinHandle<()>>,
+    flusher: OnceLock<JoinHandle<()>>,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        for task in [self.dispatcher.get(), self.flusher.get()]
+            .into_iter()
+            .flatten()
+        {
+            task.abort();
+        }
+    }
+}
+
+impl Webhooks {
+    // ---
+
+    /// Opens the webhook state described by `settings`. Deliveries start
+    /// with [`start`](Self::start), or with the first subscription or event.
+    pub fn new(settings: &WebhookSettings, metrics: MetricsPtr) -> Result<Self> {
+        // ---
+        let store = WebhookStore::open(settings.state_path.clone(), settings.max_dead_letters)?;
+        Ok(Self::with_store(store, settings, metrics))
+    }
+
+    /// Default settings, with state kept in memory only.
+    pub fn in_memory(metrics: MetricsPtr) -> Self {
+        // ---
+        let settings = WebhookSettings::default();
+        let store = WebhookStore::in_memory(settings.max_dead_letters);
+        Self::with_store(store, &settings, metrics)
+    }
+
+    fn with_store(store: WebhookStore, settings: &WebhookSettings, metrics: MetricsPtr) -> Self {
+        // ---
+        Self {
+            inner: Arc::new(Inner {
+                store: Arc::new(store),
+                wake: Arc::new(Notify::new()),
+                settings: settings.clone(),
+                metrics,
+                dispatcher: OnceLock::new(),
+                flusher: OnceLock::new(),
+            }),
+        }
+    }
+
+    /// Starts the delivery task, and with a `state_path` the periodic flush
+    /// task, if they are not running. Must be called within a Tokio runtime.
+    pub fn start(&self) -> Result<()> {
+        // ---
+        if self.inner.dispatcher.get().is_some() {
+            return Ok(());
+        }
+        if self.inner.settings.state_path.is_some() {
+            let store = self.inner.store.clone();
+            let interval = Duration::from_millis(self.inner.settings.flush_interval_ms);
+            self.inner
+                .flusher
+                .get_or_init(|| tokio::spawn(run_flusher(store, interval)));
+        }
+        let mut client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(self.inner.settings.timeout_ms));
+        if !self.inner.settings.allow_private_targets {
+            // A redirect could lead to a private address the resolver never sees
+            client = client
+                .dns_resolver(Arc::new(PublicResolver))
+                .redirect(reqwest::redirect::Policy::none());
+        }
+        let dispatcher = Dispatcher {
+            store: self.inner.store.clone(),
+            wake: self.inner.wake.clone(),
+            client: client.build()?,
+            settings: self.inner.settings.clone(),
+            metrics: self.inner.metrics.clone(),
+        };
+        self.inner
+            .dispatcher
+            .get_or_init(|| tokio::spawn(dispatcher.run()));
+        Ok(())
+    }
+
+    /// Checks that deliveries may be sent to `url`.
+    pub fn check_target(&self, url: &reqwest::Url) -> std::result::Result<(), String> {
+        // ---
+        match self.inner.settings.allow_private_targets {
+            true => Ok(()),
+            false => check_target(url),
+        }
+    }
+
+    /// Registers a subscription and saves it; a secret is generated when none is given.
+    pub async fn subscribe(
+        &self,
+        event_type: String,
+        filter: Option<Map<String, Value>>,
+        url: String,
+        secret: Option<String>,
+    ) -> Result<Subscription> {
+        // ---
+        let subscription = Subscription {
+            id: Uuid::new_v4(),
+            events: EventFilter { event_type, filter },
+            url,
+            secret: match secret {
+                Some(secret) => secret,
+                None => generate_secret()?,
+            },
+            created_at: Utc::now(),
+        };
+        self.inner.store.add_subscription(subscription.clone());
+        self.start()?;
+        flush(self.inner.store.clone()).await?;
+        tracing::info!(
+            subscription_id = %subscription.id,
+            event_type = %subscription.events.event_type,
+            url = %subscription.url,
+            "Webhook subscription created"
+        );
+        Ok(subscription)
+    }
+
+    pub fn subscriptions(&self) -> Vec<Subscription> {
+        self.inner.store.subscriptions()
+    }
+
+    /// Removes a subscription, drops its queued deliveries and saves the change.
+    pub async fn unsubscribe(&self, id: Uuid) -> Result<bool> {
+        // ---
+        if !self.inner.store.remove_subscription(id) {
+            return Ok(false);
+        }
+        flush(self.inner.store.clone()).await?;
+        Ok(true)
+    }
+
+    /// Whether any subscription matches `event`.
+    pub fn wants(&self, event: &Event) -> bool {
+        self.inner.store.wants(event)
+    }
+
+    /// Queues `event` for every matching subscription.
+    pub fn enqueue(&self, event: &Event) -> Result<()> {
+        // ---
+        if self.inner.store.enqueue(event) > 0 {
+            self.start()?;
+            self.inner.wake.notify_one();
+        }
+        Ok(())
+    }
+
+    /// Deliveries that are queued or being retried.
+    pub fn pending(&self) -> usize {
+        self.inner.store.pending_count()
+    }
+
+    /// Deliveries that were given up on, oldest first.
+    pub fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.inner.store.dead_letters()
+    }
+
+    /// Writes queue changes not yet flushed to `state_path`, if set.
+    pub fn flush(&self) -> Result<()> {
+        self.inner.store.flush()
+    }
+}
+
+/// Flushes `store` each `interval` until the task is aborted.
+async fn run_flusher(store: Arc<WebhookStore>, interval: Duration) {
+    // ---
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        if let Err(err) = flush(store.clone()).await {
+            tracing::error!("Failed to flush webhook state: {:#}", err);
+        }
+    }
+}
+
+/// Flushes `store` on the blocking pool.
+async fn flush(store: Arc<WebhookStore>) -> Result<()> {
+    // ---
+    tokio::task::spawn_blocking(move || store.flush()).await?
+}
+
+impl EventObserver for Webhooks {
+    fn wants(&self, event: &Event) -> bool {
+        Webhooks::wants(self, event)
+    }
+
+    fn event_stored(&self, event: &Event) {
+        // ---
+        if let Err(err) = self.enqueue(event) {
+            tracing::error!(event_id = %event.id, "Failed to queue webhooks: {:#}", err);
+        }
+    }
+}
+
+impl fmt::Debug for Webhooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Webhooks")
+            .field("state_path", &self.inner.settings.state_path)
+            .field("pending", &self.pending())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,33 @@
+//! Outbound webhooks.
+//!
+//! Subscribers register a URL for an event type (optionally narrowed by
+//! payload fields). Once an event is stored, a delivery is queued for each
+//! matching subscription and POSTed by a background dispatcher with an
+//! HMAC-SHA256 signature, retried with backoff, and kept as a dead letter
+//! when it keeps failing.
+
+mod dispatcher;
+mod handle;
+mod signing;
+mod store;
+mod targets;
+
+// Public exports
+pub use handle::Webhooks;
+pub use signing::sign as sign_webhook;
+
+use crate::config::WebhookSettings;
+use crate::domain::MetricsPtr;
+
+/// Opens the webhook state and starts delivering queued webhooks.
+///
+/// Must be called within a Tokio runtime.
+pub fn create_webhooks(
+    settings: &WebhookSettings,
+    metrics: MetricsPtr,
+) -> anyhow::Result<Webhooks> {
+    // ---
+    let webhooks = Webhooks::new(settings, metrics)?;
+    webhooks.start()?;
+    Ok(webhooks)
+}
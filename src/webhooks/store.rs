@@ -0,0 +1,299 @@
+//! Subscriptions, queued deliveries and dead letters.
+//!
+//! Everything lives in memory behind one lock. With a `state_path` the
+//! whole state is also written to a JSON file (to a temporary file first,
+//! then renamed over the old one), and read back at startup, so queued
+//! deliveries survive a restart. Changes only mark the state dirty, and
+//! [`flush`] writes them outside the state lock, so disk IO never holds up
+//! event ingestion. Subscription changes are flushed right after they are
+//! made, queue changes by a background task.
+//!
+//! [`flush`]: WebhookStore::flush
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use uuid::Uuid;
+
+use crate::domain::{DeadLetter, Delivery, Event, Subscription};
+
+/// Everything that is persisted.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct State {
+    subscriptions: Vec<Subscription>,
+    pending: Vec<Delivery>,
+    dead_letters: VecDeque<DeadLetter>,
+}
+
+/// Webhook state, optionally backed by a file.
+pub struct WebhookStore {
+    path: Option<PathBuf>,
+    max_dead_letters: usize,
+    state: Mutex<State>,
+    dirty: AtomicBool,
+
+    /// Held while a snapshot is taken and written, so writes land in order
+    writing: Mutex<()>,
+}
+
+impl WebhookStore {
+    // ---
+
+    /// Opens the store, loading `path` if it exists.
+    pub fn open(path: Option<PathBuf>, max_dead_letters: usize) -> Result<Self> {
+        // ---
+        let state = match &path {
+            Some(path) if path.exists() => load(path)?,
+            _ => State::default(),
+        };
+        Ok(Self {
+            path,
+            max_dead_letters,
+            state: Mutex::new(state),
+            dirty: AtomicBool::new(false),
+            writing: Mutex::new(()),
+        })
+    }
+
+    /// An empty store that is never written to disk.
+    pub fn in_memory(max_dead_letters: usize) -> Self {
+        Self {
+            path: None,
+            max_dead_letters,
+            state: Mutex::new(State::default()),
+            dirty: AtomicBool::new(false),
+            writing: Mutex::new(()),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Writes the state to the file if it changed since the last write.
+    ///
+    /// Blocks on disk IO, but only holds the state lock to take a snapshot.
+    pub fn flush(&self) -> Result<()> {
+        // ---
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let _writing = self.writing.lock().unwrap_or_else(|e| e.into_inner());
+        let bytes = {
+            let state = self.lock();
+            if !self.dirty.swap(false, Ordering::Relaxed) {
+                return Ok(());
+            }
+            serde_json::to_vec(&*state)?
+        };
+        let saved = write(path, &bytes);
+        if saved.is_err() {
+            // Retried on the next flush
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+        saved
+    }
+
+    pub fn subscriptions(&self) -> Vec<Subscription> {
+        self.lock().subscriptions.clone()
+    }
+
+    pub fn subscription(&self, id: Uuid) -> Option<Subscription> {
+        self.lock()
+            .subscriptions
+            .iter()
+            .find(|s| s.id == id)
+            .cloned()
+    }
+
+    pub fn add_subscription(&self, subscription: Subscription) {
+        // ---
+        self.lock().subscriptions.push(subscription);
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// Removes a subscription and its queued deliveries; false if it did not exist.
+    pub fn remove_subscription(&self, id: Uuid) -> bool {
+        // ---
+        let mut state = self.lock();
+        let before = state.subscriptions.len();
+        state.subscriptions.retain(|s| s.id != id);
+        if state.subscriptions.len() == before {
+            return false;
+        }
+        state.pending.retain(|d| d.subscription_id != id);
+        self.dirty.store(true, Ordering::Relaxed);
+        true
+    }
+
+    /// Whether any subscription matches `event`.
+    pub fn wants(&self, event: &Event) -> bool {
+        self.lock().subscriptions.iter().any(|s| s.matches(event))
+    }
+
+    /// Queues `event` for every matching subscription, returning how many.
+    pub fn enqueue(&self, event: &Event) -> usize {
+        // ---
+        let mut state = self.lock();
+        let deliveries: Vec<Delivery> = state
+            .subscriptions
+            .iter()
+            .filter(|s| s.matches(event))
+            .map(|s| Delivery::new(s, event.clone()))
+            .collect();
+        let queued = deliveries.len();
+        if queued > 0 {
+            state.pending.extend(deliveries);
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+        queued
+    }
+
+    /// Up to `limit` deliveries whose next attempt is due at `now`.
+    pub fn due(&self, now: DateTime<Utc>, limit: usize) -> Vec<Delivery> {
+        // ---
+        self.lock()
+            .pending
+            .iter()
+            .filter(|d| d.next_attempt_at <= now)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Earliest next attempt of any queued delivery.
+    pub fn next_due(&self) -> Option<DateTime<Utc>> {
+        self.lock().pending.iter().map(|d| d.next_attempt_at).min()
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.lock().pending.len()
+    }
+
+    /// Drops a delivery from the queue (delivered, or its subscription is gone).
+    pub fn complete(&self, id: Uuid) {
+        // ---
+        let mut state = self.lock();
+        state.pending.retain(|d| d.id != id);
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// Records a failed attempt and when to try again.
+    pub fn reschedule(&self, failed: Delivery) {
+        // ---
+        let mut state = self.lock();
+        if let Some(delivery) = state.pending.iter_mut().find(|d| d.id == failed.id) {
+            *delivery = failed;
+        }
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// Moves a delivery from the queue to the dead-letter list.
+    pub fn bury(&self, dead: DeadLetter) {
+        // ---
+        let mut state = self.lock();
+        state.pending.retain(|d| d.id != dead.id);
+        state.dead_letters.push_back(dead);
+        while state.dead_letters.len() > self.max_dead_letters {
+            state.dead_letters.pop_front();
+        }
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// Dead letters, oldest first.
+    pub fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.lock().dead_letters.iter().cloned().collect()
+    }
+}
+
+/// Writes `bytes` to a temporary file next to `path`, then renames it over `path`.
+fn write(path: &Path, bytes: &[u8]) -> Result<()> {
+    // ---
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, bytes).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))
+}
+
+fn load(path: &Path) -> Result<State> {
+    // ---
+    let bytes =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_slice(&bytes)
+        .with_context(|| format!("Invalid webhook state in {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+    use crate::domain::{EventBuilder, EventFilter};
+
+    fn subscription() -> Subscription {
+        // ---
+        Subscription {
+            id: Uuid::new_v4(),
+            events: EventFilter {
+                event_type: "signup".to_string(),
+                filter: None,
+            },
+            url: "http://localhost/hook".to_string(),
+            secret: "secret".to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn queue_survives_reopening() -> Result<()> {
+        // ---
+        let path = std::env::temp_dir().join(format!("argus-webhooks-{}.json", Uuid::new_v4()));
+        let store = WebhookStore::open(Some(path.clone()), 10)?;
+        let subscription = subscription();
+        store.add_subscription(subscription.clone());
+        assert_eq!(store.enqueue(&EventBuilder::new("signup").build()), 1);
+
+        // Changes reach the file only when flushed
+        let unflushed = WebhookStore::open(Some(path.clone()), 10)?;
+        assert!(unflushed.subscriptions().is_empty());
+        assert_eq!(unflushed.due(Utc::now(), 10).len(), 0);
+        store.flush()?;
+
+        let reopened = WebhookStore::open(Some(path.clone()), 10)?;
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(reopened.subscriptions(), vec![subscription]);
+        assert_eq!(reopened.due(Utc::now(), 10).len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn dead_letters_are_bounded() -> Result<()> {
+        // ---
+        let store = WebhookStore::in_memory(2);
+        let subscription = subscription();
+        store.add_subscription(subscription.clone());
+        for _ in 0..3 {
+            store.enqueue(&EventBuilder::new("signup").build());
+        }
+
+        for delivery in store.due(Utc::now(), 10) {
+            store.bury(DeadLetter {
+                id: delivery.id,
+                subscription_id: subscription.id,
+                url: subscription.url.clone(),
+                event: delivery.event,
+                attempts: 1,
+                last_error: "refused".to_string(),
+                failed_at: Utc::now(),
+            });
+        }
+
+        assert_eq!(store.pending_count(), 0);
+        assert_eq!(store.dead_letters().len(), 2);
+        Ok(())
+    }
+}
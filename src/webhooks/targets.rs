@@ -0,0 +1,106 @@
+//! Webhook targets outside the private network.
+//!
+//! Any client with the `ingest` role can subscribe, so without these checks
+//! a subscriber could make the server POST events to loopback, private or
+//! link-local addresses, such as a cloud metadata endpoint. URLs naming such
+//! an address, or `localhost`, are rejected when subscribing. Other host
+//! names are checked each time the dispatcher resolves them, so a name
+//! cannot be pointed at a private address later. Both checks are skipped
+//! with `webhooks.allow_private_targets`.
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::Url;
+use std::net::{IpAddr, SocketAddr};
+
+/// Whether `ip` is loopback, private, link-local or unspecified.
+pub fn is_private(ip: IpAddr) -> bool {
+    // ---
+    match ip {
+        IpAddr::V4(ip) => {
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_private(mapped.into()),
+            None => {
+                let first = ip.segments()[0];
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    // Unique local (fc00::/7) and link-local (fe80::/10)
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80
+            }
+        },
+    }
+}
+
+/// Checks that `url` does not name a private address or `localhost`.
+pub fn check_target(url: &Url) -> Result<(), String> {
+    // ---
+    let host = url.host_str().unwrap_or_default();
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let private = match host.parse::<IpAddr>() {
+        Ok(ip) => is_private(ip),
+        Err(_) => {
+            let name = host.trim_end_matches('.').to_ascii_lowercase();
+            name.is_empty() || name == "localhost" || name.ends_with(".localhost")
+        }
+    };
+    match private {
+        true => Err(format!("url '{}' points to a private address", url)),
+        false => Ok(()),
+    }
+}
+
+/// Resolves host names like the system does, but drops private addresses.
+pub struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        // ---
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| !is_private(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} resolves only to private addresses", name.as_str()).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // ---
+
+    use super::*;
+
+    #[test]
+    fn private_targets_are_rejected() {
+        // ---
+        for url in [
+            "http://127.0.0.1:8080/hook",
+            "http://10.1.2.3/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://[::1]/hook",
+            "http://[fd00::1]/hook",
+            "http://[::ffff:192.168.0.1]/hook",
+            "http://localhost/hook",
+            "http://api.localhost./hook",
+        ] {
+            assert!(check_target(&Url::parse(url).unwrap()).is_err(), "{url}");
+        }
+        for url in [
+            "https://hooks.example.com/argus",
+            "http://93.184.216.34/hook",
+            "http://[2606:2800:220:1::]/hook",
+        ] {
+            assert!(check_target(&Url::parse(url).unwrap()).is_ok(), "{url}");
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Alert rule tests against an in-process notification receiver.
+
+use anyhow::{anyhow, ensure, Result};
+use argus_events::{
+    create_alerts, create_metrics_for, create_repository, sign_webhook, AlertCondition, AlertRule,
+    AlertSettings, AppConfig, EventFilter, MetricsPtr,
+};
+use reqwest::Client;
+use serde_json::{json, Map, Value};
+use std::time::Duration;
+
+mod common;
+use common::{event, post_event, start_receiver, start_server, wait_for};
+
+fn rule(name: &str, condition: AlertCondition, threshold: f64, window_secs: u64) -> AlertRule {
+    AlertRule {
+        name: name.to_string(),
+        events: EventFilter {
+            event_type: "payment_failed".to_string(),
+            filter: None,
+        },
+        condition,
+        threshold,
+        window: Duration::from_secs(window_secs),
+        for_duration: Duration::ZERO,
+        webhook_url: None,
+        webhook_secret: None,
+    }
+}
+
+async fn start_alert_server(rules: Vec<AlertRule>, metrics: MetricsPtr) -> Result<String> {
+    // ---
+    let settings = AlertSettings {
+        evaluation_interval_ms: 50,
+        ..AlertSettings::default()
+    };
+    let config = AppConfig {
+        alerts: Some(create_alerts(rules, &settings, metrics.clone())?),
+        ..AppConfig::default()
+    };
+    start_server(create_repository("memory")?, metrics, config).await
+}
+
+/// Submits a `payment_failed` event.
+async fn post_failure(client: &Client, base_url: &str, payload: Value) -> Result<()> {
+    post_event(client, base_url, event("payment_failed", payload)).await
+}
+
+/// State of the rule named `name` in `GET /v1/alerts`.
+async fn alert_state(client: &Client, base_url: &str, name: &str) -> Result<String> {
+    // ---
+    let alerts: Vec<Value> = client
+        .get(format!("{}/v1/alerts", base_url))
+        .send()
+        .await?
+        .json()
+        .await?;
+    alerts
+        .iter()
+        .find(|alert| alert["rule"] == name)
+        .and_then(|alert| alert["state"].as_str())
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("Rule {} missing from {:?}", name, alerts))
+}
+
+#[tokio::test]
+async fn count_rule_fires_notifies_and_exports_gauges() -> Result<()> {
+    // ---
+    let (receiver, received) = start_receiver().await?;
+    let metrics = create_metrics_for("prom")?;
+    let mut filter = Map::new();
+    filter.insert("region".to_string(), json!("eu"));
+    let mut failures = AlertRule {
+        webhook_url: Some(format!("http://{}/alerts", receiver)),
+        webhook_secret: Some("s3cret".to_string()),
+        ..rule("eu_payment_failures", AlertCondition::Count, 2.0, 60)
+    };
+    failures.events.filter = Some(filter);
+    let base_url = start_alert_server(vec![failures], metrics.clone()).await?;
+    let client = Client::new();
+
+    for region in ["eu", "eu", "us"] {
+        post_failure(&client, &base_url, json!({ "region": region })).await?;
+    }
+    let state = alert_state(&client, &base_url, "eu_payment_failures").await?;
+    ensure!(
+        state == "inactive",
+        "Two matching events must not fire, got {}",
+        state
+    );
+
+    post_failure(&client, &base_url, json!({ "region": "eu" })).await?;
+    let state = alert_state(&client, &base_url, "eu_payment_failures").await?;
+    ensure!(state == "firing", "Expected firing, got {}", state);
+
+    wait_for("the firing notification", || {
+        !received.lock().unwrap().is_empty()
+    })
+    .await?;
+    let (signature, body) = received.lock().unwrap()[0].clone();
+    ensure!(
+        signature == sign_webhook("s3cret", body.as_bytes()),
+        "Bad signature {}",
+        signature
+    );
+    let notification: Value = serde_json::from_str(&body)?;
+    ensure!(
+        notification["state"] == "firing" && notification["value"] == 3.0,
+        "Unexpected notification {}",
+        notification
+    );
+
+    let rendered = metrics.render()?;
+    for line in [
+        "alert_state{rule=\"eu_payment_failures\",state=\"firing\"} 1",
+        "alert_state{rule=\"eu_payment_failures\",state=\"inactive\"} 0",
+        "alert_value{rule=\"eu_payment_failures\"} 3",
+    ] {
+        ensure!(
+            rendered.contains(line),
+            "Missing {} in:\n{}",
+            line,
+            rendered
+        );
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn absence_rule_fires_without_events_and_resolves_on_one() -> Result<()> {
+    // ---
+    let metrics = create_metrics_for("noop")?;
+    let base_url = start_alert_server(
+        vec![rule("payments_silent", AlertCondition::Absence, 0.0, 1)],
+        metrics,
+    )
+    .await?;
+    let client = Client::new();
+
+    let firing = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if alert_state(&client, &base_url, "payments_silent").await? == "firing" {
+                return Ok::<_, anyhow::Error>(());
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await;
+    ensure!(matches!(firing, Ok(Ok(()))), "Absence rule did not fire");
+
+    post_failure(&client, &base_url, json!({})).await?;
+    let state = alert_state(&client, &base_url, "payments_silent").await?;
+    ensure!(state == "resolved", "Expected resolved, got {}", state);
+    Ok(())
+}
@@ -0,0 +1,113 @@
+//! Helpers shared by the integration tests: an in-process API server, a
+//! webhook receiver, event posting and polling.
+
+#![allow(dead_code)]
+
+use anyhow::{anyhow, ensure, Result};
+use argus_events::{create_app_with_config, AppConfig, EventRepositoryPtr, MetricsPtr};
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Router,
+};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+/// Requests as seen by the receiver: signature header and raw body.
+pub type Received = Arc<Mutex<Vec<(String, String)>>>;
+
+/// Serves the API over `repo`, `metrics` and `config`, returning its base URL.
+pub async fn start_server(
+    repo: EventRepositoryPtr,
+    metrics: MetricsPtr,
+    config: AppConfig,
+) -> Result<String> {
+    // ---
+    let app = create_app_with_config(repo, metrics, config)?;
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    Ok(format!("http://{}", addr))
+}
+
+/// Starts a receiver accepting every `POST` with `200`.
+pub async fn start_receiver() -> Result<(SocketAddr, Received)> {
+    start_receiver_with(|_, _| StatusCode::OK).await
+}
+
+/// Starts a receiver recording every `POST`; `respond` picks the status from
+/// the request path and the number of requests recorded so far, this one
+/// included.
+pub async fn start_receiver_with(
+    respond: fn(&str, usize) -> StatusCode,
+) -> Result<(SocketAddr, Received)> {
+    // ---
+    let received = Received::default();
+    let app = Router::new()
+        .route(
+            "/*path",
+            post(
+                move |State(received): State<Received>,
+                      Path(path): Path<String>,
+                      headers: HeaderMap,
+                      body: String| async move {
+                    let signature = headers
+                        .get("x-argus-signature")
+                        .and_then(|v| v.to_str().ok())
+                        .unwrap_or_default()
+                        .to_string();
+                    let mut received = received.lock().unwrap();
+                    received.push((signature, body));
+                    respond(&path, received.len())
+                },
+            ),
+        )
+        .with_state(received.clone());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    Ok((addr, received))
+}
+
+/// An event of `event_type` timestamped now.
+pub fn event(event_type: &str, payload: Value) -> Value {
+    json!({
+        "event_type": event_type,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "payload": payload
+    })
+}
+
+/// Submits `event` and expects `201`.
+pub async fn post_event(client: &Client, base_url: &str, event: Value) -> Result<()> {
+    // ---
+    let response = client
+        .post(format!("{}/v1/events", base_url))
+        .json(&event)
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 201,
+        "Expected 201, got {}",
+        response.status()
+    );
+    Ok(())
+}
+
+/// Polls `done` every 20ms for up to five seconds.
+pub async fn wait_for(what: &str, mut done: impl FnMut() -> bool) -> Result<()> {
+    // ---
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !done() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .map_err(|_| anyhow!("Timed out waiting for {}", what))
+}
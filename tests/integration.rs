@@ -1,7 +1,10 @@
 //! Comprehensive integration tests for the Argus Events API.
 
 use anyhow::{anyhow, ensure, Context, Result};
-use argus_events::{create_app, create_metrics, create_repository, Event};
+use argus_events::{
+    create_app, create_app_with_config, create_metrics, create_repository, AppConfig,
+    DeprecationPolicy, Event, Lifecycle, LiveConfig, LogFilter, ValidationRules,
+};
 use axum::Router;
 use chrono::{DateTime, Utc};
 use reqwest::Client;
@@ -105,7 +108,7 @@ pub async fn start_container_server() -> Result<String> {
 
     // Wait up to 10 seconds for the server to be ready
     for _ in 0..20 {
-        if let Ok(response) = reqwest::get(&format!("{}/events", base_url)).await {
+        if let Ok(response) = reqwest::get(&format!("{}/readyz", base_url)).await {
             if response.status().is_success() {
                 break;
             }
@@ -366,6 +369,1126 @@ async fn invalid_event_returns_400() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Rule violations are reported per field in a 422 response body
+#[tokio::test]
+async fn rule_violations_return_422_with_fields() -> anyhow::Result<()> {
+    // ---
+
+    let base_url = start_test_server().await?;
+    let client = Client::new();
+
+    let invalid_event = json!({
+        "event_type": "",
+        "timestamp": "2999-01-01T00:00:00Z",
+        "payload": {"test": "data"}
+    });
+
+    let response = client
+        .post(format!("{}/events", base_url))
+        .json(&invalid_event)
+        .send()
+        .await?;
+
+    ensure!(
+        response.status() == 422,
+        "Expected status 422, got {}",
+        response.status()
+    );
+
+    let body: serde_json::Value = response.json().await?;
+    let violations = body["violations"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Missing violations array: {}", body))?;
+    let fields: Vec<&str> = violations
+        .iter()
+        .filter_map(|v| v["field"].as_str())
+        .collect();
+    ensure!(
+        fields.contains(&"event_type"),
+        "Expected event_type violation, got {:?}",
+        fields
+    );
+    ensure!(
+        fields.contains(&"timestamp"),
+        "Expected timestamp violation, got {:?}",
+        fields
+    );
+
+    // Nothing should have been stored
+    let events: Vec<Event> = client
+        .get(format!("{}/events", base_url))
+        .send()
+        .await?
+        .json()
+        .await?;
+    ensure!(
+        events.is_empty(),
+        "Expected no stored events, got {}",
+        events.len()
+    );
+
+    Ok(())
+}
+
+/// Versioned routes serve the same data as the legacy aliases, and only
+/// the aliases carry deprecation headers.
+#[tokio::test]
+async fn v1_routes_and_deprecated_aliases() -> anyhow::Result<()> {
+    // ---
+
+    let base_url = start_test_server().await?;
+    let client = Client::new();
+
+    let event = json!({
+        "event_type": "versioned",
+        "timestamp": "2025-06-16T12:00:00Z",
+        "payload": {"n": 1}
+    });
+    let response = client
+        .post(format!("{}/v1/events", base_url))
+        .json(&event)
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 201,
+        "Expected 201, got {}",
+        response.status()
+    );
+    ensure!(
+        response.headers().get("deprecation").is_none(),
+        "Versioned route must not be deprecated"
+    );
+
+    let legacy = client.get(format!("{}/events", base_url)).send().await?;
+    ensure!(
+        legacy.status() == 200,
+        "Expected 200, got {}",
+        legacy.status()
+    );
+    let deprecation = legacy
+        .headers()
+        .get("deprecation")
+        .ok_or_else(|| anyhow!("Missing Deprecation header"))?
+        .to_str()?;
+    ensure!(
+        deprecation.starts_with('@'),
+        "Unexpected Deprecation header: {}",
+        deprecation
+    );
+    let link = legacy
+        .headers()
+        .get("link")
+        .ok_or_else(|| anyhow!("Missing Link header"))?
+        .to_str()?
+        .to_string();
+    ensure!(
+        link.contains("</v1/events>"),
+        "Unexpected Link header: {}",
+        link
+    );
+
+    let legacy_events: Vec<Event> = legacy.json().await?;
+    let v1_events: Vec<Event> = client
+        .get(format!("{}/v1/events", base_url))
+        .send()
+        .await?
+        .json()
+        .await?;
+    ensure!(
+        legacy_events.len() == 1 && v1_events.len() == 1,
+        "Expected one event on both routes"
+    );
+    ensure!(
+        legacy_events[0].id == v1_events[0].id,
+        "Routes returned different events"
+    );
+
+    Ok(())
+}
+
+/// A configured sunset date is advertised on the legacy aliases.
+#[tokio::test]
+async fn legacy_routes_advertise_sunset() -> anyhow::Result<()> {
+    // ---
+
+    let sunset: DateTime<Utc> = "2027-01-01T00:00:00Z".parse()?;
+    let config = AppConfig {
+        deprecation: DeprecationPolicy {
+            sunset: Some(sunset),
+            ..DeprecationPolicy::default()
+        },
+        ..AppConfig::default()
+    };
+    let app = create_app_with_config(create_repository("memory")?, create_metrics()?, config)?;
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let response = reqwest::get(format!("http://{}/events", addr)).await?;
+    let header = response
+        .headers()
+        .get("sunset")
+        .ok_or_else(|| anyhow!("Missing Sunset header"))?;
+    ensure!(
+        header == "Fri, 01 Jan 2027 00:00:00 GMT",
+        "Unexpected Sunset header: {:?}",
+        header
+    );
+
+    Ok(())
+}
+
+/// Liveness, readiness and status endpoints report a healthy service
+#[tokio::test]
+async fn health_endpoints_report_ready() -> anyhow::Result<()> {
+    // ---
+
+    let base_url = start_test_server().await?;
+    let client = Client::new();
+
+    let live = client.get(format!("{}/healthz", base_url)).send().await?;
+    ensure!(
+        live.status() == 200,
+        "Expected 200 from /healthz, got {}",
+        live.status()
+    );
+
+    let ready = client.get(format!("{}/readyz", base_url)).send().await?;
+    ensure!(
+        ready.status() == 200,
+        "Expected 200 from /readyz, got {}",
+        ready.status()
+    );
+
+    let status: serde_json::Value = client
+        .get(format!("{}/status", base_url))
+        .send()
+        .await?
+        .json()
+        .await?;
+    ensure!(status["status"] == "ok", "Unexpected status: {}", status);
+    let components: Vec<&str> = status["components"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Missing components: {}", status))?
+        .iter()
+        .filter_map(|c| c["component"].as_str())
+        .collect();
+    ensure!(
+        components == ["repository", "metrics"],
+        "Unexpected components: {:?}",
+        components
+    );
+
+    Ok(())
+}
+
+/// The stats endpoint summarises stored events per type
+#[tokio::test]
+async fn stats_endpoint_summarises_stored_events() -> anyhow::Result<()> {
+    // ---
+
+    let base_url = start_test_server().await?;
+    let client = Client::new();
+    let events = [
+        create_signup_event("2025-06-16T10:00:00Z", "u1", "a@example.com"),
+        create_signup_event("2025-06-16T12:00:00Z", "u2", "b@example.com"),
+        create_purchase_event("2025-06-16T11:00:00Z", "u1", 9.99),
+    ];
+    for event in &events {
+        let response = client
+            .post(format!("{}/v1/events", base_url))
+            .json(event)
+            .send()
+            .await?;
+        ensure!(
+            response.status() == 201,
+            "Expected 201, got {}",
+            response.status()
+        );
+    }
+
+    let stats: serde_json::Value = client
+        .get(format!("{}/v1/stats", base_url))
+        .send()
+        .await?
+        .json()
+        .await?;
+    ensure!(stats["total_events"] == 3, "Unexpected stats: {}", stats);
+    ensure!(
+        stats["distinct_event_types"] == 2,
+        "Unexpected stats: {}",
+        stats
+    );
+    ensure!(
+        stats["oldest"] == "2025-06-16T10:00:00Z" && stats["newest"] == "2025-06-16T12:00:00Z",
+        "Unexpected time range: {}",
+        stats
+    );
+    ensure!(
+        stats["approx_bytes"].as_u64().unwrap_or(0) > 0,
+        "Missing size: {}",
+        stats
+    );
+    let signup = &stats["event_types"]["user_signup"];
+    ensure!(
+        signup["count"] == 2 && signup["newest"] == "2025-06-16T12:00:00Z",
+        "Unexpected signup stats: {}",
+        signup
+    );
+    ensure!(
+        stats["event_types"]["purchase"]["count"] == 1,
+        "Unexpected stats: {}",
+        stats
+    );
+
+    Ok(())
+}
+
+/// Funnels join steps by a payload key and respect the conversion window
+#[tokio::test]
+async fn funnel_endpoint_counts_conversions() -> anyhow::Result<()> {
+    // ---
+
+    let base_url = start_test_server().await?;
+    let client = Client::new();
+    let events = [
+        create_signup_event("2025-06-16T10:00:00Z", "u1", "a@example.com"),
+        create_signup_event("2025-06-16T10:00:00Z", "u2", "b@example.com"),
+        create_signup_event("2025-06-16T10:00:00Z", "u3", "c@example.com"),
+        create_purchase_event("2025-06-16T10:30:00Z", "u1", 9.99),
+        // Outside the one-hour window
+        create_purchase_event("2025-06-16T12:00:00Z", "u2", 5.00),
+        // Never signed up
+        create_purchase_event("2025-06-16T10:30:00Z", "u4", 1.00),
+    ];
+    for event in &events {
+        let response = client
+            .post(format!("{}/v1/events", base_url))
+            .json(event)
+            .send()
+            .await?;
+        ensure!(
+            response.status() == 201,
+            "Expected 201, got {}",
+            response.status()
+        );
+    }
+
+    let response = client
+        .post(format!("{}/v1/analytics/funnel", base_url))
+        .json(&json!({
+            "steps": ["user_signup", "purchase"],
+            "key": "user_id",
+            "window_secs": 3600,
+            "start": "2025-06-16T00:00:00Z",
+            "end": "2025-06-17T00:00:00Z"
+        }))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 200,
+        "Expected 200, got {}",
+        response.status()
+    );
+    let report: serde_json::Value = response.json().await?;
+    ensure!(
+        report["entered"] == 3 && report["completed"] == 1,
+        "Unexpected funnel: {}",
+        report
+    );
+    let purchase = &report["steps"][1];
+    ensure!(
+        purchase["event_type"] == "purchase" && purchase["count"] == 1,
+        "Unexpected step: {}",
+        purchase
+    );
+    let rate = purchase["conversion_rate"].as_f64().unwrap_or(0.0);
+    ensure!((rate - 1.0 / 3.0).abs() < 1e-9, "Unexpected rate {}", rate);
+
+    let response = client
+        .post(format!("{}/v1/analytics/funnel", base_url))
+        .json(&json!({ "steps": ["user_signup"], "key": "user_id", "window_secs": 60 }))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 422,
+        "Expected 422, got {}",
+        response.status()
+    );
+
+    Ok(())
+}
+
+/// Sessions are built from events posted out of timestamp order
+#[tokio::test]
+async fn sessions_endpoint_groups_events_by_inactivity_gap() -> anyhow::Result<()> {
+    // ---
+
+    let base_url = start_test_server().await?;
+    let client = Client::new();
+    let events = [
+        create_purchase_event("2025-06-16T10:20:00Z", "u1", 9.99),
+        create_signup_event("2025-06-16T10:00:00Z", "u1", "a@example.com"),
+        // 50 minutes after the purchase: a new session
+        create_purchase_event("2025-06-16T11:10:00Z", "u1", 5.00),
+        create_signup_event("2025-06-16T10:05:00Z", "u2", "b@example.com"),
+    ];
+    for event in &events {
+        let response = client
+            .post(format!("{}/v1/events", base_url))
+            .json(event)
+            .send()
+            .await?;
+        ensure!(
+            response.status() == 201,
+            "Expected 201, got {}",
+            response.status()
+        );
+    }
+
+    let response = client
+        .get(format!(
+            "{}/v1/analytics/sessions?key=user_id&gap_secs=1800&start=2025-06-16T00:00:00Z",
+            base_url
+        ))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 200,
+        "Expected 200, got {}",
+        response.status()
+    );
+    let report: serde_json::Value = response.json().await?;
+    let sessions = report["sessions"]
+        .as_array()
+        .ok_or_else(|| anyhow!("No sessions in {}", report))?;
+    ensure!(sessions.len() == 3, "Unexpected sessions: {}", report);
+    let first = &sessions[0];
+    ensure!(
+        first["actor"] == "u1"
+            && first["start"] == "2025-06-16T10:00:00Z"
+            && first["duration_secs"] == 1200.0
+            && first["event_types"] == json!(["user_signup", "purchase"]),
+        "Unexpected first session: {}",
+        first
+    );
+    ensure!(
+        sessions[1]["actor"] == "u2" && sessions[2]["event_count"] == 1,
+        "Unexpected order: {}",
+        report
+    );
+    let summary = &report["summary"];
+    ensure!(
+        summary["sessions"] == 3 && summary["actors"] == 2 && summary["events"] == 4,
+        "Unexpected summary: {}",
+        summary
+    );
+
+    let response = client
+        .get(format!(
+            "{}/v1/analytics/sessions?key=user_id&gap_secs=0",
+            base_url
+        ))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 422,
+        "Expected 422, got {}",
+        response.status()
+    );
+
+    Ok(())
+}
+
+/// Retention groups actors into weekly signup cohorts and counts later purchases
+#[tokio::test]
+async fn retention_endpoint_reports_weekly_cohorts() -> anyhow::Result<()> {
+    // ---
+
+    let base_url = start_test_server().await?;
+    let client = Client::new();
+    // 2025-06-16 and 2025-06-23 are Mondays
+    let events = [
+        create_signup_event("2025-06-17T09:00:00Z", "u1", "a@example.com"),
+        create_signup_event("2025-06-18T09:00:00Z", "u2", "b@example.com"),
+        create_signup_event("2025-06-24T09:00:00Z", "u3", "c@example.com"),
+        create_purchase_event("2025-06-19T12:00:00Z", "u1", 9.99),
+        create_purchase_event("2025-06-25T12:00:00Z", "u1", 5.00),
+        create_purchase_event("2025-06-26T12:00:00Z", "u2", 5.00),
+    ];
+    for event in &events {
+        let response = client
+            .post(format!("{}/v1/events", base_url))
+            .json(event)
+            .send()
+            .await?;
+        ensure!(
+            response.status() == 201,
+            "Expected 201, got {}",
+            response.status()
+        );
+    }
+
+    let response = client
+        .post(format!("{}/v1/analytics/retention", base_url))
+        .json(&json!({
+            "birth_event": "user_signup",
+            "return_event": "purchase",
+            "key": "user_id",
+            "period": "week",
+            "periods": 1
+        }))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 200,
+        "Expected 200, got {}",
+        response.status()
+    );
+    let report: serde_json::Value = response.json().await?;
+    ensure!(report["period"] == "week", "Unexpected period: {}", report);
+    ensure!(
+        report["cohorts"]
+            == json!([
+                {
+                    "start": "2025-06-16T00:00:00Z",
+                    "size": 2,
+                    "retained": [1, 2],
+                    "retention_rates": [0.5, 1.0]
+                },
+                {
+                    "start": "2025-06-23T00:00:00Z",
+                    "size": 1,
+                    "retained": [0, 0],
+                    "retention_rates": [0.0, 0.0]
+                }
+            ]),
+        "Unexpected cohorts: {}",
+        report
+    );
+
+    let response = client
+        .post(format!("{}/v1/analytics/retention", base_url))
+        .json(&json!({
+            "birth_event": "user_signup",
+            "return_event": "purchase",
+            "key": "user_id",
+            "periods": 0
+        }))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 422,
+        "Expected 422, got {}",
+        response.status()
+    );
+
+    Ok(())
+}
+
+/// Distinct and top values of a payload field, exact and approximate
+#[tokio::test]
+async fn distinct_and_top_endpoints_summarise_a_field() -> anyhow::Result<()> {
+    // ---
+
+    let base_url = start_test_server().await?;
+    let client = Client::new();
+    for (timestamp, user, amount) in [
+        ("2025-06-16T10:00:00Z", "u1", 10.0),
+        ("2025-06-16T11:00:00Z", "u2", 10.0),
+        ("2025-06-16T12:00:00Z", "u1", 25.0),
+        ("2025-06-17T09:00:00Z", "u3", 10.0),
+    ] {
+        let response = client
+            .post(format!("{}/v1/events", base_url))
+            .json(&create_purchase_event(timestamp, user, amount))
+            .send()
+            .await?;
+        ensure!(
+            response.status() == 201,
+            "Expected 201, got {}",
+            response.status()
+        );
+    }
+
+    for approximate in [false, true] {
+        let response = client
+            .get(format!(
+                "{}/v1/analytics/distinct?type=purchase&field=user_id&approximate={}",
+                base_url, approximate
+            ))
+            .send()
+            .await?;
+        ensure!(
+            response.status() == 200,
+            "Expected 200, got {}",
+            response.status()
+        );
+        let report: serde_json::Value = response.json().await?;
+        ensure!(
+            report["events"] == 4
+                && report["distinct"] == 3
+                && report["approximate"] == approximate,
+            "Unexpected distinct report: {}",
+            report
+        );
+    }
+
+    let response = client
+        .get(format!(
+            "{}/v1/analytics/top?type=purchase&field=amount&limit=1&end=2025-06-16T23:59:59Z",
+            base_url
+        ))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 200,
+        "Expected 200, got {}",
+        response.status()
+    );
+    let report: serde_json::Value = response.json().await?;
+    ensure!(
+        report["events"] == 3 && report["values"] == json!([{ "value": "10.0", "count": 2 }]),
+        "Unexpected top report: {}",
+        report
+    );
+
+    let response = client
+        .get(format!(
+            "{}/v1/analytics/top?type=purchase&field=amount&limit=1000",
+            base_url
+        ))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 422,
+        "Expected 422, got {}",
+        response.status()
+    );
+
+    Ok(())
+}
+
+/// Statistics of a numeric field are reported per bucket and in total,
+/// and a non-numeric field is a 400.
+#[tokio::test]
+async fn statistics_endpoint_summarises_a_numeric_field() -> anyhow::Result<()> {
+    // ---
+
+    let base_url = start_test_server().await?;
+    let client = Client::new();
+    for (timestamp, user, amount) in [
+        ("2025-06-16T10:05:00Z", "u1", 10.0),
+        ("2025-06-16T10:30:00Z", "u2", 30.0),
+        ("2025-06-16T10:55:00Z", "u1", 20.0),
+        ("2025-06-16T12:00:00Z", "u3", 100.0),
+    ] {
+        let response = client
+            .post(format!("{}/v1/events", base_url))
+            .json(&create_purchase_event(timestamp, user, amount))
+            .send()
+            .await?;
+        ensure!(
+            response.status() == 201,
+            "Expected 201, got {}",
+            response.status()
+        );
+    }
+
+    let response = client
+        .get(format!(
+            "{}/v1/analytics/statistics?type=purchase&field=amount&interval_secs=3600",
+            base_url
+        ))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 200,
+        "Expected 200, got {}",
+        response.status()
+    );
+    let report: serde_json::Value = response.json().await?;
+    let first = &report["buckets"][0];
+    ensure!(
+        report["buckets"].as_array().map(Vec::len) == Some(2)
+            && first["start"] == "2025-06-16T10:00:00Z"
+            && first["count"] == 3
+            && first["sum"] == 60.0
+            && first["avg"] == 20.0
+            && first["min"] == 10.0
+            && first["max"] == 30.0,
+        "Unexpected statistics report: {}",
+        report
+    );
+    let p50 = first["p50"].as_f64().unwrap_or_default();
+    ensure!((p50 - 20.0).abs() <= 0.2, "Unexpected p50: {}", p50);
+    ensure!(
+        report["total"]["count"] == 4 && report["total"]["max"] == 100.0,
+        "Unexpected total: {}",
+        report["total"]
+    );
+
+    let response = client
+        .get(format!(
+            "{}/v1/analytics/statistics?type=purchase&field=user_id",
+            base_url
+        ))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 400,
+        "Expected 400, got {}",
+        response.status()
+    );
+    let body: serde_json::Value = response.json().await?;
+    ensure!(
+        body["error"]
+            .as_str()
+            .is_some_and(|e| e.contains("'user_id' is not numeric")),
+        "Unexpected error: {}",
+        body
+    );
+
+    Ok(())
+}
+
+/// Stored event types are listed with counts, time range and payload fields.
+#[tokio::test]
+async fn event_types_endpoint_lists_stored_types() -> anyhow::Result<()> {
+    // ---
+
+    let base_url = start_test_server().await?;
+    let client = Client::new();
+    for event in [
+        create_purchase_event("2025-06-16T10:00:00Z", "u1", 10.0),
+        create_purchase_event("2025-06-16T12:00:00Z", "u2", 25.0),
+        create_test_event_with_sequence("2025-06-16T11:00:00Z", "u1", 1),
+    ] {
+        let response = client
+            .post(format!("{}/v1/events", base_url))
+            .json(&event)
+            .send()
+            .await?;
+        ensure!(
+            response.status() == 201,
+            "Expected 201, got {}",
+            response.status()
+        );
+    }
+
+    let response = client
+        .get(format!("{}/v1/event-types", base_url))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 200,
+        "Expected 200, got {}",
+        response.status()
+    );
+    let types: serde_json::Value = response.json().await?;
+    let purchase = &types[0];
+    ensure!(
+        types.as_array().map(Vec::len) == Some(2)
+            && purchase["event_type"] == "purchase"
+            && purchase["count"] == 2
+            && purchase["first_seen"] == "2025-06-16T10:00:00Z"
+            && purchase["last_seen"] == "2025-06-16T12:00:00Z"
+            && purchase["sampled"] == 2
+            && types[1]["event_type"] == "test_event",
+        "Unexpected event types: {}",
+        types
+    );
+    ensure!(
+        purchase["fields"]
+            == json!([
+                { "name": "amount", "types": ["number"], "presence": 1.0 },
+                { "name": "user_id", "types": ["string"], "presence": 1.0 }
+            ]),
+        "Unexpected purchase fields: {}",
+        purchase["fields"]
+    );
+
+    Ok(())
+}
+
+/// Readiness fails once the service starts draining, while liveness stays up
+#[tokio::test]
+async fn readiness_fails_while_draining() -> anyhow::Result<()> {
+    // ---
+
+    let lifecycle = Lifecycle::default();
+    let config = AppConfig {
+        lifecycle: lifecycle.clone(),
+        ..AppConfig::default()
+    };
+    let app = create_app_with_config(create_repository("memory")?, create_metrics()?, config)?;
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let client = Client::new();
+    let ready = client.get(format!("http://{}/readyz", addr)).send().await?;
+    ensure!(
+        ready.status() == 200,
+        "Expected 200 before draining, got {}",
+        ready.status()
+    );
+
+    lifecycle.start_draining();
+
+    let ready = client.get(format!("http://{}/readyz", addr)).send().await?;
+    ensure!(
+        ready.status() == 503,
+        "Expected 503 while draining, got {}",
+        ready.status()
+    );
+    let body: serde_json::Value = ready.json().await?;
+    ensure!(body["status"] == "draining", "Unexpected body: {}", body);
+
+    let live = client
+        .get(format!("http://{}/healthz", addr))
+        .send()
+        .await?;
+    ensure!(
+        live.status() == 200,
+        "Expected 200 from /healthz, got {}",
+        live.status()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn api_keys_guard_event_routes_and_reload_live() -> anyhow::Result<()> {
+    // ---
+
+    let live = LiveConfig::default();
+    let config = AppConfig {
+        live: live.clone(),
+        ..AppConfig::default()
+    };
+    let app = create_app_with_config(create_repository("memory")?, create_metrics()?, config)?;
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let client = Client::new();
+    let url = format!("http://{}/v1/events", addr);
+    let event = json!({
+        "event_type": "signup",
+        "timestamp": Utc::now().to_rfc3339(),
+        "payload": { "user": "a" }
+    });
+
+    // No keys configured: open access
+    let response = client.post(&url).json(&event).send().await?;
+    ensure!(
+        response.status() == 201,
+        "Expected 201 without keys, got {}",
+        response.status()
+    );
+
+    live.set_api_keys(["s3cret".to_string()].into_iter().collect());
+
+    let response = client.post(&url).json(&event).send().await?;
+    ensure!(
+        response.status() == 401,
+        "Expected 401 without a key, got {}",
+        response.status()
+    );
+    let response = client
+        .post(&url)
+        .header("x-api-key", "wrong")
+        .json(&event)
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 401,
+        "Expected 401 with a wrong key, got {}",
+        response.status()
+    );
+    let response = client
+        .post(&url)
+        .bearer_auth("s3cret")
+        .json(&event)
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 201,
+        "Expected 201 with a bearer key, got {}",
+        response.status()
+    );
+
+    // Health endpoints stay open for probes
+    let ready = client.get(format!("http://{}/readyz", addr)).send().await?;
+    ensure!(
+        ready.status() == 200,
+        "Expected 200 from /readyz, got {}",
+        ready.status()
+    );
+
+    // Tightened limits apply to the next request
+    live.set_validation(ValidationRules {
+        max_payload_keys: 0,
+        ..ValidationRules::default()
+    });
+    let response = client
+        .post(&url)
+        .header("x-api-key", "s3cret")
+        .json(&event)
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 422,
+        "Expected 422 after reload, got {}",
+        response.status()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn request_ids_are_echoed_and_added_to_errors() -> anyhow::Result<()> {
+    // ---
+
+    let base_url = start_test_server().await?;
+    let client = Client::new();
+
+    // A caller-supplied id is echoed back
+    let response = client
+        .get(format!("{}/healthz", base_url))
+        .header("x-request-id", "req-42")
+        .send()
+        .await?;
+    ensure!(
+        response.headers()["x-request-id"] == "req-42",
+        "Expected echoed request id, got {:?}",
+        response.headers().get("x-request-id")
+    );
+
+    // Otherwise one is generated, and JSON error bodies carry it
+    let response = client
+        .post(format!("{}/v1/events", base_url))
+        .json(&json!({ "event_type": "", "timestamp": Utc::now(), "payload": {} }))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 422,
+        "Unexpected status {}",
+        response.status()
+    );
+    let id = response
+        .headers()
+        .get("x-request-id")
+        .context("Missing x-request-id header")?
+        .to_str()?
+        .to_string();
+    ensure!(
+        uuid::Uuid::parse_str(&id).is_ok(),
+        "Generated id {} is not a UUID",
+        id
+    );
+    let body: serde_json::Value = response.json().await?;
+    ensure!(
+        body["request_id"] == id.as_str(),
+        "Error body lacks the id: {}",
+        body
+    );
+    ensure!(
+        body["violations"].is_array(),
+        "Error body lost its fields: {}",
+        body
+    );
+
+    // Bodiless errors get a JSON body with the id; unusable ids are replaced
+    let response = client
+        .delete(format!("{}/healthz", base_url))
+        .header("x-request-id", "has space")
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 405,
+        "Unexpected status {}",
+        response.status()
+    );
+    let id = response.headers()["x-request-id"].to_str()?.to_string();
+    ensure!(id != "has space", "Invalid incoming id was echoed");
+    let body: serde_json::Value = response.json().await?;
+    ensure!(
+        body["request_id"] == id.as_str(),
+        "Error body lacks the id: {}",
+        body
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn admin_endpoint_changes_log_filter() -> anyhow::Result<()> {
+    // ---
+
+    let applied = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log_filter = {
+        let applied = applied.clone();
+        LogFilter::new("info", move |directive| {
+            tracing_subscriber::EnvFilter::try_new(directive)?;
+            applied.lock().unwrap().push(directive.to_string());
+            Ok(())
+        })
+    };
+    let live = LiveConfig::default();
+    let config = AppConfig {
+        live: live.clone(),
+        log_filter: Some(log_filter.clone()),
+        ..AppConfig::default()
+    };
+    let app = create_app_with_config(create_repository("memory")?, create_metrics()?, config)?;
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let client = Client::new();
+    let url = format!("http://{}/admin/log-level", addr);
+
+    let current: serde_json::Value = client.get(&url).send().await?.json().await?;
+    ensure!(current["filter"] == "info", "Unexpected filter {}", current);
+
+    let directive = "info,argus_events::repository=debug";
+    let response = client
+        .put(&url)
+        .json(&json!({ "filter": directive }))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 200,
+        "Unexpected status {}",
+        response.status()
+    );
+    ensure!(
+        *log_filter.current() == directive,
+        "Filter not applied: {}",
+        log_filter.current()
+    );
+    ensure!(*applied.lock().unwrap() == [directive]);
+
+    // An invalid directive is rejected and the current filter kept
+    let response = client
+        .put(&url)
+        .json(&json!({ "filter": "info,[" }))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 400,
+        "Unexpected status {}",
+        response.status()
+    );
+    ensure!(*log_filter.current() == directive);
+
+    // Admin routes share the API key requirement of event routes
+    live.set_api_keys(["s3cret".to_string()].into_iter().collect());
+    let response = client.get(&url).send().await?;
+    ensure!(
+        response.status() == 401,
+        "Unexpected status {}",
+        response.status()
+    );
+    let response = client.get(&url).bearer_auth("s3cret").send().await?;
+    ensure!(
+        response.status() == 200,
+        "Unexpected status {}",
+        response.status()
+    );
+
+    // Not mounted unless a filter handle is provided
+    let base_url = start_test_server().await?;
+    let response = client
+        .get(format!("{}/admin/log-level", base_url))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 404,
+        "Unexpected status {}",
+        response.status()
+    );
+
+    Ok(())
+}
+
+/// Without an exporter the caller's `traceparent` is stored as-is.
+#[tokio::test]
+async fn events_record_incoming_trace_context() -> anyhow::Result<()> {
+    // ---
+
+    let base_url = start_test_server().await?;
+    let client = Client::new();
+    let event = json!({
+        "event_type": "traced",
+        "timestamp": Utc::now().to_rfc3339(),
+        "payload": {}
+    });
+
+    let response = client
+        .post(format!("{}/v1/events", base_url))
+        .header(
+            "traceparent",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        )
+        .json(&event)
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 201,
+        "Unexpected status {}",
+        response.status()
+    );
+
+    let response = client
+        .post(format!("{}/v1/events", base_url))
+        .json(&event)
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 201,
+        "Unexpected status {}",
+        response.status()
+    );
+
+    let events: Vec<Event> = client
+        .get(format!("{}/v1/events?type=traced", base_url))
+        .send()
+        .await?
+        .json()
+        .await?;
+    ensure!(events.len() == 2, "Expected 2 events, got {}", events.len());
+
+    let traced: Vec<_> = events.iter().filter_map(|e| e.trace.as_ref()).collect();
+    ensure!(
+        traced.len() == 1,
+        "Expected 1 traced event, got {}",
+        traced.len()
+    );
+    ensure!(
+        traced[0].trace_id == "4bf92f3577b34da6a3ce929d0e0e4736",
+        "Unexpected trace_id {}",
+        traced[0].trace_id
+    );
+    ensure!(
+        traced[0].span_id == "00f067aa0ba902b7",
+        "Unexpected span_id {}",
+        traced[0].span_id
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn events_have_unique_ids() -> anyhow::Result<()> {
     // ---
@@ -706,7 +1829,7 @@ async fn test_get_events_empty_results_with_filters() -> Result<()> {
     let response = app.get_events_with_query("type=nonexistent_type").await;
     let events_array = get_events_array!(response);
     ensure!(
-        events_array.len() == 0,
+        events_array.is_empty(),
         "Expected 0 events for nonexistent type, got {}",
         events_array.len()
     );
@@ -719,7 +1842,7 @@ async fn test_get_events_empty_results_with_filters() -> Result<()> {
     let response = app.get_events_with_query(&query).await;
     let events_array = get_events_array!(response);
     ensure!(
-        events_array.len() == 0,
+        events_array.is_empty(),
         "Expected 0 events for future time range, got {}",
         events_array.len()
     );
@@ -820,7 +1943,7 @@ pub struct TestApp {
 impl TestApp {
     async fn post_event(&self, event: serde_json::Value) -> reqwest::Response {
         self.client
-            .post(&format!("{}/events", &self.address))
+            .post(format!("{}/events", &self.address))
             .json(&event)
             .send()
             .await
@@ -829,7 +1952,7 @@ impl TestApp {
 
     async fn get_events_with_query(&self, query: &str) -> reqwest::Response {
         self.client
-            .get(&format!("{}/events?{}", &self.address, query))
+            .get(format!("{}/events?{}", &self.address, query))
             .send()
             .await
             .expect("Failed to execute request.")
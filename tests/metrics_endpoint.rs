@@ -1,5 +1,8 @@
 use anyhow::Result;
-use argus_events::{create_app, create_metrics, create_repository};
+use argus_events::{
+    create_app, create_metrics_for, create_metrics_with, create_repository, spawn_stats_task,
+    Event, MetricsPtr, MetricsSettings, PushSettings, StatsdSettings,
+};
 use reqwest::Client;
 use std::sync::Arc;
 use std::time::Duration;
@@ -12,20 +15,15 @@ struct TestServer {
 
 impl TestServer {
     // ---
-    async fn new() -> Result<Self> {
+    async fn new(metrics_kind: &str) -> Result<Self> {
         // ---
+        // Each server owns its metrics registry, so tests can run in parallel
+        Self::with_metrics(create_metrics_for(metrics_kind)?).await
+    }
 
-        // Enable debug logging only when requested
-        if std::env::var("TEST_DEBUG").is_ok() {
-            std::env::set_var("RUST_LOG", "debug");
-            std::env::set_var("NO_COLOR", "1");
-        }
+    async fn with_metrics(metrics: MetricsPtr) -> Result<Self> {
+        // ---
         let repo = create_repository("memory")?;
-
-        // Create a simple noop metrics implementation for testing
-        std::env::set_var("ARGUS_METRICS_TYPE", "prom");
-        let metrics = create_metrics()?;
-
         let app = create_app(repo, metrics)?;
         let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
         let addr = listener.local_addr()?;
@@ -56,13 +54,10 @@ fn print_matrics(body: &str) {
 }
 
 #[tokio::test]
-#[serial_test::serial]
 async fn metrics_endpoint_with_prometheus() -> Result<()> {
     // ---
-    // Set environment to use Prometheus metrics for this test
-    std::env::set_var("AXUM_METRICS_TYPE", "prom");
 
-    let server = TestServer::new().await?;
+    let server = TestServer::new("prom").await?;
 
     // First, hit some endpoints to generate metrics
     let _ = server.client.get(server.url("/events")).send().await?;
@@ -98,19 +93,14 @@ async fn metrics_endpoint_with_prometheus() -> Result<()> {
         println!("ℹ️  Metrics format: {}", body);
     }
 
-    // Clean up environment variable
-    std::env::remove_var("AXUM_METRICS_TYPE");
     Ok(())
 }
 
 #[tokio::test]
-#[serial_test::serial]
 async fn metrics_endpoint_with_noop() -> Result<()> {
     // ---
-    // Set environment to use noop metrics (or don't set it)
-    std::env::set_var("AXUM_METRICS_TYPE", "noop");
 
-    let server = TestServer::new().await?;
+    let server = TestServer::new("noop").await?;
 
     // Hit some endpoints
     let _ = server.client.get(server.url("/events")).send().await?;
@@ -127,18 +117,14 @@ async fn metrics_endpoint_with_noop() -> Result<()> {
     let body = res.text().await?;
     print_matrics(&body);
 
-    // Clean up environment variable
-    std::env::remove_var("AXUM_METRICS_TYPE");
     Ok(())
 }
 
 #[tokio::test]
-#[serial_test::serial]
 async fn metrics_endpoint_survives_load() -> Result<()> {
     // ---
-    std::env::set_var("AXUM_METRICS_TYPE", "prom");
 
-    let server = Arc::new(TestServer::new().await?);
+    let server = Arc::new(TestServer::new("prom").await?);
 
     // Generate some load
     let futures = (0..20).map(|i| {
@@ -176,17 +162,14 @@ async fn metrics_endpoint_survives_load() -> Result<()> {
     let body = res.text().await?;
     assert!(!body.is_empty());
 
-    std::env::remove_var("AXUM_METRICS_TYPE");
     Ok(())
 }
 
 #[tokio::test]
-#[serial_test::serial]
 async fn metrics_content_type_is_correct() -> Result<()> {
     // ---
-    std::env::set_var("AXUM_METRICS_TYPE", "prom");
 
-    let server = TestServer::new().await?;
+    let server = TestServer::new("prom").await?;
 
     let res = server.client.get(server.url("/metrics")).send().await?;
     assert!(res.status().is_success());
@@ -208,6 +191,402 @@ async fn metrics_content_type_is_correct() -> Result<()> {
         );
     }
 
-    std::env::remove_var("AXUM_METRICS_TYPE");
+    Ok(())
+}
+
+#[tokio::test]
+async fn prometheus_metrics_are_labeled() -> Result<()> {
+    // ---
+    let server = TestServer::new("prom").await?;
+
+    let event = serde_json::json!({
+        "event_type": "labeled_signup",
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "payload": { "user": "a" }
+    });
+    let res = server
+        .client
+        .post(server.url("/v1/events"))
+        .json(&event)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 201);
+    let _ = server.client.get(server.url("/v1/events")).send().await?;
+
+    let body = server
+        .client
+        .get(server.url("/metrics"))
+        .send()
+        .await?
+        .text()
+        .await?;
+    print_matrics(&body);
+
+    let series = |name: &str, labels: &[&str]| {
+        body.lines()
+            .any(|line| line.starts_with(name) && labels.iter().all(|l| line.contains(l)))
+    };
+    assert!(series(
+        "http_requests_total",
+        &["method=\"POST\"", "status=\"2xx\""]
+    ));
+    assert!(series(
+        "http_request_duration_seconds_bucket",
+        &["method=\"GET\"", "status=\"2xx\"", "le="]
+    ));
+    assert!(series(
+        "events_created_total",
+        &["event_type=\"labeled_signup\""]
+    ));
+    assert!(series(
+        "repository_operation_duration_seconds_bucket",
+        &["operation=\"store_event\"", "outcome=\"ok\""]
+    ));
+    assert!(series(
+        "repository_operation_duration_seconds_bucket",
+        &["operation=\"find_events\""]
+    ));
+    assert!(series("repository_query_result_size_bucket", &["le="]));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn middleware_measures_every_route() -> Result<()> {
+    // ---
+    let server = TestServer::new("prom").await?;
+
+    let event = serde_json::json!({
+        "event_type": "measured",
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "payload": {}
+    });
+    let _ = server
+        .client
+        .post(server.url("/v1/events"))
+        .json(&event)
+        .send()
+        .await?;
+    let _ = server.client.get(server.url("/events")).send().await?;
+    let _ = server.client.get(server.url("/healthz")).send().await?;
+    let _ = server.client.get(server.url("/metrics")).send().await?;
+    let _ = server
+        .client
+        .get(server.url("/no/such/route"))
+        .send()
+        .await?;
+
+    let body = server
+        .client
+        .get(server.url("/metrics"))
+        .send()
+        .await?
+        .text()
+        .await?;
+    print_matrics(&body);
+
+    let series = |name: &str, labels: &[&str]| {
+        body.lines()
+            .any(|line| line.starts_with(name) && labels.iter().all(|l| line.contains(l)))
+    };
+    for route in ["/v1/events", "/events", "/healthz", "/metrics"] {
+        let label = format!("route=\"{}\"", route);
+        assert!(
+            series("http_requests_total", &[&label]),
+            "missing http_requests_total for {}",
+            route
+        );
+    }
+    assert!(
+        !body.contains("/no/such/route"),
+        "Unmatched paths must not become labels"
+    );
+    assert!(series("http_requests_in_flight", &["route=\"/metrics\""]));
+    assert!(series(
+        "http_request_size_bytes_bucket",
+        &["route=\"/v1/events\"", "method=\"POST\""]
+    ));
+    assert!(series(
+        "http_response_size_bytes_bucket",
+        &["route=\"/healthz\""]
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn servers_render_only_their_own_metrics() -> Result<()> {
+    // ---
+    let first = TestServer::new("prom").await?;
+    let second = TestServer::new("prom").await?;
+
+    let event = serde_json::json!({
+        "event_type": "first_only",
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "payload": {}
+    });
+    let res = first
+        .client
+        .post(first.url("/v1/events"))
+        .json(&event)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 201);
+
+    let first_body = first
+        .client
+        .get(first.url("/metrics"))
+        .send()
+        .await?
+        .text()
+        .await?;
+    let second_body = second
+        .client
+        .get(second.url("/metrics"))
+        .send()
+        .await?
+        .text()
+        .await?;
+
+    assert!(first_body.contains("event_type=\"first_only\""));
+    assert!(
+        !second_body.contains("first_only"),
+        "Second server saw the first server's events:\n{}",
+        second_body
+    );
+    assert!(
+        !second_body.contains("method=\"POST\""),
+        "Second server saw the first server's requests"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn statsd_backend_pushes_packets() -> Result<()> {
+    // ---
+    let agent = tokio::net::UdpSocket::bind("127.0.0.1:0").await?;
+    let metrics = create_metrics_with(&MetricsSettings {
+        kind: "statsd".to_string(),
+        statsd: StatsdSettings {
+            address: agent.local_addr()?.to_string(),
+            tags: [("service".to_string(), "argus".to_string())].into(),
+            flush_interval_ms: 20,
+            ..StatsdSettings::default()
+        },
+        ..MetricsSettings::default()
+    })?;
+    let server = TestServer::with_metrics(metrics).await?;
+
+    let event = serde_json::json!({
+        "event_type": "signup",
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "payload": {}
+    });
+    let res = server
+        .client
+        .post(server.url("/v1/events"))
+        .json(&event)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 201);
+
+    // /metrics explains where metrics go instead of rendering them
+    let body = server
+        .client
+        .get(server.url("/metrics"))
+        .send()
+        .await?
+        .text()
+        .await?;
+    assert!(body.contains("pushed to StatsD"), "{}", body);
+
+    let mut lines = Vec::new();
+    let mut buf = [0u8; 65_536];
+    let expected = "argus_events.events.created:1|c|#service:argus,event_type:signup";
+    while !lines.iter().any(|line: &String| line == expected) {
+        let len = tokio::time::timeout(Duration::from_secs(2), agent.recv(&mut buf))
+            .await
+            .map_err(|_| anyhow::anyhow!("No packet with {} in {:?}", expected, lines))??;
+        lines.extend(
+            String::from_utf8(buf[..len].to_vec())?
+                .lines()
+                .map(str::to_string),
+        );
+    }
+
+    assert!(lines.iter().any(
+        |line| line.starts_with("argus_events.http.request.duration:")
+            && line.contains("route:/v1/events,method:POST,status:201")
+    ));
+    assert!(lines.iter().any(|line| line
+        .starts_with("argus_events.repository.operation.duration:")
+        && line.ends_with("|ms|#service:argus,operation:store_event,outcome:success")));
+
+    Ok(())
+}
+
+/// Push gateway stand-in: fails the first push, then records bodies.
+#[derive(Clone, Default)]
+struct Gateway {
+    attempts: Arc<std::sync::atomic::AtomicUsize>,
+    bodies: Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+async fn start_gateway() -> Result<(std::net::SocketAddr, Gateway)> {
+    // ---
+    use axum::{extract::State, http::StatusCode, routing::put, Router};
+
+    let gateway = Gateway::default();
+    let app = Router::new()
+        .route(
+            "/metrics/job/argus",
+            put(|State(gateway): State<Gateway>, body: String| async move {
+                let attempt = gateway
+                    .attempts
+                    .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                if attempt == 0 {
+                    return StatusCode::SERVICE_UNAVAILABLE;
+                }
+                gateway.bodies.lock().unwrap().push(body);
+                StatusCode::OK
+            }),
+        )
+        .with_state(gateway.clone());
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    Ok((addr, gateway))
+}
+
+/// Posts one `signup` event.
+async fn post_signup(server: &TestServer) -> Result<()> {
+    // ---
+    let event = serde_json::json!({
+        "event_type": "signup",
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "payload": {}
+    });
+    let res = server
+        .client
+        .post(server.url("/v1/events"))
+        .json(&event)
+        .send()
+        .await?;
+    assert_eq!(res.status(), 201);
+    Ok(())
+}
+
+const PUSHED_SIGNUP: &str = "events_created_total{event_type=\"signup\"} 1";
+
+#[tokio::test(flavor = "multi_thread")]
+async fn push_backend_pushes_periodically() -> Result<()> {
+    // ---
+    let (addr, gateway) = start_gateway().await?;
+    let metrics = create_metrics_with(&MetricsSettings {
+        kind: "push".to_string(),
+        push: PushSettings {
+            url: format!("http://{}/metrics/job/argus", addr),
+            interval_ms: 50,
+            initial_backoff_ms: 10,
+            ..PushSettings::default()
+        },
+        ..MetricsSettings::default()
+    })?;
+    let server = TestServer::with_metrics(metrics).await?;
+    post_signup(&server).await?;
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !gateway
+            .bodies
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|body| body.contains(PUSHED_SIGNUP))
+        {
+            sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("No push contained {}", PUSHED_SIGNUP))?;
+
+    // Scraping keeps working alongside pushing
+    let body = server
+        .client
+        .get(server.url("/metrics"))
+        .send()
+        .await?
+        .text()
+        .await?;
+    assert!(body.contains(PUSHED_SIGNUP), "{}", body);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn push_backend_retries_the_final_push() -> Result<()> {
+    // ---
+    let (addr, gateway) = start_gateway().await?;
+    let metrics = create_metrics_with(&MetricsSettings {
+        kind: "push".to_string(),
+        push: PushSettings {
+            url: format!("http://{}/metrics/job/argus", addr),
+            // Long enough that only the flush pushes
+            interval_ms: 3_600_000,
+            initial_backoff_ms: 10,
+            ..PushSettings::default()
+        },
+        ..MetricsSettings::default()
+    })?;
+    let server = TestServer::with_metrics(metrics.clone()).await?;
+    post_signup(&server).await?;
+
+    metrics.flush().await;
+
+    // The gateway rejected the first attempt; the retry delivered the metrics
+    assert_eq!(
+        gateway.attempts.load(std::sync::atomic::Ordering::SeqCst),
+        2
+    );
+    let bodies = gateway.bodies.lock().unwrap();
+    assert_eq!(bodies.len(), 1);
+    assert!(bodies[0].contains(PUSHED_SIGNUP), "{}", bodies[0]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn repository_stats_are_published_as_gauges() -> Result<()> {
+    // ---
+    let repo = create_repository("memory")?;
+    let metrics = create_metrics_for("prom")?;
+    for event_type in ["signup", "signup", "login"] {
+        repo.store_event(Event {
+            id: uuid::Uuid::new_v4(),
+            event_type: event_type.to_string(),
+            timestamp: "2025-06-16T12:00:00Z".parse()?,
+            payload: serde_json::json!({}),
+            trace: None,
+        })
+        .await?;
+    }
+
+    let task = spawn_stats_task(repo, metrics.clone(), Duration::from_millis(20));
+    sleep(Duration::from_millis(100)).await;
+    task.abort();
+
+    let body = metrics.render()?;
+    print_matrics(&body);
+    for expected in [
+        "repository_events{event_type=\"signup\"} 2",
+        "repository_events{event_type=\"login\"} 1",
+        "repository_event_types 2",
+        "repository_newest_event_timestamp_seconds 1750075200",
+    ] {
+        assert!(body.contains(expected), "Missing {}", expected);
+    }
+    assert!(body.contains("repository_size_bytes"));
+
     Ok(())
 }
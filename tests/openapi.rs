@@ -0,0 +1,191 @@
+//! Checks that the served OpenAPI document matches the live router.
+
+use anyhow::{anyhow, ensure, Result};
+use argus_events::{create_metrics, create_repository, ApiDoc, AppConfig, LogFilter, API_ROUTES};
+use reqwest::{Client, Method, StatusCode};
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use utoipa::OpenApi;
+
+mod common;
+use common::start_server;
+
+const ALL_METHODS: [&str; 5] = ["get", "post", "put", "patch", "delete"];
+
+/// Serves the API with every optional route mounted.
+async fn start_full_server() -> Result<String> {
+    // ---
+    let config = AppConfig {
+        log_filter: Some(LogFilter::new("info", |_| Ok(()))),
+        openapi_ui: true,
+        ..AppConfig::default()
+    };
+    start_server(create_repository("memory")?, create_metrics()?, config).await
+}
+
+async fn fetch_spec(client: &Client, base_url: &str) -> Result<Value> {
+    // ---
+    let response = client
+        .get(format!("{}/openapi.json", base_url))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 200,
+        "Expected 200, got {}",
+        response.status()
+    );
+    Ok(response.json().await?)
+}
+
+/// The spec documents exactly the operations in `API_ROUTES`; each of them
+/// must be routed, and every other method on a listed path must be rejected
+/// with 405, so the spec and router can't drift.
+/// A handler may answer 404 for an unknown path parameter (e.g. `{name}`), but
+/// only the router's fallback 404 has an empty body.
+#[tokio::test]
+async fn served_spec_matches_router() -> Result<()> {
+    // ---
+    let base_url = start_full_server().await?;
+    let client = Client::new();
+    let spec = fetch_spec(&client, &base_url).await?;
+
+    let generated = serde_json::to_value(ApiDoc::openapi())?;
+    ensure!(
+        spec == generated,
+        "Served spec differs from ApiDoc::openapi()"
+    );
+
+    let paths = spec["paths"]
+        .as_object()
+        .ok_or_else(|| anyhow!("spec has no paths"))?;
+    let documented: BTreeSet<(&str, &str)> = paths
+        .iter()
+        .flat_map(|(path, item)| {
+            ALL_METHODS
+                .into_iter()
+                .filter(|method| item.get(method).is_some())
+                .map(move |method| (path.as_str(), method))
+        })
+        .collect();
+    let routed: BTreeSet<(&str, &str)> = API_ROUTES
+        .iter()
+        .flat_map(|(path, methods)| methods.iter().map(move |method| (*path, *method)))
+        .collect();
+    ensure!(
+        documented == routed,
+        "Spec and API_ROUTES differ: undocumented {:?}, not in API_ROUTES {:?}",
+        routed.difference(&documented).collect::<Vec<_>>(),
+        documented.difference(&routed).collect::<Vec<_>>()
+    );
+
+    for (path, methods) in API_ROUTES {
+        for method in ALL_METHODS {
+            let documented = methods.contains(&method);
+            let request = client.request(
+                Method::from_bytes(method.to_uppercase().as_bytes())?,
+                format!("{}{}", base_url, path),
+            );
+            let response = request.json(&json!({})).send().await?;
+            let status = response.status();
+            let unrouted = status == StatusCode::NOT_FOUND && response.bytes().await?.is_empty();
+
+            if documented {
+                ensure!(
+                    !unrouted && status != StatusCode::METHOD_NOT_ALLOWED,
+                    "{} {} is documented but not routed (status {})",
+                    method,
+                    path,
+                    status
+                );
+            } else {
+                ensure!(
+                    status == StatusCode::METHOD_NOT_ALLOWED,
+                    "{} {} is routed but not documented (status {})",
+                    method,
+                    path,
+                    status
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The query parameter for event type is `type`, not `event_type`.
+#[tokio::test]
+async fn get_events_parameters_use_wire_names() -> Result<()> {
+    // ---
+    let base_url = start_full_server().await?;
+    let spec = fetch_spec(&Client::new(), &base_url).await?;
+
+    let names: Vec<&str> = spec["paths"]["/v1/events"]["get"]["parameters"]
+        .as_array()
+        .ok_or_else(|| anyhow!("GET /v1/events has no parameters"))?
+        .iter()
+        .filter_map(|p| p["name"].as_str())
+        .collect();
+    ensure!(
+        names == ["type", "start", "end"],
+        "Unexpected parameters: {:?}",
+        names
+    );
+
+    let required = &spec["components"]["schemas"]["EventInput"]["required"];
+    ensure!(
+        *required == json!(["event_type", "timestamp", "payload"]),
+        "Unexpected EventInput required fields: {}",
+        required
+    );
+
+    Ok(())
+}
+
+/// The Swagger UI is served from the binary rather than a CDN.
+#[tokio::test]
+async fn swagger_ui_assets_are_embedded() -> Result<()> {
+    // ---
+    let base_url = start_full_server().await?;
+    let client = Client::new();
+
+    let page = client
+        .get(format!("{}/docs/", base_url))
+        .send()
+        .await?
+        .text()
+        .await?;
+    ensure!(
+        page.contains("swagger-ui") && !page.contains("unpkg.com"),
+        "Unexpected Swagger UI page: {}",
+        page
+    );
+    for asset in [
+        "swagger-ui.css",
+        "swagger-ui-bundle.js",
+        "swagger-initializer.js",
+    ] {
+        let response = client
+            .get(format!("{}/docs/{}", base_url, asset))
+            .send()
+            .await?;
+        ensure!(
+            response.status() == 200,
+            "Expected 200 for {}, got {}",
+            asset,
+            response.status()
+        );
+    }
+    let initializer = client
+        .get(format!("{}/docs/swagger-initializer.js", base_url))
+        .send()
+        .await?
+        .text()
+        .await?;
+    ensure!(
+        initializer.contains("/openapi.json"),
+        "UI does not load /openapi.json: {}",
+        initializer
+    );
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+//! Rollup tests: buckets maintained as events are stored.
+
+use anyhow::{anyhow, ensure, Result};
+use argus_events::{
+    create_metrics, create_repository, create_rollups, AppConfig, EventFilter, EventRepositoryPtr,
+    RollupDefinition, RollupSettings,
+};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+mod common;
+use common::{post_event, start_server};
+
+fn revenue() -> RollupDefinition {
+    RollupDefinition {
+        name: "revenue".to_string(),
+        events: EventFilter {
+            event_type: "purchase".to_string(),
+            filter: None,
+        },
+        interval: Duration::from_secs(3600),
+        group_by: vec!["country".to_string()],
+        fields: vec!["amount".to_string()],
+        max_age: None,
+    }
+}
+
+async fn start_rollup_server(repo: EventRepositoryPtr) -> Result<String> {
+    // ---
+    let config = AppConfig {
+        rollups: Some(create_rollups(vec![revenue()], &RollupSettings::default())?),
+        ..AppConfig::default()
+    };
+    start_server(repo, create_metrics()?, config).await
+}
+
+fn purchase(timestamp: &str, country: &str, amount: f64) -> Value {
+    json!({
+        "event_type": "purchase",
+        "timestamp": timestamp,
+        "payload": { "country": country, "amount": amount }
+    })
+}
+
+/// Stored events update the buckets, which outlive the raw events.
+#[tokio::test]
+async fn rollup_buckets_are_maintained_and_survive_purges() -> Result<()> {
+    // ---
+    let repo = create_repository("memory")?;
+    let base_url = start_rollup_server(repo.clone()).await?;
+    let client = Client::new();
+    for event in [
+        purchase("2025-06-16T10:05:00Z", "US", 10.0),
+        purchase("2025-06-16T10:40:00Z", "US", 30.0),
+        purchase("2025-06-16T10:50:00Z", "DE", 5.0),
+        purchase("2025-06-16T11:15:00Z", "US", 7.5),
+        json!({ "event_type": "signup", "timestamp": "2025-06-16T10:10:00Z", "payload": {} }),
+    ] {
+        post_event(&client, &base_url, event).await?;
+    }
+    repo.purge_before("2030-01-01T00:00:00Z".parse()?).await?;
+
+    let response = client
+        .get(format!(
+            "{}/v1/rollups/revenue?end=2025-06-16T10:59:59Z",
+            base_url
+        ))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 200,
+        "Expected 200, got {}",
+        response.status()
+    );
+    let report: Value = response.json().await?;
+    let buckets = report["buckets"]
+        .as_array()
+        .ok_or_else(|| anyhow!("No buckets in {}", report))?;
+    ensure!(buckets.len() == 2, "Unexpected buckets: {}", report);
+    ensure!(
+        buckets[1]
+            == json!({
+                "start": "2025-06-16T10:00:00Z",
+                "group": { "country": "US" },
+                "events": 2,
+                "fields": {
+                    "amount": { "count": 2, "sum": 40.0, "min": 10.0, "max": 30.0, "avg": 20.0 }
+                }
+            }),
+        "Unexpected US bucket: {}",
+        buckets[1]
+    );
+    ensure!(
+        report["interval_secs"] == 3600 && buckets[0]["group"]["country"] == "DE",
+        "Unexpected report: {}",
+        report
+    );
+
+    let response = client
+        .get(format!("{}/v1/rollups/unknown", base_url))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 404,
+        "Expected 404, got {}",
+        response.status()
+    );
+    Ok(())
+}
@@ -0,0 +1,127 @@
+//! Schema inference tests: schemas served over HTTP, drift counted and posted.
+
+use anyhow::{ensure, Result};
+use argus_events::{
+    create_metrics_for, create_repository, create_schemas, sign_webhook, AppConfig, SchemaSettings,
+};
+use reqwest::Client;
+use serde_json::{json, Value};
+
+mod common;
+use common::{event, post_event, start_receiver, start_server, wait_for};
+
+/// Submits a `purchase` event.
+async fn post_purchase(client: &Client, base_url: &str, payload: Value) -> Result<()> {
+    post_event(client, base_url, event("purchase", payload)).await
+}
+
+/// An established schema is served, and an event departing from it is
+/// counted and posted to the webhook.
+#[tokio::test]
+async fn schema_is_inferred_and_drift_is_reported() -> Result<()> {
+    // ---
+    let (receiver, received) = start_receiver().await?;
+    let metrics = create_metrics_for("prom")?;
+    let settings = SchemaSettings {
+        min_events: 2,
+        webhook_url: Some(format!("http://{}/drift", receiver)),
+        webhook_secret: Some("s3cret".to_string()),
+        ..SchemaSettings::default()
+    };
+    let config = AppConfig {
+        schemas: Some(create_schemas(&settings, metrics.clone())?),
+        ..AppConfig::default()
+    };
+    let base_url = start_server(create_repository("memory")?, metrics.clone(), config).await?;
+    let client = Client::new();
+
+    post_purchase(&client, &base_url, json!({ "amount": 10, "coupon": null })).await?;
+    post_purchase(&client, &base_url, json!({ "amount": 12.5 })).await?;
+
+    let response = client
+        .get(format!("{}/v1/schemas/purchase", base_url))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 200,
+        "Expected 200, got {}",
+        response.status()
+    );
+    let schema: Value = response.json().await?;
+    ensure!(
+        schema
+            == json!({
+                "event_type": "purchase",
+                "events": 2,
+                "fields": [
+                    { "name": "amount", "types": ["number"], "nullable": false, "required": true, "seen": 2 },
+                    { "name": "coupon", "types": [], "nullable": true, "required": false, "seen": 1 }
+                ]
+            }),
+        "Unexpected schema: {}",
+        schema
+    );
+
+    post_purchase(
+        &client,
+        &base_url,
+        json!({ "amount": "9.99", "currency": "EUR" }),
+    )
+    .await?;
+    wait_for("the drift notification", || {
+        !received.lock().unwrap().is_empty()
+    })
+    .await?;
+    let (signature, body) = received.lock().unwrap()[0].clone();
+    ensure!(
+        signature == sign_webhook("s3cret", body.as_bytes()),
+        "Bad signature {}",
+        signature
+    );
+    let drift: Value = serde_json::from_str(&body)?;
+    ensure!(
+        drift["event_type"] == "purchase"
+            && drift["changes"]
+                == json!([
+                    { "change": "type_changed", "field": "amount", "expected": ["number"], "found": "string" },
+                    { "change": "field_added", "field": "currency", "found": "string" }
+                ]),
+        "Unexpected drift: {}",
+        drift
+    );
+
+    let rendered = metrics.render()?;
+    for line in [
+        "schema_drift_total{event_type=\"purchase\",change=\"type_changed\"} 1",
+        "schema_drift_total{event_type=\"purchase\",change=\"field_added\"} 1",
+    ] {
+        ensure!(
+            rendered.contains(line),
+            "Missing {} in:\n{}",
+            line,
+            rendered
+        );
+    }
+
+    let schemas: Value = client
+        .get(format!("{}/v1/schemas", base_url))
+        .send()
+        .await?
+        .json()
+        .await?;
+    ensure!(
+        schemas.as_array().map(Vec::len) == Some(1),
+        "Unexpected schemas: {}",
+        schemas
+    );
+    let response = client
+        .get(format!("{}/v1/schemas/signup", base_url))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 404,
+        "Expected 404, got {}",
+        response.status()
+    );
+    Ok(())
+}
@@ -0,0 +1,172 @@
+//! OTLP trace export tests against an in-process mock collector.
+
+use anyhow::{anyhow, ensure, Result};
+use argus_events::{
+    create_app, create_metrics_for, create_repository, create_telemetry, Event, TelemetrySettings,
+};
+use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+const PARENT_SPAN_ID: &str = "00f067aa0ba902b7";
+
+/// Export requests received by the mock collector, as OTLP/JSON.
+type Received = Arc<Mutex<Vec<Value>>>;
+
+/// Starts a collector accepting OTLP/HTTP JSON on `/v1/traces`.
+async fn start_collector() -> Result<(SocketAddr, Received)> {
+    // ---
+    let received = Received::default();
+    let app = Router::new()
+        .route(
+            "/v1/traces",
+            post(
+                |State(received): State<Received>, Json(body): Json<Value>| async move {
+                    received.lock().unwrap().push(body);
+                    StatusCode::OK
+                },
+            ),
+        )
+        .with_state(received.clone());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    Ok((addr, received))
+}
+
+/// Every exported span as (name, traceId, spanId, parentSpanId).
+fn exported_spans(received: &Received) -> Vec<(String, String, String, String)> {
+    // ---
+    let field = |span: &Value, key: &str| span[key].as_str().unwrap_or_default().to_string();
+    let requests = received.lock().unwrap();
+    requests
+        .iter()
+        .flat_map(|body| {
+            body["resourceSpans"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default()
+        })
+        .flat_map(|resource| {
+            resource["scopeSpans"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default()
+        })
+        .flat_map(|scope| scope["spans"].as_array().cloned().unwrap_or_default())
+        .map(|span| {
+            (
+                field(&span, "name"),
+                field(&span, "traceId"),
+                field(&span, "spanId"),
+                field(&span, "parentSpanId"),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn handler_and_repository_spans_join_the_callers_trace() -> Result<()> {
+    // ---
+    let (collector, received) = start_collector().await?;
+    let telemetry = create_telemetry(&TelemetrySettings {
+        otlp_endpoint: Some(format!("http://{}", collector)),
+        otlp_protocol: "http/json".to_string(),
+        ..TelemetrySettings::default()
+    })?
+    .ok_or_else(|| anyhow!("Telemetry should be enabled with an endpoint"))?;
+    tracing_subscriber::registry()
+        .with(telemetry.layer())
+        .try_init()?;
+
+    let app = create_app(create_repository("memory")?, create_metrics_for("noop")?)?;
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let client = Client::new();
+    let url = format!("http://{}/v1/events", addr);
+    let response = client
+        .post(&url)
+        .header(
+            "traceparent",
+            format!("00-{}-{}-01", TRACE_ID, PARENT_SPAN_ID),
+        )
+        .json(&json!({
+            "event_type": "traced",
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "payload": {}
+        }))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 201,
+        "Unexpected status {}",
+        response.status()
+    );
+
+    let events: Vec<Event> = client.get(&url).send().await?.json().await?;
+    let trace = events
+        .first()
+        .and_then(|event| event.trace.clone())
+        .ok_or_else(|| anyhow!("Stored event has no trace context"))?;
+    ensure!(
+        trace.trace_id == TRACE_ID,
+        "Unexpected trace_id {}",
+        trace.trace_id
+    );
+    ensure!(
+        trace.span_id != PARENT_SPAN_ID,
+        "Event should reference the server span, not the caller's"
+    );
+
+    telemetry.force_flush().await?;
+    let spans = exported_spans(&received);
+
+    // The request span stored on the event is a child of the caller's span
+    let request_span = spans
+        .iter()
+        .find(|(_, _, span_id, _)| *span_id == trace.span_id)
+        .ok_or_else(|| anyhow!("Request span not exported: {:?}", spans))?;
+    ensure!(
+        request_span.0 == "POST /v1/events",
+        "Unexpected request span name {}",
+        request_span.0
+    );
+    ensure!(
+        request_span.3 == PARENT_SPAN_ID,
+        "Request span parent is {}",
+        request_span.3
+    );
+
+    for name in ["events.submit", "repository.store_event"] {
+        let (_, trace_id, _, _) = spans
+            .iter()
+            .find(|(n, ..)| n == name)
+            .ok_or_else(|| anyhow!("Span {} not exported: {:?}", name, spans))?;
+        ensure!(
+            trace_id == TRACE_ID,
+            "Span {} is in trace {}, expected {}",
+            name,
+            trace_id,
+            TRACE_ID
+        );
+    }
+    for name in ["GET /v1/events", "events.query", "repository.find_events"] {
+        ensure!(
+            spans.iter().any(|(n, ..)| n == name),
+            "Span {} not exported: {:?}",
+            name,
+            spans
+        );
+    }
+
+    telemetry.shutdown().await
+}
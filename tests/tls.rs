@@ -0,0 +1,339 @@
+//! Native TLS and mutual-TLS tests using generated certificates.
+
+use anyhow::{ensure, Result};
+use argus_events::{
+    create_app, create_metrics_for, create_repository, Role, TlsServer, TlsSettings,
+};
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa,
+    KeyPair,
+};
+use reqwest::{Certificate as RootCert, Client, Identity};
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use tokio::net::TcpListener;
+
+/// A throwaway certificate authority.
+struct TestCa {
+    cert: Certificate,
+    key: KeyPair,
+}
+
+impl TestCa {
+    fn new(name: &str) -> Result<Self> {
+        // ---
+        let key = KeyPair::generate()?;
+        let mut params = CertificateParams::new(Vec::<String>::new())?;
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params.distinguished_name.push(DnType::CommonName, name);
+        let cert = params.self_signed(&key)?;
+        Ok(Self { cert, key })
+    }
+
+    /// Issues a server certificate for `localhost`; returns (cert PEM, key PEM).
+    fn server_cert(&self) -> Result<(String, String)> {
+        // ---
+        let key = KeyPair::generate()?;
+        let params = CertificateParams::new(vec!["localhost".to_string()])?;
+        let cert = params.signed_by(&key, &self.cert, &self.key)?;
+        Ok((cert.pem(), key.serialize_pem()))
+    }
+
+    /// Issues a client certificate and returns it as a reqwest identity.
+    fn client_identity(&self, common_name: &str) -> Result<Identity> {
+        // ---
+        let key = KeyPair::generate()?;
+        let mut params = CertificateParams::new(Vec::<String>::new())?;
+        params
+            .distinguished_name
+            .push(DnType::CommonName, common_name);
+        params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ClientAuth];
+        let cert = params.signed_by(&key, &self.cert, &self.key)?;
+        let pem = format!("{}{}", cert.pem(), key.serialize_pem());
+        Ok(Identity::from_pem(pem.as_bytes())?)
+    }
+
+    fn root(&self) -> Result<RootCert> {
+        // ---
+        Ok(RootCert::from_pem(self.cert.pem().as_bytes())?)
+    }
+}
+
+fn temp_dir() -> Result<PathBuf> {
+    // ---
+    let dir = std::env::temp_dir().join(format!("argus-tls-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn write_server_files(dir: &Path, ca: &TestCa) -> Result<TlsSettings> {
+    // ---
+    let (cert, key) = ca.server_cert()?;
+    let cert_path = dir.join("server.pem");
+    let key_path = dir.join("server.key");
+    std::fs::write(&cert_path, cert)?;
+    std::fs::write(&key_path, key)?;
+    Ok(TlsSettings {
+        cert_path: Some(cert_path),
+        key_path: Some(key_path),
+        ..TlsSettings::default()
+    })
+}
+
+async fn start_tls_server(tls: TlsServer) -> Result<SocketAddr> {
+    // ---
+    let app = create_app(create_repository("memory")?, create_metrics_for("noop")?)?;
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(tls.serve(listener, app, std::future::pending()));
+    Ok(addr)
+}
+
+fn client(addr: SocketAddr, root: RootCert, identity: Option<Identity>) -> Result<Client> {
+    // ---
+    let mut builder = Client::builder()
+        .use_rustls_tls()
+        .tls_built_in_root_certs(false)
+        .add_root_certificate(root)
+        .resolve("localhost", addr);
+    if let Some(identity) = identity {
+        builder = builder.identity(identity);
+    }
+    Ok(builder.build()?)
+}
+
+#[tokio::test]
+async fn serves_https_with_configured_certificate() -> Result<()> {
+    // ---
+    let dir = temp_dir()?;
+    let ca = TestCa::new("Argus Test CA")?;
+    let settings = write_server_files(&dir, &ca)?;
+    let addr = start_tls_server(TlsServer::new(&settings)?).await?;
+
+    let client = client(addr, ca.root()?, None)?;
+    let response = client
+        .get(format!("https://localhost:{}/healthz", addr.port()))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 200,
+        "Unexpected status {}",
+        response.status()
+    );
+
+    let plain = reqwest::get(format!("http://127.0.0.1:{}/healthz", addr.port())).await;
+    ensure!(
+        plain.is_err(),
+        "Plain HTTP should not be served on the TLS port"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn mutual_tls_maps_subjects_to_roles() -> Result<()> {
+    // ---
+    let dir = temp_dir()?;
+    let server_ca = TestCa::new("Argus Server CA")?;
+    let client_ca = TestCa::new("Argus Client CA")?;
+    let client_ca_path = dir.join("clients-ca.pem");
+    std::fs::write(&client_ca_path, client_ca.cert.pem())?;
+
+    let mut settings = write_server_files(&dir, &server_ca)?;
+    settings.client_ca_path = Some(client_ca_path);
+    settings.require_client_cert = true;
+    settings
+        .identities
+        .insert("collector-1".to_string(), BTreeSet::from([Role::Ingest]));
+    let addr = start_tls_server(TlsServer::new(&settings)?).await?;
+    let url = format!("https://localhost:{}/v1/events", addr.port());
+
+    // No client certificate: handshake is refused
+    let anonymous = client(addr, server_ca.root()?, None)?;
+    ensure!(
+        anonymous.get(&url).send().await.is_err(),
+        "Expected handshake failure without a client certificate"
+    );
+
+    // Mapped subject may ingest but not read
+    let collector = client(
+        addr,
+        server_ca.root()?,
+        Some(client_ca.client_identity("collector-1")?),
+    )?;
+    let event = json!({
+        "event_type": "signup",
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "payload": { "user": "a" }
+    });
+    let response = collector.post(&url).json(&event).send().await?;
+    ensure!(
+        response.status() == 201,
+        "Expected 201 for ingest, got {}",
+        response.status()
+    );
+    let response = collector.get(&url).send().await?;
+    ensure!(
+        response.status() == 403,
+        "Expected 403 for read, got {}",
+        response.status()
+    );
+    let subscription = json!({ "event_type": "signup", "url": "https://hooks.example.com/argus" });
+    let response = collector
+        .post(format!(
+            "https://localhost:{}/v1/subscriptions",
+            addr.port()
+        ))
+        .json(&subscription)
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 201,
+        "Expected 201 for a subscription with the ingest role, got {}",
+        response.status()
+    );
+
+    // Unmapped subject has no roles
+    let stranger = client(
+        addr,
+        server_ca.root()?,
+        Some(client_ca.client_identity("stranger")?),
+    )?;
+    let response = stranger.post(&url).json(&event).send().await?;
+    ensure!(
+        response.status() == 403,
+        "Expected 403 for unmapped subject, got {}",
+        response.status()
+    );
+
+    // Certificate signed by an untrusted CA is refused
+    let rogue_ca = TestCa::new("Rogue CA")?;
+    let rogue = client(
+        addr,
+        server_ca.root()?,
+        Some(rogue_ca.client_identity("collector-1")?),
+    )?;
+    ensure!(
+        rogue.get(&url).send().await.is_err(),
+        "Expected handshake failure for an untrusted client certificate"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn optional_client_certificates_are_denied_when_identities_are_configured() -> Result<()> {
+    // ---
+    let dir = temp_dir()?;
+    let server_ca = TestCa::new("Argus Server CA")?;
+    let client_ca = TestCa::new("Argus Client CA")?;
+    let client_ca_path = dir.join("clients-ca.pem");
+    std::fs::write(&client_ca_path, client_ca.cert.pem())?;
+
+    let mut settings = write_server_files(&dir, &server_ca)?;
+    settings.client_ca_path = Some(client_ca_path);
+    settings
+        .identities
+        .insert("dashboard".to_string(), BTreeSet::from([Role::Read]));
+    let addr = start_tls_server(TlsServer::new(&settings)?).await?;
+    let url = format!("https://localhost:{}/v1/events", addr.port());
+
+    // The handshake succeeds without a certificate, but role-guarded routes refuse it
+    let anonymous = client(addr, server_ca.root()?, None)?;
+    for response in [
+        anonymous.get(&url).send().await?,
+        anonymous
+            .get(format!("https://localhost:{}/v1/stats", addr.port()))
+            .send()
+            .await?,
+    ] {
+        ensure!(
+            response.status() == 403,
+            "Expected 403 without a client certificate, got {}",
+            response.status()
+        );
+    }
+
+    // Operational endpoints stay open
+    let response = anonymous
+        .get(format!("https://localhost:{}/healthz", addr.port()))
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 200,
+        "Expected 200 for /healthz, got {}",
+        response.status()
+    );
+
+    // A mapped certificate is admitted
+    let dashboard = client(
+        addr,
+        server_ca.root()?,
+        Some(client_ca.client_identity("dashboard")?),
+    )?;
+    let response = dashboard.get(&url).send().await?;
+    ensure!(
+        response.status() == 200,
+        "Expected 200 for read, got {}",
+        response.status()
+    );
+
+    // Reading does not extend to creating webhook subscriptions
+    let subscription = json!({ "event_type": "signup", "url": "https://hooks.example.com/argus" });
+    let response = dashboard
+        .post(format!(
+            "https://localhost:{}/v1/subscriptions",
+            addr.port()
+        ))
+        .json(&subscription)
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 403,
+        "Expected 403 for a subscription without a write role, got {}",
+        response.status()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn certificates_reload_without_restart() -> Result<()> {
+    // ---
+    let dir = temp_dir()?;
+    let old_ca = TestCa::new("Old CA")?;
+    let new_ca = TestCa::new("New CA")?;
+    let settings = write_server_files(&dir, &old_ca)?;
+    let tls = TlsServer::new(&settings)?;
+    let addr = start_tls_server(tls.clone()).await?;
+    let url = format!("https://localhost:{}/healthz", addr.port());
+
+    let trusts_new = client(addr, new_ca.root()?, None)?;
+    ensure!(
+        trusts_new.get(&url).send().await.is_err(),
+        "Client trusting only the new CA should reject the old certificate"
+    );
+
+    // A broken file is rejected and the old certificate stays in service
+    std::fs::write(settings.cert_path.as_ref().unwrap(), "not a certificate")?;
+    ensure!(
+        tls.reload().is_err(),
+        "Reload of an invalid certificate should fail"
+    );
+    let trusts_old = client(addr, old_ca.root()?, None)?;
+    ensure!(trusts_old.get(&url).send().await?.status() == 200);
+
+    write_server_files(&dir, &new_ca)?;
+    tls.reload()?;
+
+    let response = trusts_new.get(&url).send().await?;
+    ensure!(
+        response.status() == 200,
+        "Unexpected status {}",
+        response.status()
+    );
+
+    Ok(())
+}
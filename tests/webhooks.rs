@@ -0,0 +1,239 @@
+//! Webhook delivery tests against an in-process receiver.
+
+use anyhow::{anyhow, ensure, Result};
+use argus_events::{
+    create_metrics_for, create_repository, sign_webhook, AppConfig, MetricsPtr, WebhookSettings,
+    Webhooks,
+};
+use axum::http::StatusCode;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+mod common;
+use common::{event, post_event, start_receiver_with, start_server, wait_for};
+
+/// Fails the first delivery on `/flaky` and every delivery on `/gone`.
+fn flaky(path: &str, received: usize) -> StatusCode {
+    match path {
+        "gone" => StatusCode::GONE,
+        "flaky" if received == 1 => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::OK,
+    }
+}
+
+async fn start_webhook_server(metrics: MetricsPtr) -> Result<String> {
+    // ---
+    let settings = WebhookSettings {
+        max_attempts: 3,
+        initial_backoff_ms: 20,
+        max_backoff_ms: 100,
+        // The receivers listen on loopback
+        allow_private_targets: true,
+        ..WebhookSettings::default()
+    };
+    let config = AppConfig {
+        webhooks: Some(Webhooks::new(&settings, metrics.clone())?),
+        ..AppConfig::default()
+    };
+    start_server(create_repository("memory")?, metrics, config).await
+}
+
+async fn subscribe(client: &Client, base_url: &str, body: Value) -> Result<Value> {
+    // ---
+    let response = client
+        .post(format!("{}/v1/subscriptions", base_url))
+        .json(&body)
+        .send()
+        .await?;
+    ensure!(
+        response.status() == 201,
+        "Expected 201, got {}",
+        response.status()
+    );
+    Ok(response.json().await?)
+}
+
+#[tokio::test]
+async fn matching_events_are_signed_and_retried() -> Result<()> {
+    // ---
+    let (receiver, received) = start_receiver_with(flaky).await?;
+    let metrics = create_metrics_for("prom")?;
+    let base_url = start_webhook_server(metrics.clone()).await?;
+    let client = Client::new();
+
+    let subscription = subscribe(
+        &client,
+        &base_url,
+        json!({
+            "event_type": "purchase",
+            "url": format!("http://{}/flaky", receiver),
+            "filter": { "currency": "EUR" }
+        }),
+    )
+    .await?;
+    let secret = subscription["secret"]
+        .as_str()
+        .ok_or_else(|| anyhow!("No secret in {}", subscription))?
+        .to_string();
+
+    post_event(
+        &client,
+        &base_url,
+        event("purchase", json!({ "currency": "USD" })),
+    )
+    .await?;
+    post_event(
+        &client,
+        &base_url,
+        event("signup", json!({ "currency": "EUR" })),
+    )
+    .await?;
+    post_event(
+        &client,
+        &base_url,
+        event("purchase", json!({ "currency": "EUR" })),
+    )
+    .await?;
+
+    wait_for("a retried delivery", || received.lock().unwrap().len() >= 2).await?;
+    // Give a wrongly matched event the chance to show up
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let received = received.lock().unwrap().clone();
+    ensure!(received.len() == 2, "Unexpected deliveries: {:?}", received);
+    let (signature, body) = &received[1];
+    ensure!(
+        *signature == sign_webhook(&secret, body.as_bytes()),
+        "Bad signature {}",
+        signature
+    );
+    let body: Value = serde_json::from_str(body)?;
+    ensure!(body["attempt"] == 2, "Unexpected attempt in {}", body);
+    ensure!(
+        body["event"]["payload"]["currency"] == "EUR",
+        "Unexpected event in {}",
+        body
+    );
+    ensure!(
+        received[0].1 != received[1].1,
+        "Retries should carry their attempt number"
+    );
+
+    let rendered = metrics.render()?;
+    for expected in [
+        "webhook_deliveries_total{outcome=\"retry\"} 1",
+        "webhook_deliveries_total{outcome=\"delivered\"} 1",
+    ] {
+        ensure!(rendered.contains(expected), "Missing {}", expected);
+    }
+
+    // Listing never reveals the secret
+    let listed: Value = client
+        .get(format!("{}/v1/subscriptions", base_url))
+        .send()
+        .await?
+        .json()
+        .await?;
+    ensure!(
+        listed[0]["id"] == subscription["id"] && listed[0].get("secret").is_none(),
+        "Unexpected listing {}",
+        listed
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn failing_deliveries_become_dead_letters() -> Result<()> {
+    // ---
+    let (receiver, _) = start_receiver_with(flaky).await?;
+    let base_url = start_webhook_server(create_metrics_for("noop")?).await?;
+    let client = Client::new();
+
+    let subscription = subscribe(
+        &client,
+        &base_url,
+        json!({ "event_type": "signup", "url": format!("http://{}/gone", receiver) }),
+    )
+    .await?;
+    post_event(&client, &base_url, event("signup", json!({}))).await?;
+
+    let dead_letters_url = format!("{}/v1/dead-letters", base_url);
+    let mut dead_letters = Value::Null;
+    for _ in 0..250 {
+        dead_letters = client.get(&dead_letters_url).send().await?.json().await?;
+        if dead_letters.as_array().is_some_and(|d| !d.is_empty()) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let dead = &dead_letters[0];
+    ensure!(
+        dead["subscription_id"] == subscription["id"] && dead["attempts"] == 3,
+        "Unexpected dead letters {}",
+        dead_letters
+    );
+    ensure!(
+        dead["last_error"]
+            .as_str()
+            .is_some_and(|e| e.contains("410")),
+        "Unexpected error in {}",
+        dead
+    );
+
+    let subscription_url = format!(
+        "{}/v1/subscriptions/{}",
+        base_url,
+        subscription["id"].as_str().unwrap_or_default()
+    );
+    let deleted = client.delete(&subscription_url).send().await?;
+    ensure!(
+        deleted.status() == 204,
+        "Expected 204, got {}",
+        deleted.status()
+    );
+    let again = client.delete(&subscription_url).send().await?;
+    ensure!(
+        again.status() == 404,
+        "Expected 404, got {}",
+        again.status()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn invalid_subscriptions_are_rejected() -> Result<()> {
+    // ---
+    // Default settings, which keep deliveries off private addresses
+    let metrics = create_metrics_for("noop")?;
+    let config = AppConfig {
+        webhooks: Some(Webhooks::new(&WebhookSettings::default(), metrics.clone())?),
+        ..AppConfig::default()
+    };
+    let base_url = start_server(create_repository("memory")?, metrics, config).await?;
+    let client = Client::new();
+
+    for body in [
+        json!({ "event_type": "signup", "url": "http://127.0.0.1:9/hook" }),
+        json!({ "event_type": "signup", "url": "http://169.254.169.254/latest/meta-data" }),
+        json!({ "event_type": "signup", "url": "ftp://example.com/hook" }),
+        json!({ "event_type": "", "url": "http://example.com/hook" }),
+        json!({ "event_type": "signup", "url": "http://example.com/hook", "filter": [1] }),
+    ] {
+        let response = client
+            .post(format!("{}/v1/subscriptions", base_url))
+            .json(&body)
+            .send()
+            .await?;
+        ensure!(
+            response.status() == 422,
+            "Expected 422 for {}, got {}",
+            body,
+            response.status()
+        );
+    }
+
+    Ok(())
+}